@@ -0,0 +1,301 @@
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::{
+	parse_macro_input, punctuated::Punctuated, token::Comma, Data, DeriveInput, Field, Fields, Lit,
+	Meta, NestedMeta,
+};
+
+/**
+ * Decode a Starknet event, field by field and in order, from the data of the event.
+ * The event name, used to compute the selector, defaults to the name of the struct:
+ *
+ * ```ignore
+ * #[derive(StarknetEvent)]
+ * #[starknet_event(name = "GithubIdentifierRegistered")]
+ * pub struct GithubIdentifierRegisteredEvent { ... }
+ * ```
+ *
+ * Every field type must be supported by the `StarknetTopics` trait, and the type must convert
+ * into a domain `Event`. Unless it is generic, the event is registered in the dispatcher of the
+ * indexer, so that it is decoded as soon as its selector is received.
+ * The generated code relies on `crate::infrastructure::starknet_events`, so that the macro is
+ * only meant for the indexer crate.
+ */
+#[proc_macro_derive(StarknetEvent, attributes(starknet_event))]
+pub fn derive_starknet_event(input: TokenStream) -> TokenStream {
+	let input = parse_macro_input!(input as DeriveInput);
+	expand(input).unwrap_or_else(|error| error.to_compile_error()).into()
+}
+
+fn expand(input: DeriveInput) -> syn::Result<TokenStream2> {
+	let ident = &input.ident;
+	let name = event_name(&input)?.unwrap_or_else(|| ident.to_string());
+	let fields = named_fields(&input, "StarknetEvent")?;
+
+	let decoded_fields = fields.iter().map(|field| {
+		let field_ident = field.ident.as_ref().expect("fields are named");
+		let field_type = &field.ty;
+		let field_name = field_ident.to_string();
+		quote! {
+			#field_ident: <::std::collections::VecDeque<support::TopicValue> as support::StarknetTopics<#field_type>>::pop_front_as(&mut data)
+				.map_err(|details: support::TopicError| support::FromEventError::InvalidField {
+					event: #name,
+					field: #field_name,
+					details,
+				})?
+		}
+	});
+
+	let (impl_generics, type_generics, where_clause) = input.generics.split_for_impl();
+
+	// Only concrete types can be registered
+	let registration = input.generics.params.is_empty().then(|| {
+		quote! {
+			support::inventory::submit! {
+				support::Dispatcher(<#ident as support::StarknetEvent>::dispatch)
+			}
+		}
+	});
+
+	Ok(quote! {
+		const _: () = {
+			use crate::infrastructure::starknet_events as support;
+
+			#registration
+
+			impl #impl_generics support::StarknetEvent for #ident #type_generics #where_clause {
+				const NAME: &'static str = #name;
+
+				fn decode(
+					mut data: ::std::collections::VecDeque<support::TopicValue>,
+				) -> ::std::result::Result<Self, support::FromEventError> {
+					Ok(Self {
+						#(#decoded_fields,)*
+					})
+				}
+			}
+
+			impl #impl_generics ::std::convert::TryFrom<::std::collections::VecDeque<support::TopicValue>> for #ident #type_generics #where_clause {
+				type Error = support::FromEventError;
+
+				fn try_from(
+					data: ::std::collections::VecDeque<support::TopicValue>,
+				) -> ::std::result::Result<Self, Self::Error> {
+					<Self as support::StarknetEvent>::decode(data)
+				}
+			}
+		};
+	})
+}
+
+/**
+ * Decode a struct nested in a Starknet event, field by field and in order.
+ * Decoding errors are forwarded as is, the event reports which of its fields failed.
+ */
+#[proc_macro_derive(StarknetStruct)]
+pub fn derive_starknet_struct(input: TokenStream) -> TokenStream {
+	let input = parse_macro_input!(input as DeriveInput);
+	expand_struct(input).unwrap_or_else(|error| error.to_compile_error()).into()
+}
+
+fn expand_struct(input: DeriveInput) -> syn::Result<TokenStream2> {
+	let ident = &input.ident;
+	let fields = named_fields(&input, "StarknetStruct")?;
+
+	let decoded_fields = fields.iter().map(|field| {
+		let field_ident = field.ident.as_ref().expect("fields are named");
+		let field_type = &field.ty;
+		quote! {
+			#field_ident: <Self as support::StarknetTopics<#field_type>>::pop_front_as(self)?
+		}
+	});
+
+	let (impl_generics, type_generics, where_clause) = input.generics.split_for_impl();
+
+	Ok(quote! {
+		const _: () = {
+			use crate::infrastructure::starknet_events as support;
+
+			impl #impl_generics support::StarknetTopics<#ident #type_generics> for ::std::collections::VecDeque<support::TopicValue> #where_clause {
+				fn pop_front_as(
+					&mut self,
+				) -> ::std::result::Result<#ident #type_generics, support::TopicError> {
+					Ok(#ident {
+						#(#decoded_fields,)*
+					})
+				}
+			}
+		};
+	})
+}
+
+fn named_fields<'a>(
+	input: &'a DeriveInput,
+	derive: &str,
+) -> syn::Result<&'a Punctuated<Field, Comma>> {
+	match &input.data {
+		Data::Struct(data) => match &data.fields {
+			Fields::Named(fields) => Ok(&fields.named),
+			_ => Err(syn::Error::new_spanned(
+				&input.ident,
+				format!(
+					"{} can only be derived for structs with named fields",
+					derive
+				),
+			)),
+		},
+		_ => Err(syn::Error::new_spanned(
+			&input.ident,
+			format!("{} can only be derived for structs", derive),
+		)),
+	}
+}
+
+/**
+ * Read the name given with `#[starknet_event(name = "...")]`, if any
+ */
+fn event_name(input: &DeriveInput) -> syn::Result<Option<String>> {
+	let mut name = None;
+
+	for attribute in
+		input.attrs.iter().filter(|attribute| attribute.path.is_ident("starknet_event"))
+	{
+		let list = match attribute.parse_meta()? {
+			Meta::List(list) => list,
+			meta =>
+				return Err(syn::Error::new_spanned(
+					meta,
+					"expected #[starknet_event(name = \"...\")]",
+				)),
+		};
+
+		for nested in list.nested {
+			match nested {
+				NestedMeta::Meta(Meta::NameValue(value)) if value.path.is_ident("name") =>
+					match value.lit {
+						Lit::Str(lit) => name = Some(lit.value()),
+						lit => return Err(syn::Error::new_spanned(lit, "expected a string")),
+					},
+				nested =>
+					return Err(syn::Error::new_spanned(
+						nested,
+						"unknown starknet_event attribute, expected `name`",
+					)),
+			}
+		}
+	}
+
+	Ok(name)
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use syn::parse_quote;
+
+	fn error(input: DeriveInput) -> String {
+		expand(input).unwrap_err().to_string()
+	}
+
+	#[test]
+	fn name_defaults_to_the_struct_name() {
+		let input: DeriveInput = parse_quote! {
+			struct Transfer { amount: u128 }
+		};
+
+		assert_eq!(None, event_name(&input).unwrap());
+		assert!(expand(input).unwrap().to_string().contains("\"Transfer\""));
+	}
+
+	#[test]
+	fn name_can_be_given_as_attribute() {
+		let input: DeriveInput = parse_quote! {
+			#[starknet_event(name = "Transferred")]
+			struct TransferEvent { amount: u128 }
+		};
+
+		assert_eq!(
+			Some(String::from("Transferred")),
+			event_name(&input).unwrap()
+		);
+	}
+
+	#[test]
+	fn decoding_errors_name_the_field() {
+		let input: DeriveInput = parse_quote! {
+			struct Transfer { amount: u128 }
+		};
+
+		let expanded = expand(input).unwrap().to_string();
+		assert!(expanded.contains("field : \"amount\""), "{}", expanded);
+	}
+
+	#[test]
+	fn concrete_events_are_registered() {
+		let input: DeriveInput = parse_quote! {
+			struct Transfer { amount: u128 }
+		};
+		assert!(expand(input).unwrap().to_string().contains("inventory :: submit !"));
+
+		let input: DeriveInput = parse_quote! {
+			struct Transfer<T> { amount: T }
+		};
+		assert!(!expand(input).unwrap().to_string().contains("inventory :: submit !"));
+	}
+
+	#[test]
+	fn only_structs_with_named_fields_are_supported() {
+		assert_eq!(
+			"StarknetEvent can only be derived for structs",
+			error(parse_quote! { enum Transfer { A } })
+		);
+		assert_eq!(
+			"StarknetEvent can only be derived for structs with named fields",
+			error(parse_quote! { struct Transfer(u128); })
+		);
+	}
+
+	#[test]
+	fn nested_structs_are_decoded_field_by_field() {
+		let input: DeriveInput = parse_quote! {
+			struct Point { x: u64, y: u64 }
+		};
+
+		let expanded = expand_struct(input).unwrap().to_string();
+		assert!(
+			expanded.contains("StarknetTopics < Point >"),
+			"{}",
+			expanded
+		);
+		assert!(expanded.contains("x :"), "{}", expanded);
+		assert!(expanded.contains("y :"), "{}", expanded);
+	}
+
+	#[test]
+	fn nested_structs_must_have_named_fields() {
+		let error = expand_struct(parse_quote! { struct Point(u64, u64); }).unwrap_err();
+		assert_eq!(
+			"StarknetStruct can only be derived for structs with named fields",
+			error.to_string()
+		);
+	}
+
+	#[test]
+	fn unknown_attributes_are_rejected() {
+		assert_eq!(
+			"unknown starknet_event attribute, expected `name`",
+			error(parse_quote! {
+				#[starknet_event(selector = "0x1")]
+				struct Transfer { amount: u128 }
+			})
+		);
+		assert_eq!(
+			"expected a string",
+			error(parse_quote! {
+				#[starknet_event(name = 1)]
+				struct Transfer { amount: u128 }
+			})
+		);
+	}
+}
@@ -0,0 +1,33 @@
+-- Every registration and unregistration of a GitHub identifier, one row per event.
+-- Indexers watching the same contract keep their own rows, rolled back per indexer on chain reorg
+CREATE TABLE github_identifiers (
+	indexer_id TEXT NOT NULL,
+	profile_contract TEXT NOT NULL,
+	contributor_id TEXT NOT NULL,
+	block_number BIGINT NOT NULL,
+	block_hash TEXT NOT NULL,
+	log_index BIGINT NOT NULL,
+	identifier NUMERIC(39, 0) NOT NULL,
+	registered BOOLEAN NOT NULL,
+	PRIMARY KEY (indexer_id, profile_contract, contributor_id, block_number, log_index)
+);
+
+CREATE INDEX github_identifiers_indexer_id_block_number_idx ON github_identifiers (indexer_id, block_number);
+
+-- The GitHub identifier currently linked to each contributor
+CREATE VIEW current_github_identifiers AS
+SELECT indexer_id, profile_contract, contributor_id, identifier, block_number
+FROM (
+	SELECT DISTINCT ON (indexer_id, profile_contract, contributor_id) *
+	FROM github_identifiers
+	ORDER BY indexer_id, profile_contract, contributor_id, block_number DESC, log_index DESC
+) AS latest
+WHERE registered;
+
+-- The last block each indexer projected events from
+CREATE TABLE indexer_checkpoints (
+	indexer_id TEXT PRIMARY KEY,
+	block_number BIGINT NOT NULL,
+	block_hash TEXT NOT NULL,
+	updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
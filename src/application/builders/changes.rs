@@ -0,0 +1,121 @@
+use crate::domain::*;
+
+/**
+ * Differences between the definition of an existing indexer and the requested one
+ */
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Changes {
+	pub network: Option<(Network, Network)>,
+	pub index_from_block: Option<(u64, u64)>,
+	pub added_filters: Vec<EventFilter>,
+	pub removed_filters: Vec<EventFilter>,
+}
+
+impl Changes {
+	/**
+	 * Compare two indexer definitions, filters are compared as sets.
+	 * The start block is only compared when `compare_start_block` is set.
+	 */
+	pub fn between(existing: &Indexer, requested: &Indexer, compare_start_block: bool) -> Self {
+		let missing_from = |filters: &[EventFilter], other: &[EventFilter]| -> Vec<EventFilter> {
+			other.iter().filter(|filter| !filters.contains(filter)).cloned().collect()
+		};
+
+		Self {
+			network: (existing.network != requested.network)
+				.then(|| (existing.network.clone(), requested.network.clone())),
+			index_from_block: (compare_start_block
+				&& existing.index_from_block != requested.index_from_block)
+				.then_some((existing.index_from_block, requested.index_from_block)),
+			added_filters: missing_from(&existing.filters, &requested.filters),
+			removed_filters: missing_from(&requested.filters, &existing.filters),
+		}
+	}
+
+	pub fn is_empty(&self) -> bool {
+		self == &Self::default()
+	}
+}
+
+pub fn format_filters(filters: &[EventFilter]) -> String {
+	filters
+		.iter()
+		.map(|filter| format!("{}:{}", filter.contract_address, filter.event_name))
+		.collect::<Vec<_>>()
+		.join(", ")
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use rstest::*;
+	use std::str::FromStr;
+
+	fn filter(address: &str, event_name: &str) -> EventFilter {
+		EventFilter::new(ContractAddress::from_str(address).unwrap(), event_name)
+	}
+
+	#[fixture]
+	fn indexer() -> Indexer {
+		Indexer::new(
+			"ID".into(),
+			Network::Starknet(StarknetChain::Goerli),
+			42,
+			vec![filter("0x1234", "Event1"), filter("0x1234", "Event2")],
+		)
+	}
+
+	#[rstest]
+	fn same_definitions_have_no_changes(indexer: Indexer) {
+		assert!(Changes::between(&indexer, &indexer, true).is_empty());
+	}
+
+	#[rstest]
+	fn filters_are_compared_as_sets(indexer: Indexer) {
+		let mut requested = indexer.clone();
+		requested.filters = vec![
+			filter("0x001234", "Event2"),
+			filter("0x1234", "Event1"),
+			filter("0x1234", "Event1"),
+		];
+
+		assert!(Changes::between(&indexer, &requested, true).is_empty());
+	}
+
+	#[rstest]
+	fn progress_is_not_a_change(indexer: Indexer) {
+		let existing = indexer.clone().indexed_to_block(100);
+		assert!(Changes::between(&existing, &indexer, true).is_empty());
+	}
+
+	#[rstest]
+	fn all_changes_are_reported(indexer: Indexer) {
+		let requested = Indexer::new(
+			"ID".into(),
+			Network::Starknet(StarknetChain::Mainnet),
+			43,
+			vec![filter("0x1234", "Event1"), filter("0x5678", "Event3")],
+		);
+
+		assert_eq!(
+			Changes {
+				network: Some((
+					Network::Starknet(StarknetChain::Goerli),
+					Network::Starknet(StarknetChain::Mainnet)
+				)),
+				index_from_block: Some((42, 43)),
+				added_filters: vec![filter("0x5678", "Event3")],
+				removed_filters: vec![filter("0x1234", "Event2")],
+			},
+			Changes::between(&indexer, &requested, true)
+		);
+	}
+
+	#[rstest]
+	fn start_block_can_be_ignored(mut indexer: Indexer) {
+		let existing = indexer.clone();
+		indexer.index_from_block = 1000;
+
+		assert!(Changes::between(&existing, &indexer, false).is_empty());
+	}
+}
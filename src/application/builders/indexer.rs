@@ -1,3 +1,4 @@
+use super::changes::{format_filters, Changes};
 use crate::domain::*;
 use std::sync::Arc;
 
@@ -12,12 +13,15 @@ pub struct Builder {
 	filters: Vec<EventFilter>,
 	start_at_block: u64,
 	on_conflict: OnConflictAction,
+	checkpoint_store: Option<Arc<dyn CheckpointStore>>,
 }
 
 #[derive(Clone, Debug, PartialEq, Eq)]
 enum OnConflictAction {
 	DoNothing,
 	Recreate,
+	RecreateIfChanged,
+	Fail,
 }
 
 impl Builder {
@@ -31,6 +35,7 @@ impl Builder {
 			filters: vec![],
 			start_at_block: 0,
 			on_conflict: OnConflictAction::DoNothing,
+			checkpoint_store: None,
 		}
 	}
 
@@ -78,37 +83,104 @@ impl Builder {
 		self
 	}
 
+	/**
+	 * If the indexer we are creating already exists, re-create it only if its network, start
+	 * block or filters changed. When resuming from a checkpoint, the start block is not
+	 * compared.
+	 */
+	pub fn on_conflict_recreate_if_changed(&mut self) -> &mut Self {
+		self.on_conflict = OnConflictAction::RecreateIfChanged;
+		self
+	}
+
+	/**
+	 * If the indexer we are creating already exists, fail with an `IndexerAlreadyExists` error
+	 */
+	pub fn on_conflict_fail(&mut self) -> &mut Self {
+		self.on_conflict = OnConflictAction::Fail;
+		self
+	}
+
+	/**
+	 * When (re-)creating the indexer, start right after the last checkpoint found in the store
+	 * instead of at the static start block
+	 */
+	pub fn resume_from_checkpoint(
+		&mut self,
+		checkpoint_store: Arc<dyn CheckpointStore>,
+	) -> &mut Self {
+		self.checkpoint_store = Some(checkpoint_store);
+		self
+	}
+
+	async fn index_from_block(&self, indexer_id: &IndexerId) -> Result<u64> {
+		let checkpoint = match &self.checkpoint_store {
+			Some(store) => store.load(indexer_id).await?,
+			None => None,
+		};
+
+		Ok(checkpoint.map_or(self.start_at_block, |checkpoint| checkpoint.number + 1))
+	}
+
 	/**
 	 * Build the indexer
 	 */
 	pub async fn build(&mut self, indexer_id: IndexerId) -> Result<Indexer> {
-		let existing = match self.indexer_repository.by_id(&indexer_id).await? {
-			Some(indexer) => match self.on_conflict {
-				OnConflictAction::Recreate => {
-					self.indexer_repository.delete(&indexer.id).await?;
-					None
+		let existing = self.indexer_repository.by_id(&indexer_id).await?;
+		if let Some(existing) = &existing {
+			match self.on_conflict {
+				OnConflictAction::DoNothing => return Ok(existing.clone()),
+				OnConflictAction::Fail =>
+					return Err(Error::IndexerAlreadyExists(existing.id.clone())),
+				OnConflictAction::Recreate => (),
+				OnConflictAction::RecreateIfChanged => {
+					let requested = Indexer::new(
+						indexer_id.clone(),
+						self.network.clone(),
+						self.start_at_block,
+						self.filters.clone(),
+					);
+					let changes =
+						Changes::between(existing, &requested, self.checkpoint_store.is_none());
+					if changes.is_empty() {
+						return Ok(existing.clone());
+					}
+					log_changes(&indexer_id, &changes);
 				},
-				OnConflictAction::DoNothing => Some(indexer),
-			},
-			None => None,
-		};
+			}
+		}
 
-		match existing {
-			Some(indexer) => Ok(indexer),
-			None => {
-				let indexer = Indexer::new(
-					indexer_id,
-					self.network.clone(),
-					self.start_at_block,
-					self.filters.clone(),
-				);
-				self.indexer_repository.create(&indexer).await?;
-				Ok(indexer)
-			},
+		// Load the checkpoint before deleting anything, so that a failure leaves the existing
+		// indexer untouched
+		let index_from_block = self.index_from_block(&indexer_id).await?;
+		if let Some(existing) = existing {
+			self.indexer_repository.delete(&existing.id).await?;
 		}
+
+		let indexer = Indexer::new(
+			indexer_id,
+			self.network.clone(),
+			index_from_block,
+			self.filters.clone(),
+		);
+		self.indexer_repository.create(&indexer).await?;
+		Ok(indexer)
 	}
 }
 
+fn log_changes(indexer_id: &IndexerId, changes: &Changes) {
+	slog::info!(
+		slog_scope::logger(),
+		"🔀 Indexer `{}` definition changed, recreating it", indexer_id;
+		"indexer" => indexer_id.to_string(),
+		"network" => changes.network.as_ref().map(|(from, to)| format!("{:?} -> {:?}", from, to)),
+		"index_from_block" =>
+			changes.index_from_block.map(|(from, to)| format!("{} -> {}", from, to)),
+		"added_filters" => format_filters(&changes.added_filters),
+		"removed_filters" => format_filters(&changes.removed_filters),
+	);
+}
+
 #[cfg(test)]
 mod tests {
 	use super::*;
@@ -250,6 +322,205 @@ mod tests {
 		assert!(result.is_ok(), "{}", result.err().unwrap());
 	}
 
+	fn existing_indexer() -> Indexer {
+		Indexer::new(
+			"ID".into(),
+			Network::Starknet(StarknetChain::Goerli),
+			1234,
+			vec![
+				EventFilter::new(ContractAddress::from_str("0x1234").unwrap(), "Event1"),
+				EventFilter::new(ContractAddress::from_str("0x1234").unwrap(), "Event2"),
+			],
+		)
+		.indexed_to_block(2000)
+	}
+
+	#[rstest]
+	#[tokio::test]
+	async fn on_conflict_recreate_if_changed_keeps_an_unchanged_indexer(
+		mut indexer_repository: MockIndexerRepository,
+	) {
+		indexer_repository.expect_by_id().returning(|_| Ok(Some(existing_indexer())));
+
+		let result = Builder::new(Arc::new(indexer_repository))
+			.network(Network::Starknet(StarknetChain::Goerli))
+			.start_at_block(1234)
+			.filter(ContractAddress::from_str("0x1234").unwrap(), "Event2")
+			.filter(ContractAddress::from_str("0x1234").unwrap(), "Event1")
+			.on_conflict_recreate_if_changed()
+			.build("ID".into())
+			.await;
+
+		assert!(result.is_ok(), "{}", result.err().unwrap());
+		assert_eq!(existing_indexer(), result.unwrap());
+	}
+
+	#[rstest]
+	#[case(Network::Starknet(StarknetChain::Mainnet), 1234, "Event2")]
+	#[case(Network::Starknet(StarknetChain::Goerli), 1000, "Event2")]
+	#[case(Network::Starknet(StarknetChain::Goerli), 1234, "Event3")]
+	#[tokio::test]
+	async fn on_conflict_recreate_if_changed_recreates_a_changed_indexer(
+		mut indexer_repository: MockIndexerRepository,
+		#[case] network: Network,
+		#[case] start_at_block: u64,
+		#[case] event_name: &'static str,
+	) {
+		indexer_repository.expect_by_id().returning(|_| Ok(Some(existing_indexer())));
+		indexer_repository
+			.expect_delete()
+			.with(eq(IndexerId::from("ID")))
+			.times(1)
+			.returning(|_| Ok(()));
+		indexer_repository.expect_create().times(1).returning(|_| Ok(()));
+
+		let result = Builder::new(Arc::new(indexer_repository))
+			.network(network)
+			.start_at_block(start_at_block)
+			.filter(ContractAddress::from_str("0x1234").unwrap(), "Event1")
+			.filter(ContractAddress::from_str("0x1234").unwrap(), event_name)
+			.on_conflict_recreate_if_changed()
+			.build("ID".into())
+			.await;
+
+		assert!(result.is_ok(), "{}", result.err().unwrap());
+		assert_eq!(None, result.unwrap().indexed_to_block);
+	}
+
+	#[rstest]
+	#[tokio::test]
+	async fn on_conflict_recreate_if_changed_ignores_start_block_when_resuming(
+		mut indexer_repository: MockIndexerRepository,
+	) {
+		indexer_repository.expect_by_id().returning(|_| Ok(Some(existing_indexer())));
+
+		let result = Builder::new(Arc::new(indexer_repository))
+			.network(Network::Starknet(StarknetChain::Goerli))
+			.start_at_block(0)
+			.filter(ContractAddress::from_str("0x1234").unwrap(), "Event1")
+			.filter(ContractAddress::from_str("0x1234").unwrap(), "Event2")
+			.on_conflict_recreate_if_changed()
+			.resume_from_checkpoint(Arc::new(MockCheckpointStore::new()))
+			.build("ID".into())
+			.await;
+
+		assert!(result.is_ok(), "{}", result.err().unwrap());
+	}
+
+	#[rstest]
+	#[tokio::test]
+	async fn on_conflict_fail(mut indexer_repository: MockIndexerRepository) {
+		indexer_repository.expect_by_id().returning(|_| Ok(Some(existing_indexer())));
+
+		let result = Builder::new(Arc::new(indexer_repository))
+			.on_conflict_fail()
+			.build("ID".into())
+			.await;
+
+		assert!(
+			matches!(result, Err(Error::IndexerAlreadyExists(ref id)) if id == &IndexerId::from("ID")),
+			"{:?}",
+			result
+		);
+	}
+
+	#[rstest]
+	#[tokio::test]
+	async fn builder_resumes_from_the_stored_checkpoint(
+		mut indexer_repository: MockIndexerRepository,
+	) {
+		indexer_repository.expect_by_id().returning(|_| Ok(None));
+		indexer_repository
+			.expect_create()
+			.withf(|indexer| indexer.index_from_block == 43)
+			.returning(|_| Ok(()));
+
+		let mut checkpoint_store = MockCheckpointStore::new();
+		checkpoint_store.expect_load().with(eq(IndexerId::from("ID"))).returning(|_| {
+			Ok(Some(BlockHeader::new(
+				BlockHash::from_str("0x1234").unwrap(),
+				42,
+			)))
+		});
+
+		let result = Builder::new(Arc::new(indexer_repository))
+			.start_at_block(10)
+			.resume_from_checkpoint(Arc::new(checkpoint_store))
+			.build("ID".into())
+			.await;
+
+		assert!(result.is_ok(), "{}", result.err().unwrap());
+		assert_eq!(43, result.unwrap().index_from_block);
+	}
+
+	#[rstest]
+	#[tokio::test]
+	async fn builder_starts_at_block_without_checkpoint(
+		mut indexer_repository: MockIndexerRepository,
+	) {
+		indexer_repository.expect_by_id().returning(|_| Ok(None));
+		indexer_repository.expect_create().returning(|_| Ok(()));
+
+		let mut checkpoint_store = MockCheckpointStore::new();
+		checkpoint_store.expect_load().returning(|_| Ok(None));
+
+		let result = Builder::new(Arc::new(indexer_repository))
+			.start_at_block(10)
+			.resume_from_checkpoint(Arc::new(checkpoint_store))
+			.build("ID".into())
+			.await;
+
+		assert!(result.is_ok(), "{}", result.err().unwrap());
+		assert_eq!(10, result.unwrap().index_from_block);
+	}
+
+	#[rstest]
+	#[tokio::test]
+	async fn builder_forward_error_on_checkpoint_load(
+		mut indexer_repository: MockIndexerRepository,
+	) {
+		indexer_repository.expect_by_id().returning(|_| Ok(None));
+
+		let mut checkpoint_store = MockCheckpointStore::new();
+		checkpoint_store.expect_load().returning(|_| {
+			Err(CheckpointStoreError::Load {
+				id: "ID".into(),
+				details: String::new(),
+			})
+		});
+
+		let result = Builder::new(Arc::new(indexer_repository))
+			.resume_from_checkpoint(Arc::new(checkpoint_store))
+			.build("ID".into())
+			.await;
+		assert!(result.is_err());
+	}
+
+	#[rstest]
+	#[tokio::test]
+	async fn builder_keeps_the_existing_indexer_on_checkpoint_load_error(
+		mut indexer_repository: MockIndexerRepository,
+	) {
+		indexer_repository.expect_by_id().returning(|_| Ok(Some(existing_indexer())));
+		indexer_repository.expect_delete().never();
+		indexer_repository.expect_create().never();
+
+		let mut checkpoint_store = MockCheckpointStore::new();
+		checkpoint_store.expect_load().returning(|_| {
+			Err(CheckpointStoreError::Load {
+				id: "ID".into(),
+				details: String::new(),
+			})
+		});
+
+		let result = Builder::new(Arc::new(indexer_repository))
+			.on_conflict_recreate()
+			.resume_from_checkpoint(Arc::new(checkpoint_store))
+			.build("ID".into())
+			.await;
+		assert!(result.is_err());
+	}
+
 	#[rstest]
 	#[tokio::test]
 	async fn builder_forward_error_on_get(mut indexer_repository: MockIndexerRepository) {
@@ -1,2 +1,4 @@
+mod changes;
+
 mod indexer;
 pub use indexer::Builder as IndexerBuilder;
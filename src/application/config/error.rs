@@ -0,0 +1,39 @@
+use crate::domain::*;
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum Error {
+	#[error("unable to read configuration file `{path}`: {details}")]
+	Read { path: String, details: String },
+	#[error("environment variable `{0}` used in configuration is not set")]
+	UndefinedVariable(String),
+	#[error("invalid configuration: {0}")]
+	Parse(#[from] toml::de::Error),
+	#[error("configuration must declare at least one indexer")]
+	NoIndexer,
+	#[error("indexer `{0}` is declared more than once")]
+	DuplicateIndexer(IndexerId),
+	#[error("ABI has an invalid contract address `{address}`: {details}")]
+	InvalidAbiContractAddress {
+		address: String,
+		details: ParseHexaStringError,
+	},
+	#[error("indexer `{0}` must declare at least one filter")]
+	NoFilter(IndexerId),
+	#[error("indexer `{id}` has an invalid contract address `{address}`: {details}")]
+	InvalidContractAddress {
+		id: IndexerId,
+		address: String,
+		details: ParseHexaStringError,
+	},
+	#[error("indexer `{id}` has an invalid event signature `{signature}`: {details}")]
+	InvalidEventSignature {
+		id: IndexerId,
+		signature: String,
+		details: ParseEventSignatureError,
+	},
+	#[error("indexer `{0}` records checkpoints but no checkpoint store is configured")]
+	MissingCheckpointStore(IndexerId),
+	#[error("indexer `{0}` records dead letters but no dead letter store is configured")]
+	MissingDeadLetterStore(IndexerId),
+}
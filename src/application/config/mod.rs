@@ -0,0 +1,672 @@
+mod error;
+pub use error::Error;
+
+use super::IndexerBuilder;
+use crate::domain::*;
+use serde::Deserialize;
+use std::{collections::HashSet, path::Path, str::FromStr, sync::Arc};
+
+type Result<T> = std::result::Result<T, Error>;
+
+/**
+ * Declarative description of the indexers to run, usually read from a TOML file
+ */
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Config {
+	pub checkpoint_store: Option<StoreConfig>,
+	/** Where to keep the events which could not be decoded */
+	pub dead_letter_store: Option<StoreConfig>,
+	/** ABI files of the contracts whose events have no dedicated type */
+	pub abis: Vec<AbiConfig>,
+	pub indexers: Vec<IndexerConfig>,
+}
+
+/**
+ * Storage backend shared by the checkpoint and dead letter stores
+ */
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case", deny_unknown_fields)]
+pub enum StoreConfig {
+	File { path: String },
+	Sqlite { database_url: String },
+}
+
+/**
+ * The ABI file describing the events of a contract
+ */
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AbiConfig {
+	pub contract_address: ContractAddress,
+	pub path: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IndexerConfig {
+	pub id: IndexerId,
+	pub network: Network,
+	pub start_at_block: u64,
+	pub on_conflict: OnConflict,
+	pub filters: Vec<EventFilter>,
+	pub observers: Vec<ObserverKind>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum OnConflict {
+	DoNothing,
+	Recreate,
+	RecreateIfChanged,
+	Fail,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ObserverKind {
+	Logger,
+	GithubIdentifierProjection,
+	Checkpoint,
+	DeadLetter,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RawConfig {
+	checkpoint_store: Option<StoreConfig>,
+	dead_letter_store: Option<StoreConfig>,
+	#[serde(default, rename = "abi")]
+	abis: Vec<RawAbiConfig>,
+	#[serde(default, rename = "indexer")]
+	indexers: Vec<RawIndexerConfig>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RawAbiConfig {
+	contract_address: String,
+	path: String,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RawIndexerConfig {
+	id: String,
+	network: RawNetwork,
+	#[serde(default)]
+	start_at_block: u64,
+	#[serde(default = "default_on_conflict")]
+	on_conflict: OnConflict,
+	#[serde(default, rename = "filter")]
+	filters: Vec<RawFilterConfig>,
+	#[serde(default = "default_observers")]
+	observers: Vec<ObserverKind>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RawFilterConfig {
+	contract_address: String,
+	event_name: String,
+}
+
+#[derive(Deserialize)]
+#[serde(try_from = "String")]
+struct RawNetwork(Network);
+
+fn default_on_conflict() -> OnConflict {
+	OnConflict::DoNothing
+}
+
+fn default_observers() -> Vec<ObserverKind> {
+	vec![ObserverKind::Logger]
+}
+
+impl TryFrom<String> for RawNetwork {
+	type Error = ParseNetworkError;
+
+	fn try_from(name: String) -> std::result::Result<Self, Self::Error> {
+		name.parse().map(Self)
+	}
+}
+
+impl Config {
+	/**
+	 * Read and validate the configuration file at the given path
+	 */
+	pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self> {
+		let content = std::fs::read_to_string(&path).map_err(|e| Error::Read {
+			path: path.as_ref().display().to_string(),
+			details: e.to_string(),
+		})?;
+		content.parse()
+	}
+
+	/**
+	 * Parse the configuration, looking up the variables it refers to with `env`
+	 */
+	fn parse<F: Fn(&str) -> Option<String>>(content: &str, env: &F) -> Result<Self> {
+		let mut value: toml::Value = toml::from_str(content)?;
+		expand_env_variables(&mut value, env)?;
+		Self::validate(value.try_into()?)
+	}
+
+	fn validate(raw: RawConfig) -> Result<Self> {
+		if raw.indexers.is_empty() {
+			return Err(Error::NoIndexer);
+		}
+
+		let mut config = Self {
+			checkpoint_store: raw.checkpoint_store,
+			dead_letter_store: raw.dead_letter_store,
+			abis: raw.abis.into_iter().map(AbiConfig::validate).collect::<Result<_>>()?,
+			indexers: Vec::new(),
+		};
+
+		let mut ids = HashSet::new();
+		config.indexers = raw
+			.indexers
+			.into_iter()
+			.map(|indexer| {
+				let id = IndexerId::from(indexer.id.clone());
+				if !ids.insert(id.to_string()) {
+					return Err(Error::DuplicateIndexer(id));
+				}
+				IndexerConfig::validate(id, indexer, &config)
+			})
+			.collect::<Result<_>>()?;
+
+		Ok(config)
+	}
+}
+
+impl FromStr for Config {
+	type Err = Error;
+
+	fn from_str(content: &str) -> Result<Self> {
+		Self::parse(content, &|name| std::env::var(name).ok())
+	}
+}
+
+impl AbiConfig {
+	fn validate(raw: RawAbiConfig) -> Result<Self> {
+		let contract_address = ContractAddress::from_str(&raw.contract_address).map_err(
+			|details| Error::InvalidAbiContractAddress {
+				address: raw.contract_address,
+				details,
+			},
+		)?;
+		Ok(Self {
+			contract_address,
+			path: raw.path,
+		})
+	}
+}
+
+impl IndexerConfig {
+	fn validate(id: IndexerId, raw: RawIndexerConfig, config: &Config) -> Result<Self> {
+		if raw.filters.is_empty() {
+			return Err(Error::NoFilter(id));
+		}
+
+		if raw.observers.contains(&ObserverKind::Checkpoint) && config.checkpoint_store.is_none() {
+			return Err(Error::MissingCheckpointStore(id));
+		}
+
+		if raw.observers.contains(&ObserverKind::DeadLetter) && config.dead_letter_store.is_none() {
+			return Err(Error::MissingDeadLetterStore(id));
+		}
+
+		let network = raw.network.0;
+		let filters = raw
+			.filters
+			.into_iter()
+			.map(|filter| {
+				let contract_address = ContractAddress::from_str(&filter.contract_address)
+					.map_err(|details| Error::InvalidContractAddress {
+						id: id.clone(),
+						address: filter.contract_address,
+						details,
+					})?;
+				// Ethereum filters match the topic hashed from the canonical signature
+				let event_name = match network {
+					Network::Starknet(_) => filter.event_name,
+					Network::Ethereum(_) => EventSignature::from_str(&filter.event_name)
+						.map_err(|details| Error::InvalidEventSignature {
+							id: id.clone(),
+							signature: filter.event_name,
+							details,
+						})?
+						.to_string(),
+				};
+				Ok(EventFilter::new(contract_address, event_name))
+			})
+			.collect::<Result<_>>()?;
+
+		Ok(Self {
+			id,
+			network,
+			start_at_block: raw.start_at_block,
+			on_conflict: raw.on_conflict,
+			filters,
+			observers: raw.observers,
+		})
+	}
+
+	/**
+	 * Create a builder configured to build this indexer
+	 */
+	pub fn builder(&self, indexer_repository: Arc<dyn IndexerRepository>) -> IndexerBuilder {
+		let mut builder = IndexerBuilder::new(indexer_repository);
+		builder.network(self.network.clone()).start_at_block(self.start_at_block);
+
+		match self.on_conflict {
+			OnConflict::DoNothing => builder.on_conflict_do_nothing(),
+			OnConflict::Recreate => builder.on_conflict_recreate(),
+			OnConflict::RecreateIfChanged => builder.on_conflict_recreate_if_changed(),
+			OnConflict::Fail => builder.on_conflict_fail(),
+		};
+
+		for filter in &self.filters {
+			builder.filter(filter.contract_address.clone(), filter.event_name.clone());
+		}
+
+		builder
+	}
+}
+
+/**
+ * Replace `${NAME}` occurrences in string values by the value of the NAME environment variable
+ */
+fn expand_env_variables<F: Fn(&str) -> Option<String>>(
+	value: &mut toml::Value,
+	env: &F,
+) -> Result<()> {
+	match value {
+		toml::Value::String(string) => *string = expand_string(string, env)?,
+		toml::Value::Array(values) =>
+			values.iter_mut().try_for_each(|value| expand_env_variables(value, env))?,
+		toml::Value::Table(table) =>
+			table.iter_mut().try_for_each(|(_, value)| expand_env_variables(value, env))?,
+		_ => (),
+	}
+	Ok(())
+}
+
+fn expand_string<F: Fn(&str) -> Option<String>>(content: &str, env: &F) -> Result<String> {
+	let mut expanded = String::with_capacity(content.len());
+	let mut rest = content;
+
+	while let Some(start) = rest.find("${") {
+		let end = match rest[start..].find('}') {
+			Some(end) => start + end,
+			None => break,
+		};
+		let name = &rest[start + 2..end];
+		let value = env(name).ok_or_else(|| Error::UndefinedVariable(name.to_string()))?;
+
+		expanded.push_str(&rest[..start]);
+		expanded.push_str(&value);
+		rest = &rest[end + 1..];
+	}
+
+	expanded.push_str(rest);
+	Ok(expanded)
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use mockall::predicate::*;
+	use rstest::*;
+
+	const CONFIG: &str = r#"
+		[checkpoint_store]
+		type = "file"
+		path = "checkpoints.json"
+
+		[dead_letter_store]
+		type = "sqlite"
+		database_url = "sqlite://dead-letters.db"
+
+		[[abi]]
+		contract_address = "0x1234"
+		path = "abi/registry.json"
+
+		[[indexer]]
+		id = "indexer-goerli"
+		network = "starknet-goerli"
+		start_at_block = 291345
+		on_conflict = "recreate"
+		observers = ["logger", "checkpoint", "dead-letter"]
+
+		[[indexer.filter]]
+		contract_address = "0x1234"
+		event_name = "GithubIdentifierRegistered"
+
+		[[indexer.filter]]
+		contract_address = "0x1234"
+		event_name = "GithubIdentifierUnregistered"
+
+		[[indexer]]
+		id = "indexer-devnet"
+		network = "starknet-devnet"
+
+		[[indexer.filter]]
+		contract_address = "0x5678"
+		event_name = "GithubIdentifierRegistered"
+	"#;
+
+	#[test]
+	fn config_can_be_parsed() {
+		let config = Config::from_str(CONFIG).unwrap();
+
+		assert_eq!(
+			Some(StoreConfig::File {
+				path: String::from("checkpoints.json")
+			}),
+			config.checkpoint_store
+		);
+		assert_eq!(
+			Some(StoreConfig::Sqlite {
+				database_url: String::from("sqlite://dead-letters.db")
+			}),
+			config.dead_letter_store
+		);
+		assert_eq!(
+			vec![AbiConfig {
+				contract_address: ContractAddress::from_str("0x1234").unwrap(),
+				path: String::from("abi/registry.json"),
+			}],
+			config.abis
+		);
+		assert_eq!(
+			vec![
+				IndexerConfig {
+					id: "indexer-goerli".into(),
+					network: Network::Starknet(StarknetChain::Goerli),
+					start_at_block: 291345,
+					on_conflict: OnConflict::Recreate,
+					filters: vec![
+						EventFilter::new(
+							ContractAddress::from_str("0x1234").unwrap(),
+							"GithubIdentifierRegistered"
+						),
+						EventFilter::new(
+							ContractAddress::from_str("0x1234").unwrap(),
+							"GithubIdentifierUnregistered"
+						),
+					],
+					observers: vec![
+						ObserverKind::Logger,
+						ObserverKind::Checkpoint,
+						ObserverKind::DeadLetter
+					],
+				},
+				IndexerConfig {
+					id: "indexer-devnet".into(),
+					network: Network::Starknet(StarknetChain::Devnet),
+					start_at_block: 0,
+					on_conflict: OnConflict::DoNothing,
+					filters: vec![EventFilter::new(
+						ContractAddress::from_str("0x5678").unwrap(),
+						"GithubIdentifierRegistered"
+					)],
+					observers: vec![ObserverKind::Logger],
+				},
+			],
+			config.indexers
+		);
+	}
+
+	#[test]
+	fn example_config_file_is_valid() {
+		let result = Config::parse(include_str!("../../../indexers.toml"), &|name| {
+			(name == "REGISTRY_ADDRESS").then(|| String::from("0x1234"))
+		});
+		assert!(result.is_ok(), "{}", result.err().unwrap());
+	}
+
+	#[test]
+	fn ethereum_filters_are_solidity_signatures() {
+		let config = Config::from_str(
+			r#"
+			[[indexer]]
+			id = "bridge-mainnet"
+			network = "ethereum-mainnet"
+
+			[[indexer.filter]]
+			contract_address = "0x1234"
+			event_name = "Deposited(address indexed from, uint amount)"
+			"#,
+		)
+		.unwrap();
+
+		assert_eq!(
+			Network::Ethereum(EthereumChain::Mainnet),
+			config.indexers[0].network
+		);
+		assert_eq!(
+			"Deposited(address,uint256)",
+			config.indexers[0].filters[0].event_name
+		);
+	}
+
+	#[test]
+	fn custom_networks_can_be_configured() {
+		let config = Config::from_str(
+			r#"
+			[[indexer]]
+			id = "ID"
+			network = "starknet-local"
+
+			[[indexer.filter]]
+			contract_address = "0x1234"
+			event_name = "Event"
+			"#,
+		)
+		.unwrap();
+
+		assert!(matches!(
+			&config.indexers[0].network,
+			Network::Starknet(StarknetChain::Custom(name)) if name.to_string() == "starknet-local"
+		));
+	}
+
+	#[test]
+	fn environment_variables_are_expanded() {
+		let config = Config::parse(
+			r#"
+			[[indexer]]
+			id = "ID"
+			network = "starknet-devnet"
+
+			[[indexer.filter]]
+			contract_address = "${ADDRESS}"
+			event_name = "Event"
+			"#,
+			&|name| (name == "ADDRESS").then(|| String::from("0x4321")),
+		)
+		.unwrap();
+
+		assert_eq!(
+			ContractAddress::from_str("0x4321").unwrap(),
+			config.indexers[0].filters[0].contract_address
+		);
+	}
+
+	#[test]
+	fn undefined_environment_variables_are_rejected() {
+		let result = Config::parse(
+			r#"
+			[[indexer]]
+			id = "ID"
+			network = "starknet-devnet"
+
+			[[indexer.filter]]
+			contract_address = "${ADDRESS}"
+			event_name = "Event"
+			"#,
+			&|_| None,
+		);
+
+		assert!(matches!(result, Err(Error::UndefinedVariable(name)) if name == "ADDRESS"));
+	}
+
+	#[rstest]
+	#[case("", "configuration must declare at least one indexer")]
+	#[case(
+		r#"
+		[[indexer]]
+		id = "ID"
+		network = "starknet-devnet"
+		[[indexer.filter]]
+		contract_address = "${CONFIG_TEST_UNDEFINED}"
+		event_name = "Event"
+		"#,
+		"environment variable `CONFIG_TEST_UNDEFINED` used in configuration is not set"
+	)]
+	#[case(
+		r#"
+		[[indexer]]
+		id = "ID"
+		network = "starknet-devnet"
+		[[indexer.filter]]
+		contract_address = "0x1234"
+		event_name = "Event"
+		[[indexer]]
+		id = "ID"
+		network = "starknet-devnet"
+		[[indexer.filter]]
+		contract_address = "0x1234"
+		event_name = "Event"
+		"#,
+		"indexer `ID` is declared more than once"
+	)]
+	#[case(
+		r#"
+		[[indexer]]
+		id = "ID"
+		network = "starknet-devnet"
+		"#,
+		"indexer `ID` must declare at least one filter"
+	)]
+	#[case(
+		r#"
+		[[indexer]]
+		id = "ID"
+		network = "starknet-devnet"
+		[[indexer.filter]]
+		contract_address = "1234"
+		event_name = "Event"
+		"#,
+		"indexer `ID` has an invalid contract address `1234`: provided string shoud be '0x' prefixed"
+	)]
+	#[case(
+		r#"
+		[[indexer]]
+		id = "ID"
+		network = "starknet-devnet"
+		observers = ["checkpoint"]
+		[[indexer.filter]]
+		contract_address = "0x1234"
+		event_name = "Event"
+		"#,
+		"indexer `ID` records checkpoints but no checkpoint store is configured"
+	)]
+	#[case(
+		r#"
+		[[indexer]]
+		id = "ID"
+		network = "starknet-devnet"
+		observers = ["dead-letter"]
+		[[indexer.filter]]
+		contract_address = "0x1234"
+		event_name = "Event"
+		"#,
+		"indexer `ID` records dead letters but no dead letter store is configured"
+	)]
+	#[case(
+		r#"
+		[[indexer]]
+		id = "ID"
+		network = "ethereum-goerli"
+		[[indexer.filter]]
+		contract_address = "0x1234"
+		event_name = "Deposited(felt)"
+		"#,
+		"indexer `ID` has an invalid event signature `Deposited(felt)`: unsupported type `felt`"
+	)]
+	#[case(
+		r#"
+		[[abi]]
+		contract_address = "1234"
+		path = "abi.json"
+		[[indexer]]
+		id = "ID"
+		network = "starknet-devnet"
+		[[indexer.filter]]
+		contract_address = "0x1234"
+		event_name = "Event"
+		"#,
+		"ABI has an invalid contract address `1234`: provided string shoud be '0x' prefixed"
+	)]
+	fn invalid_config_is_rejected(#[case] content: &str, #[case] expected_message: &str) {
+		let result = Config::from_str(content);
+		assert_eq!(expected_message, result.unwrap_err().to_string());
+	}
+
+	#[rstest]
+	#[case("network = \"unknown\"")]
+	#[case("observers = [\"unknown\"]")]
+	#[case("on_conflict = \"unknown\"")]
+	#[case("unknown_field = 1")]
+	fn unknown_values_are_rejected(#[case] line: &str) {
+		let result = Config::from_str(&format!(
+			"[[indexer]]\nid = \"ID\"\nnetwork = \"starknet-devnet\"\n{}\n",
+			line
+		));
+		assert!(matches!(result, Err(Error::Parse(_))), "{:?}", result);
+	}
+
+	#[test]
+	fn missing_file_is_rejected() {
+		let result = Config::from_file("/does/not/exist.toml");
+		assert!(matches!(result, Err(Error::Read { .. })));
+	}
+
+	#[rstest]
+	#[tokio::test]
+	async fn indexer_config_can_be_turned_into_a_builder() {
+		let config = Config::from_str(CONFIG).unwrap();
+		let indexer_config = config.indexers[0].clone();
+
+		let mut indexer_repository = MockIndexerRepository::new();
+		indexer_repository.expect_by_id().returning(|_| {
+			Ok(Some(Indexer::new(
+				"indexer-goerli".into(),
+				Network::Starknet(StarknetChain::Devnet),
+				0,
+				Vec::new(),
+			)))
+		});
+		indexer_repository
+			.expect_delete()
+			.with(eq(IndexerId::from("indexer-goerli")))
+			.returning(|_| Ok(()));
+		indexer_repository
+			.expect_create()
+			.with(eq(Indexer::new(
+				indexer_config.id.clone(),
+				indexer_config.network.clone(),
+				indexer_config.start_at_block,
+				indexer_config.filters.clone(),
+			)))
+			.returning(|_| Ok(()));
+
+		let result = indexer_config
+			.builder(Arc::new(indexer_repository))
+			.build(indexer_config.id.clone())
+			.await;
+
+		assert!(result.is_ok(), "{}", result.err().unwrap());
+	}
+}
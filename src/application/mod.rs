@@ -1,2 +1,12 @@
 mod builders;
 pub use builders::*;
+
+mod config;
+pub use config::{
+	AbiConfig, Config, Error as ConfigError, IndexerConfig, ObserverKind, OnConflict, StoreConfig,
+};
+
+mod supervisor;
+pub use supervisor::{
+	Error as SupervisorError, IndexerState, IndexerStatus, RestartPolicy, Supervisor,
+};
@@ -0,0 +1,350 @@
+mod restart;
+pub use restart::RestartPolicy;
+
+mod status;
+use status::Tracker;
+pub use status::{State as IndexerState, Status as IndexerStatus};
+
+use crate::domain::*;
+use futures::future::join_all;
+use log::error;
+use std::sync::Arc;
+use thiserror::Error as ThisError;
+use tokio_util::sync::CancellationToken;
+
+#[derive(ThisError, Debug)]
+pub enum Error {
+	#[error("indexer `{id}` gave up after {restarts} restart(s): {source}")]
+	GaveUp {
+		id: IndexerId,
+		restarts: u32,
+		source: IndexingServiceError,
+	},
+}
+
+type Result<T> = std::result::Result<T, Error>;
+
+struct Supervised {
+	indexer: Indexer,
+	observer: Arc<dyn BlockchainObserver>,
+	tracker: Arc<Tracker>,
+}
+
+/**
+ * The Supervisor runs several indexers concurrently on a shared indexing service.
+ * Each indexer is restarted independently when it stops with an error, according to the
+ * restart policy, and all of them are stopped together on shutdown, after the block they are
+ * handling.
+ */
+pub struct Supervisor {
+	indexing_service: Arc<dyn IndexingService>,
+	restart_policy: RestartPolicy,
+	indexers: Vec<Supervised>,
+	shutdown: CancellationToken,
+}
+
+impl Supervisor {
+	pub fn new(indexing_service: Arc<dyn IndexingService>) -> Self {
+		Self {
+			indexing_service,
+			restart_policy: RestartPolicy::default(),
+			indexers: Vec::new(),
+			shutdown: CancellationToken::new(),
+		}
+	}
+
+	/**
+	 * Modify the policy used to restart crashed indexers
+	 */
+	pub fn with_restart_policy(mut self, restart_policy: RestartPolicy) -> Self {
+		self.restart_policy = restart_policy;
+		self
+	}
+
+	/**
+	 * Add an indexer to supervise, streaming its events to its own observer
+	 */
+	pub fn supervise(
+		&mut self,
+		indexer: Indexer,
+		observer: Arc<dyn BlockchainObserver>,
+	) -> &mut Self {
+		self.indexers.push(Supervised {
+			indexer,
+			observer,
+			tracker: Arc::new(Tracker::default()),
+		});
+		self
+	}
+
+	/**
+	 * Current status of every supervised indexer
+	 */
+	pub fn statuses(&self) -> Vec<(IndexerId, IndexerStatus)> {
+		self.indexers
+			.iter()
+			.map(|supervised| (supervised.indexer.id.clone(), supervised.tracker.status()))
+			.collect()
+	}
+
+	/**
+	 * Stop all the supervised indexers
+	 */
+	pub fn shutdown(&self) {
+		self.shutdown.cancel();
+	}
+
+	/**
+	 * Run all the indexers until they stop, or until shutdown, then let their observers flush.
+	 * If some indexers gave up, the error of the first one is returned.
+	 */
+	pub async fn run(&self) -> Result<()> {
+		let results =
+			join_all(self.indexers.iter().map(|supervised| self.run_indexer(supervised))).await;
+
+		for supervised in &self.indexers {
+			if let Err(error) = supervised.observer.on_shutdown().await {
+				error!(
+					"💥 Observers of indexer `{}` failed to shut down: {}",
+					supervised.indexer.id, error
+				);
+			}
+		}
+
+		results.into_iter().collect()
+	}
+
+	async fn run_indexer(&self, supervised: &Supervised) -> Result<()> {
+		// The tracker comes last so that it only sees blocks fully handled by the observer
+		let observer: Arc<dyn BlockchainObserver> =
+			Arc::new(BlockchainObserverComposite::new(vec![
+				supervised.observer.clone(),
+				supervised.tracker.clone(),
+			]));
+		let mut restarts = 0;
+
+		loop {
+			let processed_blocks = supervised.tracker.status().processed_blocks;
+			// The indexing service stops by itself on shutdown, once the current block is handled
+			let result = self
+				.indexing_service
+				.fetch_new_events(&supervised.indexer, observer.clone(), self.shutdown.clone())
+				.await;
+
+			let error = match result {
+				Ok(()) => {
+					stop(supervised);
+					return Ok(());
+				},
+				Err(error) => error,
+			};
+
+			error!("💥 Indexer `{}` crashed: {}", supervised.indexer.id, error);
+			supervised.tracker.update(|status| {
+				status.connected = false;
+				status.error_count += 1;
+				status.last_error = Some(error.to_string());
+			});
+
+			// Only consecutive crashes count, an indexer which made progress starts over
+			if supervised.tracker.status().processed_blocks > processed_blocks {
+				restarts = 0;
+			}
+
+			if !self.restart_policy.should_restart(restarts) {
+				supervised.tracker.update(|status| status.state = IndexerState::Failed);
+				return Err(Error::GaveUp {
+					id: supervised.indexer.id.clone(),
+					restarts,
+					source: error,
+				});
+			}
+
+			supervised.tracker.update(|status| status.state = IndexerState::Restarting);
+			tokio::select! {
+				_ = self.shutdown.cancelled() => {
+					stop(supervised);
+					return Ok(());
+				},
+				_ = tokio::time::sleep(self.restart_policy.restart_delay()) => (),
+			};
+			restarts += 1;
+		}
+	}
+}
+
+/**
+ * Mark the indexer as stopped, on success or on shutdown
+ */
+fn stop(supervised: &Supervised) {
+	supervised.tracker.update(|status| {
+		status.state = IndexerState::Stopped;
+		status.connected = false;
+	});
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use rstest::*;
+	use std::time::Duration;
+
+	fn indexer(id: &str) -> Indexer {
+		Indexer::new(
+			id.into(),
+			Network::Starknet(StarknetChain::Devnet),
+			0,
+			Vec::new(),
+		)
+	}
+
+	#[fixture]
+	fn observer() -> Arc<dyn BlockchainObserver> {
+		let mut observer = MockBlockchainObserver::new();
+		observer.expect_on_shutdown().returning(|| Ok(()));
+		Arc::new(observer)
+	}
+
+	#[rstest]
+	#[tokio::test]
+	async fn runs_all_indexers(observer: Arc<dyn BlockchainObserver>) {
+		let mut indexing_service = MockIndexingService::new();
+		indexing_service.expect_fetch_new_events().times(2).returning(|_, _, _| Ok(()));
+
+		let mut supervisor = Supervisor::new(Arc::new(indexing_service));
+		supervisor
+			.supervise(indexer("A"), observer.clone())
+			.supervise(indexer("B"), observer);
+
+		supervisor.run().await.unwrap();
+
+		for (_, status) in supervisor.statuses() {
+			assert_eq!(IndexerState::Stopped, status.state);
+		}
+	}
+
+	#[tokio::test]
+	async fn shuts_observers_down_once_the_indexers_stopped() {
+		let mut indexing_service = MockIndexingService::new();
+		indexing_service.expect_fetch_new_events().times(2).returning(|_, _, _| Ok(()));
+
+		let mut observer = MockBlockchainObserver::new();
+		observer.expect_on_shutdown().times(2).returning(|| Ok(()));
+		let observer: Arc<dyn BlockchainObserver> = Arc::new(observer);
+
+		let mut supervisor = Supervisor::new(Arc::new(indexing_service));
+		supervisor
+			.supervise(indexer("A"), observer.clone())
+			.supervise(indexer("B"), observer);
+
+		supervisor.run().await.unwrap();
+	}
+
+	#[rstest]
+	#[tokio::test]
+	async fn restarts_crashed_indexers_independently(observer: Arc<dyn BlockchainObserver>) {
+		let mut indexing_service = MockIndexingService::new();
+		indexing_service
+			.expect_fetch_new_events()
+			.withf(|indexer, _, _| indexer.id == IndexerId::from("A"))
+			.times(1)
+			.returning(|_, _, _| Ok(()));
+		indexing_service
+			.expect_fetch_new_events()
+			.withf(|indexer, _, _| indexer.id == IndexerId::from("B"))
+			.times(3)
+			.returning(|_, _, _| Err(IndexingServiceError::StreamClosed));
+
+		let mut supervisor = Supervisor::new(Arc::new(indexing_service)).with_restart_policy(
+			RestartPolicy::default().delay(Duration::from_millis(1)).max_restarts(2),
+		);
+		supervisor
+			.supervise(indexer("A"), observer.clone())
+			.supervise(indexer("B"), observer);
+
+		let result = supervisor.run().await;
+		assert!(
+			matches!(result, Err(Error::GaveUp { ref id, restarts: 2, .. }) if id == &IndexerId::from("B")),
+			"{:?}",
+			result
+		);
+
+		let statuses = supervisor.statuses();
+		assert_eq!(IndexerState::Stopped, statuses[0].1.state);
+		assert_eq!(0, statuses[0].1.error_count);
+		assert_eq!(IndexerState::Failed, statuses[1].1.state);
+		assert_eq!(3, statuses[1].1.error_count);
+		assert_eq!(
+			Some(String::from("the indexing server closed the stream")),
+			statuses[1].1.last_error
+		);
+	}
+
+	#[tokio::test]
+	async fn restarts_are_counted_again_once_a_block_is_processed() {
+		let block = BlockHeader::new(BlockHash::from(vec![1]), 1);
+		let mut calls = 0;
+		let mut indexing_service = MockIndexingService::new();
+		indexing_service
+			.expect_fetch_new_events()
+			.times(3)
+			.returning(move |_, observer, _| {
+				calls += 1;
+				if calls == 2 {
+					futures::executor::block_on(observer.on_block_processed(&block)).unwrap();
+				}
+				Err(IndexingServiceError::StreamClosed)
+			});
+
+		let mut observer = MockBlockchainObserver::new();
+		observer.expect_on_block_processed().returning(|_| Ok(()));
+		observer.expect_on_shutdown().returning(|| Ok(()));
+
+		let mut supervisor = Supervisor::new(Arc::new(indexing_service)).with_restart_policy(
+			RestartPolicy::default().delay(Duration::from_millis(1)).max_restarts(1),
+		);
+		supervisor.supervise(indexer("A"), Arc::new(observer));
+
+		let result = supervisor.run().await;
+		assert!(
+			matches!(result, Err(Error::GaveUp { restarts: 1, .. })),
+			"{:?}",
+			result
+		);
+		assert_eq!(3, supervisor.statuses()[0].1.error_count);
+	}
+
+	#[rstest]
+	#[tokio::test]
+	async fn shuts_all_indexers_down(observer: Arc<dyn BlockchainObserver>) {
+		let (failures, mut failed) = tokio::sync::mpsc::unbounded_channel();
+		let mut indexing_service = MockIndexingService::new();
+		indexing_service.expect_fetch_new_events().returning(move |_, _, _| {
+			failures.send(()).ok();
+			Err(IndexingServiceError::StreamClosed)
+		});
+
+		let mut supervisor = Supervisor::new(Arc::new(indexing_service))
+			.with_restart_policy(RestartPolicy::default().delay(Duration::from_secs(60)));
+		supervisor
+			.supervise(indexer("A"), observer.clone())
+			.supervise(indexer("B"), observer);
+
+		let supervisor = Arc::new(supervisor);
+		let handle = {
+			let supervisor = supervisor.clone();
+			tokio::spawn(async move { supervisor.run().await })
+		};
+
+		// Both indexers failed once
+		failed.recv().await;
+		failed.recv().await;
+		supervisor.shutdown();
+
+		let result = tokio::time::timeout(Duration::from_secs(1), handle).await;
+		assert!(result.expect("supervisor did not shut down").unwrap().is_ok());
+		for (_, status) in supervisor.statuses() {
+			assert_eq!(IndexerState::Stopped, status.state);
+		}
+	}
+}
@@ -0,0 +1,75 @@
+use std::time::Duration;
+
+/**
+ * Describes how the supervisor restarts an indexer that stopped with an error
+ */
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RestartPolicy {
+	delay: Duration,
+	max_restarts: Option<u32>,
+}
+
+impl Default for RestartPolicy {
+	fn default() -> Self {
+		Self {
+			delay: Duration::from_secs(5),
+			max_restarts: None,
+		}
+	}
+}
+
+impl RestartPolicy {
+	/**
+	 * Never restart, an indexer stopping with an error stays stopped
+	 */
+	pub fn never() -> Self {
+		Self::default().max_restarts(0)
+	}
+
+	/**
+	 * Modify the delay to wait before restarting a crashed indexer
+	 */
+	pub fn delay(mut self, delay: Duration) -> Self {
+		self.delay = delay;
+		self
+	}
+
+	/**
+	 * Give up after an indexer has been restarted `max_restarts` times without processing any
+	 * block in between
+	 */
+	pub fn max_restarts(mut self, max_restarts: u32) -> Self {
+		self.max_restarts = Some(max_restarts);
+		self
+	}
+
+	/**
+	 * Whether an indexer which has already been restarted `restarts` times should be restarted
+	 */
+	pub fn should_restart(&self, restarts: u32) -> bool {
+		!matches!(self.max_restarts, Some(max_restarts) if restarts >= max_restarts)
+	}
+
+	pub(super) fn restart_delay(&self) -> Duration {
+		self.delay
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use rstest::*;
+
+	#[rstest]
+	#[case(RestartPolicy::default(), 1000, true)]
+	#[case(RestartPolicy::never(), 0, false)]
+	#[case(RestartPolicy::default().max_restarts(2), 1, true)]
+	#[case(RestartPolicy::default().max_restarts(2), 2, false)]
+	fn restarts_according_to_the_policy(
+		#[case] policy: RestartPolicy,
+		#[case] restarts: u32,
+		#[case] expected: bool,
+	) {
+		assert_eq!(expected, policy.should_restart(restarts));
+	}
+}
@@ -0,0 +1,166 @@
+use crate::domain::*;
+use async_trait::async_trait;
+use serde::Serialize;
+use std::sync::RwLock;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum State {
+	Starting,
+	Running,
+	Restarting,
+	Stopped,
+	Failed,
+}
+
+/**
+ * Snapshot of the health of a supervised indexer
+ */
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Status {
+	pub state: State,
+	pub connected: bool,
+	pub last_block: Option<u64>,
+	/** Blocks processed since the indexer started */
+	pub processed_blocks: u64,
+	pub error_count: u32,
+	pub last_error: Option<String>,
+	/** Events which could not be decoded since the indexer started */
+	pub undecodable_events: u64,
+}
+
+impl Default for Status {
+	fn default() -> Self {
+		Self {
+			state: State::Starting,
+			connected: false,
+			last_block: None,
+			processed_blocks: 0,
+			error_count: 0,
+			last_error: None,
+			undecodable_events: 0,
+		}
+	}
+}
+
+/**
+ * Observer keeping the status of an indexer up to date with its stream
+ */
+#[derive(Default)]
+pub(super) struct Tracker(RwLock<Status>);
+
+impl Tracker {
+	pub fn status(&self) -> Status {
+		self.0.read().expect("status lock is poisoned").clone()
+	}
+
+	pub fn update<F: FnOnce(&mut Status)>(&self, update: F) {
+		update(&mut self.0.write().expect("status lock is poisoned"))
+	}
+
+	fn set_connected(&self, connected: bool) {
+		self.update(|status| {
+			status.connected = connected;
+			if connected {
+				status.state = State::Running;
+			}
+		});
+	}
+}
+
+#[async_trait]
+impl BlockchainObserver for Tracker {
+	async fn on_connect(&self, _indexer_id: &IndexerId) -> Result<(), BlockchainObserverError> {
+		self.set_connected(true);
+		Ok(())
+	}
+
+	async fn on_undecodable_event(
+		&self,
+		_event: &RawEvent,
+		_metadata: &EventMetadata,
+		_error: &DecodingError,
+	) -> Result<(), BlockchainObserverError> {
+		self.update(|status| status.undecodable_events += 1);
+		Ok(())
+	}
+
+	async fn on_reorg(&self, new_head: &BlockHeader) -> Result<(), BlockchainObserverError> {
+		self.update(|status| status.last_block = Some(new_head.number));
+		Ok(())
+	}
+
+	async fn on_block_processed(&self, block: &BlockHeader) -> Result<(), BlockchainObserverError> {
+		self.update(|status| {
+			status.last_block = Some(block.number);
+			status.processed_blocks += 1;
+		});
+		Ok(())
+	}
+
+	async fn on_disconnect(&self, _indexer_id: &IndexerId) -> Result<(), BlockchainObserverError> {
+		self.set_connected(false);
+		Ok(())
+	}
+
+	async fn on_reconnect(&self, _indexer_id: &IndexerId) -> Result<(), BlockchainObserverError> {
+		self.set_connected(true);
+		Ok(())
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use std::str::FromStr;
+
+	#[tokio::test]
+	async fn tracks_the_connection() {
+		let tracker = Tracker::default();
+		assert_eq!(State::Starting, tracker.status().state);
+
+		tracker.on_connect(&"ID".into()).await.unwrap();
+		assert!(tracker.status().connected);
+		assert_eq!(State::Running, tracker.status().state);
+
+		tracker.on_disconnect(&"ID".into()).await.unwrap();
+		assert!(!tracker.status().connected);
+
+		tracker.on_reconnect(&"ID".into()).await.unwrap();
+		assert!(tracker.status().connected);
+	}
+
+	#[tokio::test]
+	async fn tracks_the_last_block() {
+		let tracker = Tracker::default();
+		let block_hash = BlockHash::from_str("0x1234").unwrap();
+
+		tracker
+			.on_block_processed(&BlockHeader::new(block_hash.clone(), 42))
+			.await
+			.unwrap();
+		assert_eq!(Some(42), tracker.status().last_block);
+		assert_eq!(1, tracker.status().processed_blocks);
+
+		tracker.on_reorg(&BlockHeader::new(block_hash, 40)).await.unwrap();
+		assert_eq!(Some(40), tracker.status().last_block);
+	}
+
+	#[tokio::test]
+	async fn counts_undecodable_events() {
+		let tracker = Tracker::default();
+
+		for _ in 0..2 {
+			tracker
+				.on_undecodable_event(
+					&Default::default(),
+					&Default::default(),
+					&DecodingError::Unsupported,
+				)
+				.await
+				.unwrap();
+		}
+
+		assert_eq!(2, tracker.status().undecodable_events);
+	}
+}
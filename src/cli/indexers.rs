@@ -0,0 +1,111 @@
+use super::{
+	output::{self, DeadLetterRow, IndexerRow, StatusRow},
+	run::{checkpoint_store, dead_letter_store},
+	OutputFormat, Result,
+};
+use marketplace_indexer::{application::Config, domain::*, infrastructure::ApibaraClient};
+use std::sync::Arc;
+
+pub async fn create(
+	config: &Config,
+	apibara_client: Arc<ApibaraClient>,
+	id: Option<String>,
+	format: OutputFormat,
+) -> Result<()> {
+	let indexer_configs: Vec<_> = config
+		.indexers
+		.iter()
+		.filter(|indexer| id.iter().all(|id| indexer.id == IndexerId::from(id)))
+		.collect();
+
+	if let (Some(id), true) = (&id, indexer_configs.is_empty()) {
+		return Err(format!("indexer `{}` is not configured", id).into());
+	}
+
+	let mut rows = Vec::new();
+	for indexer_config in indexer_configs {
+		let indexer = indexer_config
+			.builder(apibara_client.clone())
+			.build(indexer_config.id.clone())
+			.await?;
+		rows.push(IndexerRow::from(indexer));
+	}
+
+	output::print(&rows, format);
+	Ok(())
+}
+
+pub async fn get(
+	apibara_client: Arc<ApibaraClient>,
+	id: String,
+	format: OutputFormat,
+) -> Result<()> {
+	let indexer = apibara_client
+		.by_id(&id.clone().into())
+		.await?
+		.ok_or_else(|| format!("indexer `{}` not found", id))?;
+
+	output::print(&[IndexerRow::from(indexer)], format);
+	Ok(())
+}
+
+pub async fn list(
+	apibara_client: Arc<ApibaraClient>,
+	network: Option<Network>,
+	format: OutputFormat,
+) -> Result<()> {
+	let rows: Vec<IndexerRow> = apibara_client
+		.list()
+		.await?
+		.into_iter()
+		.filter(|indexer| network.iter().all(|network| &indexer.network == network))
+		.map(IndexerRow::from)
+		.collect();
+
+	output::print(&rows, format);
+	Ok(())
+}
+
+pub async fn delete(apibara_client: Arc<ApibaraClient>, id: String) -> Result<()> {
+	apibara_client.delete(&id.into()).await?;
+	Ok(())
+}
+
+pub async fn status(
+	config: &Config,
+	apibara_client: Arc<ApibaraClient>,
+	format: OutputFormat,
+) -> Result<()> {
+	let checkpoint_store = checkpoint_store(config).await?;
+
+	let mut rows = Vec::new();
+	for indexer_config in &config.indexers {
+		let indexer = apibara_client.by_id(&indexer_config.id).await?;
+		let checkpoint = match &checkpoint_store {
+			Some(store) => store.load(&indexer_config.id).await?,
+			None => None,
+		};
+
+		rows.push(StatusRow {
+			id: indexer_config.id.to_string(),
+			network: indexer_config.network.to_string(),
+			registered: indexer.is_some(),
+			index_from_block: indexer.as_ref().map(|indexer| indexer.index_from_block),
+			indexed_to_block: indexer.and_then(|indexer| indexer.indexed_to_block),
+			checkpoint: checkpoint.map(|checkpoint| checkpoint.number),
+		});
+	}
+
+	output::print(&rows, format);
+	Ok(())
+}
+
+pub async fn dead_letters(config: &Config, id: String, format: OutputFormat) -> Result<()> {
+	let store = dead_letter_store(config).await?.ok_or("no dead letter store is configured")?;
+
+	let rows: Vec<DeadLetterRow> =
+		store.list(&id.into()).await?.into_iter().map(DeadLetterRow::from).collect();
+
+	output::print(&rows, format);
+	Ok(())
+}
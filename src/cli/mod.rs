@@ -0,0 +1,212 @@
+mod indexers;
+mod output;
+mod run;
+
+use clap::{Parser, Subcommand};
+use marketplace_indexer::{
+	application::Config,
+	domain::Network,
+	infrastructure::{ApibaraClient, ApibaraRecorder},
+};
+pub use output::Format as OutputFormat;
+use std::{path::PathBuf, sync::Arc, time::Duration};
+
+type Result<T> = std::result::Result<T, Box<dyn std::error::Error>>;
+
+#[derive(Debug, Parser)]
+#[clap(version, about = "Onlydust marketplace on-chain events indexer")]
+pub struct Cli {
+	/// Path to the indexers configuration file
+	#[clap(
+		long,
+		global = true,
+		env = "INDEXER_CONFIG",
+		default_value = "indexers.toml"
+	)]
+	config: PathBuf,
+
+	/// Output format of the management commands
+	#[clap(long, short, global = true, value_enum, default_value = "table")]
+	output: OutputFormat,
+
+	#[clap(subcommand)]
+	command: Option<Command>,
+}
+
+#[derive(Debug, Subcommand)]
+enum Command {
+	/// Run the configured indexers (default)
+	Run {
+		/// Record the indexing streams in `<DIR>/<indexer id>.jsonl`
+		#[clap(long, value_name = "DIR")]
+		record: Option<PathBuf>,
+		/// Serve the metrics of the indexers on `http://<ADDRESS>/metrics`, their liveness on
+		/// `/healthz`, their readiness on `/readyz` and their status on `/status`
+		#[clap(long, env = "HTTP_ADDRESS", value_name = "ADDRESS")]
+		http_address: Option<String>,
+		/// Consider the indexers dead when they receive no block for this many seconds
+		#[clap(
+			long,
+			env = "LIVENESS_WINDOW",
+			value_name = "SECONDS",
+			default_value = "300"
+		)]
+		liveness_window: u64,
+		/// Reconnect an indexer when the Apibara server sends nothing for this many seconds
+		#[clap(long, env = "IDLE_TIMEOUT", value_name = "SECONDS")]
+		idle_timeout: Option<u64>,
+	},
+	/// Create the configured indexers on the Apibara server, or only the given one
+	Create { id: Option<String> },
+	/// Show an indexer registered on the Apibara server
+	Get { id: String },
+	/// List the indexers registered on the Apibara server
+	List {
+		/// Only list the indexers of this network, such as `starknet-goerli`
+		#[clap(long)]
+		network: Option<Network>,
+	},
+	/// Delete an indexer from the Apibara server
+	Delete { id: String },
+	/// Show the status of the configured indexers
+	Status,
+	/// Replay a recorded indexing stream through the observers of a configured indexer
+	Replay { file: PathBuf, id: String },
+	/// Manage the events an indexer could not decode
+	DeadLetters {
+		#[clap(subcommand)]
+		command: DeadLettersCommand,
+	},
+}
+
+#[derive(Debug, Subcommand)]
+enum DeadLettersCommand {
+	/// List the events an indexer could not decode
+	List { id: String },
+	/// Decode the events of an indexer again and hand the decoded ones to its observers
+	Replay { id: String },
+}
+
+impl Cli {
+	pub async fn execute(mut self) -> Result<()> {
+		let command = self.command.take().unwrap_or(Command::Run {
+			record: None,
+			http_address: None,
+			liveness_window: 300,
+			idle_timeout: None,
+		});
+
+		match command {
+			Command::Run {
+				record,
+				http_address,
+				liveness_window,
+				idle_timeout,
+			} => {
+				let config = self.config()?;
+				let mut apibara_client = ApibaraClient::default().await?;
+				if let Some(directory) = record {
+					apibara_client = apibara_client.with_recorder(ApibaraRecorder::new(directory));
+				}
+				if let Some(idle_timeout) = idle_timeout {
+					apibara_client =
+						apibara_client.with_idle_timeout(Duration::from_secs(idle_timeout));
+				}
+				for (contract_address, abi) in run::abis(&config)? {
+					apibara_client = apibara_client.with_abi(contract_address, abi);
+				}
+				let monitoring = http_address
+					.map(|address| {
+						run::bind_monitoring(&address, Duration::from_secs(liveness_window))
+					})
+					.transpose()?;
+				if let Some(monitoring) = &monitoring {
+					apibara_client = apibara_client
+						.with_metrics(monitoring.metrics.clone())
+						.with_health(monitoring.health.clone());
+				}
+				run::run(config, Arc::new(apibara_client), monitoring).await
+			},
+			Command::Create { id } =>
+				indexers::create(
+					&self.config()?,
+					self.apibara_client().await?,
+					id,
+					self.output,
+				)
+				.await,
+			Command::Get { id } =>
+				indexers::get(self.apibara_client().await?, id, self.output).await,
+			Command::List { network } =>
+				indexers::list(self.apibara_client().await?, network, self.output).await,
+			Command::Delete { id } => indexers::delete(self.apibara_client().await?, id).await,
+			Command::Status =>
+				indexers::status(&self.config()?, self.apibara_client().await?, self.output).await,
+			Command::Replay { file, id } => run::replay(self.config()?, file, id.into()).await,
+			Command::DeadLetters {
+				command: DeadLettersCommand::List { id },
+			} => indexers::dead_letters(&self.config()?, id, self.output).await,
+			Command::DeadLetters {
+				command: DeadLettersCommand::Replay { id },
+			} => run::replay_dead_letters(self.config()?, id.into()).await,
+		}
+	}
+
+	fn config(&self) -> Result<Config> {
+		Ok(Config::from_file(&self.config)?)
+	}
+
+	async fn apibara_client(&self) -> Result<Arc<ApibaraClient>> {
+		Ok(Arc::new(ApibaraClient::default().await?))
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use rstest::*;
+
+	#[test]
+	fn runs_without_subcommand() {
+		let cli = Cli::try_parse_from(["marketplace-indexer"]).unwrap();
+		assert!(cli.command.is_none());
+		assert_eq!(OutputFormat::Table, cli.output);
+	}
+
+	#[rstest]
+	#[case(&["marketplace-indexer", "run"])]
+	#[case(&["marketplace-indexer", "run", "--record", "recordings"])]
+	#[case(&["marketplace-indexer", "run", "--http-address", "0.0.0.0:9090", "--liveness-window", "60"])]
+	#[case(&["marketplace-indexer", "run", "--idle-timeout", "120"])]
+	#[case(&["marketplace-indexer", "create"])]
+	#[case(&["marketplace-indexer", "create", "ID"])]
+	#[case(&["marketplace-indexer", "get", "ID"])]
+	#[case(&["marketplace-indexer", "list", "--output", "json"])]
+	#[case(&["marketplace-indexer", "list", "--network", "starknet-goerli"])]
+	#[case(&["marketplace-indexer", "delete", "ID"])]
+	#[case(&["marketplace-indexer", "--config", "other.toml", "status"])]
+	#[case(&["marketplace-indexer", "replay", "indexer.jsonl", "ID"])]
+	#[case(&["marketplace-indexer", "dead-letters", "list", "ID", "--output", "json"])]
+	#[case(&["marketplace-indexer", "dead-letters", "replay", "ID"])]
+	fn subcommands_can_be_parsed(#[case] args: &[&str]) {
+		let result = Cli::try_parse_from(args);
+		assert!(result.is_ok(), "{}", result.err().unwrap());
+	}
+
+	#[rstest]
+	#[case(&["marketplace-indexer", "get"])]
+	#[case(&["marketplace-indexer", "delete"])]
+	#[case(&["marketplace-indexer", "replay", "indexer.jsonl"])]
+	#[case(&["marketplace-indexer", "dead-letters"])]
+	#[case(&["marketplace-indexer", "dead-letters", "ID"])]
+	#[case(&["marketplace-indexer", "dead-letters", "replay"])]
+	#[case(&["marketplace-indexer", "list", "--output", "xml"])]
+	#[case(&["marketplace-indexer", "list", "--network", "goerli"])]
+	#[case(&["marketplace-indexer", "run", "--liveness-window", "soon"])]
+	#[case(&["marketplace-indexer", "run", "--idle-timeout", "-1"])]
+	#[case(&["marketplace-indexer", "run", "--metrics-address", "0.0.0.0:9090"])]
+	#[case(&["marketplace-indexer", "unknown"])]
+	fn invalid_arguments_are_rejected(#[case] args: &[&str]) {
+		assert!(Cli::try_parse_from(args).is_err());
+	}
+}
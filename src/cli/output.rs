@@ -0,0 +1,258 @@
+use clap::ValueEnum;
+use marketplace_indexer::domain::*;
+use serde::Serialize;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Format {
+	Table,
+	Json,
+}
+
+/**
+ * Something that can be printed as a table row, or as a JSON object
+ */
+pub trait Row: Serialize {
+	fn headers() -> Vec<&'static str>;
+	fn cells(&self) -> Vec<String>;
+}
+
+#[derive(Serialize)]
+pub struct IndexerRow {
+	id: String,
+	network: String,
+	index_from_block: u64,
+	indexed_to_block: Option<u64>,
+	filters: Vec<FilterRow>,
+}
+
+#[derive(Serialize)]
+struct FilterRow {
+	contract_address: String,
+	event_name: String,
+}
+
+impl From<Indexer> for IndexerRow {
+	fn from(indexer: Indexer) -> Self {
+		Self {
+			id: indexer.id.to_string(),
+			network: indexer.network.to_string(),
+			index_from_block: indexer.index_from_block,
+			indexed_to_block: indexer.indexed_to_block,
+			filters: indexer
+				.filters
+				.into_iter()
+				.map(|filter| FilterRow {
+					contract_address: filter.contract_address.to_string(),
+					event_name: filter.event_name,
+				})
+				.collect(),
+		}
+	}
+}
+
+impl Row for IndexerRow {
+	fn headers() -> Vec<&'static str> {
+		vec!["ID", "NETWORK", "FROM BLOCK", "INDEXED TO", "FILTERS"]
+	}
+
+	fn cells(&self) -> Vec<String> {
+		vec![
+			self.id.clone(),
+			self.network.clone(),
+			self.index_from_block.to_string(),
+			optional(self.indexed_to_block),
+			self.filters
+				.iter()
+				.map(|filter| format!("{}:{}", filter.contract_address, filter.event_name))
+				.collect::<Vec<_>>()
+				.join(", "),
+		]
+	}
+}
+
+#[derive(Serialize)]
+pub struct StatusRow {
+	pub id: String,
+	pub network: String,
+	pub registered: bool,
+	pub index_from_block: Option<u64>,
+	pub indexed_to_block: Option<u64>,
+	pub checkpoint: Option<u64>,
+}
+
+impl Row for StatusRow {
+	fn headers() -> Vec<&'static str> {
+		vec![
+			"ID",
+			"NETWORK",
+			"REGISTERED",
+			"FROM BLOCK",
+			"INDEXED TO",
+			"CHECKPOINT",
+		]
+	}
+
+	fn cells(&self) -> Vec<String> {
+		vec![
+			self.id.clone(),
+			self.network.clone(),
+			if self.registered { "yes" } else { "no" }.to_owned(),
+			optional(self.index_from_block),
+			optional(self.indexed_to_block),
+			optional(self.checkpoint),
+		]
+	}
+}
+
+#[derive(Serialize)]
+pub struct DeadLetterRow {
+	block_number: u64,
+	block_hash: String,
+	log_index: u64,
+	contract_address: String,
+	topics: Vec<String>,
+	data: Vec<String>,
+	error: String,
+}
+
+impl From<DeadLetter> for DeadLetterRow {
+	fn from(dead_letter: DeadLetter) -> Self {
+		let to_strings = |values: Vec<HexaString>| values.iter().map(ToString::to_string).collect();
+
+		Self {
+			block_number: dead_letter.metadata.block_number,
+			block_hash: dead_letter.metadata.block_hash.to_string(),
+			log_index: dead_letter.metadata.log_index,
+			contract_address: dead_letter.metadata.contract_address.to_string(),
+			topics: to_strings(dead_letter.event.topics),
+			data: to_strings(dead_letter.event.data),
+			error: dead_letter.error.to_string(),
+		}
+	}
+}
+
+impl Row for DeadLetterRow {
+	fn headers() -> Vec<&'static str> {
+		vec!["BLOCK", "LOG", "CONTRACT", "TOPICS", "DATA", "ERROR"]
+	}
+
+	fn cells(&self) -> Vec<String> {
+		vec![
+			self.block_number.to_string(),
+			self.log_index.to_string(),
+			self.contract_address.clone(),
+			self.topics.join(", "),
+			self.data.join(", "),
+			self.error.clone(),
+		]
+	}
+}
+
+fn optional(value: Option<u64>) -> String {
+	value.map(|value| value.to_string()).unwrap_or_else(|| String::from("-"))
+}
+
+pub fn print<R: Row>(rows: &[R], format: Format) {
+	println!("{}", render(rows, format));
+}
+
+fn render<R: Row>(rows: &[R], format: Format) -> String {
+	match format {
+		Format::Json => serde_json::to_string_pretty(rows).expect("rows can be serialized"),
+		Format::Table => table(R::headers(), rows.iter().map(|row| row.cells()).collect()),
+	}
+}
+
+fn table(headers: Vec<&str>, rows: Vec<Vec<String>>) -> String {
+	let widths: Vec<usize> = headers
+		.iter()
+		.enumerate()
+		.map(|(i, header)| {
+			rows.iter()
+				.map(|row| row[i].chars().count())
+				.max()
+				.unwrap_or(0)
+				.max(header.len())
+		})
+		.collect();
+
+	let line = |cells: Vec<&str>| {
+		cells
+			.iter()
+			.zip(&widths)
+			.map(|(cell, width)| format!("{:width$}", cell, width = width))
+			.collect::<Vec<_>>()
+			.join("  ")
+			.trim_end()
+			.to_owned()
+	};
+
+	std::iter::once(line(headers.clone()))
+		.chain(rows.iter().map(|row| line(row.iter().map(String::as_str).collect())))
+		.collect::<Vec<_>>()
+		.join("\n")
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use std::str::FromStr;
+
+	fn rows() -> Vec<IndexerRow> {
+		vec![
+			Indexer::new(
+				"indexer-goerli".into(),
+				Network::Starknet(StarknetChain::Goerli),
+				291345,
+				vec![EventFilter::new(
+					ContractAddress::from_str("0x1234").unwrap(),
+					"Event",
+				)],
+			)
+			.indexed_to_block(291400)
+			.into(),
+			Indexer::new(
+				"ID".into(),
+				Network::Starknet(StarknetChain::Devnet),
+				0,
+				Vec::new(),
+			)
+			.into(),
+		]
+	}
+
+	#[test]
+	fn rows_can_be_rendered_as_a_table() {
+		assert_eq!(
+			"ID              NETWORK          FROM BLOCK  INDEXED TO  FILTERS
+indexer-goerli  starknet-goerli  291345      291400      0x1234:Event
+ID              starknet-devnet  0           -",
+			render(&rows(), Format::Table)
+		);
+	}
+
+	#[test]
+	fn rows_can_be_rendered_as_json() {
+		let json: serde_json::Value = serde_json::from_str(&render(&rows(), Format::Json)).unwrap();
+
+		assert_eq!(
+			serde_json::json!([
+				{
+					"id": "indexer-goerli",
+					"network": "starknet-goerli",
+					"index_from_block": 291345,
+					"indexed_to_block": 291400,
+					"filters": [{ "contract_address": "0x1234", "event_name": "Event" }]
+				},
+				{
+					"id": "ID",
+					"network": "starknet-devnet",
+					"index_from_block": 0,
+					"indexed_to_block": null,
+					"filters": []
+				}
+			]),
+			json
+		);
+	}
+}
@@ -0,0 +1,314 @@
+use super::Result;
+use marketplace_indexer::{
+	application::{Config, IndexerConfig, ObserverKind, StoreConfig, Supervisor},
+	domain::*,
+	infrastructure::{
+		ApibaraClient, ApibaraDeadLetterDecoder, ApibaraReplay, ContractAbi, FileCheckpointStore,
+		FileDeadLetterStore, GithubIdentifierProjection, Health, HttpServer, Metrics,
+		PostgresClient, SqliteClient,
+	},
+};
+use std::{path::PathBuf, sync::Arc, time::Duration};
+#[cfg(unix)]
+use tokio::signal::unix::{signal, SignalKind};
+use tokio_util::sync::CancellationToken;
+
+/**
+ * Create the configured indexers and run them until interrupted
+ */
+pub async fn run(
+	config: Config,
+	apibara_client: Arc<ApibaraClient>,
+	monitoring: Option<Monitoring>,
+) -> Result<()> {
+	let checkpoint_store = checkpoint_store(&config).await?;
+	let dead_letter_store = dead_letter_store(&config).await?;
+	let postgres_client = postgres_client(&config).await?;
+
+	let mut supervisor = Supervisor::new(apibara_client.clone());
+	for indexer_config in &config.indexers {
+		let checkpoint_store = checkpoint_store
+			.clone()
+			.filter(|_| indexer_config.observers.contains(&ObserverKind::Checkpoint));
+		let dead_letter_store = dead_letter_store
+			.clone()
+			.filter(|_| indexer_config.observers.contains(&ObserverKind::DeadLetter));
+
+		let mut builder = indexer_config.builder(apibara_client.clone());
+		if let Some(checkpoint_store) = &checkpoint_store {
+			builder.resume_from_checkpoint(checkpoint_store.clone());
+		}
+
+		let indexer = builder.build(indexer_config.id.clone()).await?;
+
+		let observers = observers(
+			indexer_config,
+			&indexer.id,
+			checkpoint_store,
+			dead_letter_store,
+			postgres_client.clone(),
+			monitoring.as_ref(),
+		);
+		supervisor.supervise(indexer, observers);
+	}
+
+	let supervisor = Arc::new(supervisor);
+	if let Some(monitoring) = monitoring {
+		monitoring.serve(supervisor.clone());
+	}
+	{
+		let supervisor = supervisor.clone();
+		tokio::spawn(async move {
+			shutdown_signal().await;
+			supervisor.shutdown();
+		});
+	}
+
+	supervisor.run().await?;
+	Ok(())
+}
+
+/**
+ * Replay a recorded stream through the observers of the configured indexer.
+ * Checkpoints are left untouched, as the replayed blocks may be far behind the live ones, and
+ * dead letters are not recorded again.
+ */
+pub async fn replay(config: Config, file: PathBuf, id: IndexerId) -> Result<()> {
+	let indexer_config = config
+		.indexers
+		.iter()
+		.find(|indexer| indexer.id == id)
+		.ok_or_else(|| format!("indexer `{}` is not configured", id))?;
+
+	let postgres_client = postgres_client(&config).await?;
+	let observers = observers(indexer_config, &id, None, None, postgres_client, None);
+
+	let indexer = Indexer::new(
+		id,
+		indexer_config.network.clone(),
+		indexer_config.start_at_block,
+		indexer_config.filters.clone(),
+	);
+	let mut replay = ApibaraReplay::new(file);
+	for (contract_address, abi) in abis(&config)? {
+		replay = replay.with_abi(contract_address, abi);
+	}
+	let shutdown = CancellationToken::new();
+	{
+		let shutdown = shutdown.clone();
+		tokio::spawn(async move {
+			shutdown_signal().await;
+			shutdown.cancel();
+		});
+	}
+	replay.fetch_new_events(&indexer, observers.clone(), shutdown).await?;
+	observers.on_shutdown().await?;
+	Ok(())
+}
+
+/**
+ * Decode the dead letters of the configured indexer again, once its decoder or ABIs are fixed.
+ * The decoded events are handed to the observers of the indexer, as in `replay`, and removed
+ * from the dead letter store. The others are kept.
+ */
+pub async fn replay_dead_letters(config: Config, id: IndexerId) -> Result<()> {
+	let indexer_config = config
+		.indexers
+		.iter()
+		.find(|indexer| indexer.id == id)
+		.ok_or_else(|| format!("indexer `{}` is not configured", id))?;
+	let store = dead_letter_store(&config).await?.ok_or("no dead letter store is configured")?;
+
+	let postgres_client = postgres_client(&config).await?;
+	let observers = observers(indexer_config, &id, None, None, postgres_client, None);
+
+	let mut decoder = ApibaraDeadLetterDecoder::default();
+	for (contract_address, abi) in abis(&config)? {
+		decoder = decoder.with_abi(contract_address, abi);
+	}
+
+	let dead_letters = store.list(&id).await?;
+	let mut decoded = 0;
+	for dead_letter in &dead_letters {
+		match decoder.decode(&indexer_config.network, dead_letter) {
+			Ok(event) => {
+				observers.on_new_event(&event, &dead_letter.metadata).await?;
+				store.remove(&id, &dead_letter.metadata).await?;
+				decoded += 1;
+			},
+			Err(error) => log::warn!(
+				"Still unable to decode the event {} of block {}: {}",
+				dead_letter.metadata.log_index,
+				dead_letter.metadata.block_number,
+				error
+			),
+		}
+	}
+	observers.on_shutdown().await?;
+	store.flush().await?;
+
+	log::info!(
+		"Replayed {} of the {} dead letters of indexer `{}`",
+		decoded,
+		dead_letters.len(),
+		id
+	);
+	Ok(())
+}
+
+/**
+ * Wait for SIGINT or SIGTERM.
+ * The indexers then finish and ack the block they are handling before stopping.
+ */
+async fn shutdown_signal() {
+	#[cfg(unix)]
+	let terminate = async {
+		match signal(SignalKind::terminate()) {
+			Ok(mut terminate) => {
+				terminate.recv().await;
+			},
+			Err(_) => std::future::pending().await,
+		}
+	};
+	#[cfg(not(unix))]
+	let terminate = std::future::pending::<()>();
+
+	tokio::select! {
+		_ = tokio::signal::ctrl_c() => (),
+		_ = terminate => (),
+	};
+	log::info!("🛑 Shutting down once the current blocks are handled");
+}
+
+/**
+ * Metrics, health and status of the indexers, served over HTTP
+ */
+pub struct Monitoring {
+	pub metrics: Arc<Metrics>,
+	pub health: Arc<Health>,
+	server: HttpServer,
+}
+
+/**
+ * Listen on the address, the indexers are served once they are supervised
+ */
+pub fn bind_monitoring(address: &str, liveness_window: Duration) -> Result<Monitoring> {
+	let metrics = Arc::new(Metrics::default());
+	let health = Arc::new(Health::new(liveness_window));
+	let server = HttpServer::bind(address)?
+		.with_metrics(metrics.clone())
+		.with_health(health.clone());
+	Ok(Monitoring {
+		metrics,
+		health,
+		server,
+	})
+}
+
+impl Monitoring {
+	/**
+	 * Start serving the metrics, the health and the status of the indexers in the background
+	 */
+	fn serve(self, supervisor: Arc<Supervisor>) {
+		let server = self.server.with_supervisor(supervisor);
+		tokio::spawn(async move {
+			if let Err(error) = server.run().await {
+				log::error!("{}", error);
+			}
+		});
+	}
+}
+
+pub fn abis(config: &Config) -> Result<Vec<(ContractAddress, ContractAbi)>> {
+	let abis = config
+		.abis
+		.iter()
+		.map(|abi| {
+			ContractAbi::from_file(&abi.path).map(|parsed| (abi.contract_address.clone(), parsed))
+		})
+		.collect::<std::result::Result<_, _>>()?;
+	Ok(abis)
+}
+
+pub async fn checkpoint_store(config: &Config) -> Result<Option<Arc<dyn CheckpointStore>>> {
+	let store: Arc<dyn CheckpointStore> = match &config.checkpoint_store {
+		None => return Ok(None),
+		Some(StoreConfig::File { path }) => Arc::new(FileCheckpointStore::new(path)),
+		Some(StoreConfig::Sqlite { database_url }) =>
+			Arc::new(SqliteClient::connect(database_url).await?),
+	};
+	Ok(Some(store))
+}
+
+pub async fn dead_letter_store(config: &Config) -> Result<Option<Arc<dyn DeadLetterStore>>> {
+	let store: Arc<dyn DeadLetterStore> = match &config.dead_letter_store {
+		None => return Ok(None),
+		Some(StoreConfig::File { path }) => Arc::new(FileDeadLetterStore::new(path)),
+		Some(StoreConfig::Sqlite { database_url }) =>
+			Arc::new(SqliteClient::connect(database_url).await?),
+	};
+	Ok(Some(store))
+}
+
+async fn postgres_client(config: &Config) -> Result<Option<Arc<PostgresClient>>> {
+	let needs_postgres = config
+		.indexers
+		.iter()
+		.any(|indexer| indexer.observers.contains(&ObserverKind::GithubIdentifierProjection));
+
+	if !needs_postgres {
+		return Ok(None);
+	}
+
+	Ok(Some(Arc::new(PostgresClient::default().await?)))
+}
+
+fn observers(
+	config: &IndexerConfig,
+	indexer_id: &IndexerId,
+	checkpoint_store: Option<Arc<dyn CheckpointStore>>,
+	dead_letter_store: Option<Arc<dyn DeadLetterStore>>,
+	postgres_client: Option<Arc<PostgresClient>>,
+	monitoring: Option<&Monitoring>,
+) -> Arc<dyn BlockchainObserver> {
+	let mut observers: Vec<Arc<dyn BlockchainObserver>> = Vec::new();
+
+	if let Some(monitoring) = monitoring {
+		observers.push(Arc::new(
+			monitoring.metrics.observer(indexer_id, &config.network),
+		));
+		observers.push(Arc::new(monitoring.health.observer(indexer_id)));
+	}
+
+	for kind in &config.observers {
+		match kind {
+			ObserverKind::Logger => observers.push(Arc::new(BlockchainLogger::default())),
+			ObserverKind::GithubIdentifierProjection =>
+				if let Some(postgres_client) = &postgres_client {
+					observers.push(Arc::new(GithubIdentifierProjection::new(
+						postgres_client.clone(),
+						indexer_id.clone(),
+					)));
+				},
+			ObserverKind::DeadLetter =>
+				if let Some(dead_letter_store) = &dead_letter_store {
+					observers.push(Arc::new(DeadLetterRecorder::new(
+						dead_letter_store.clone(),
+						indexer_id.clone(),
+					)));
+				},
+			ObserverKind::Checkpoint => (),
+		}
+	}
+
+	// Record checkpoints last, so that a block is only checkpointed once every projection handled
+	// it
+	if let Some(checkpoint_store) = checkpoint_store {
+		observers.push(Arc::new(CheckpointRecorder::new(
+			checkpoint_store,
+			indexer_id.clone(),
+		)));
+	}
+
+	Arc::new(BlockchainObserverComposite::new(observers))
+}
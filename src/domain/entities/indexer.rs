@@ -22,6 +22,8 @@ pub struct Indexer {
 	pub id: Id,
 	pub network: Network,
 	pub index_from_block: u64,
+	/** Last block indexed by the indexing server, if it indexed any block yet */
+	pub indexed_to_block: Option<u64>,
 	pub filters: Vec<EventFilter>,
 }
 
@@ -31,9 +33,18 @@ impl Indexer {
 			id,
 			network,
 			index_from_block,
+			indexed_to_block: None,
 			filters,
 		}
 	}
+
+	/**
+	 * Set the last block indexed by the indexing server
+	 */
+	pub fn indexed_to_block(mut self, block_number: u64) -> Self {
+		self.indexed_to_block = Some(block_number);
+		self
+	}
 }
 
 #[cfg(test)]
@@ -56,6 +67,7 @@ mod test {
 		assert_eq!(String::from("123"), indexer.id.to_string());
 		assert_eq!(Network::Starknet(StarknetChain::Devnet), indexer.network);
 		assert_eq!(42, indexer.index_from_block);
+		assert_eq!(None, indexer.indexed_to_block);
 		assert_eq!(
 			vec![
 				EventFilter::new(ContractAddress::from_str("0x1234").unwrap(), "event1"),
@@ -65,6 +77,19 @@ mod test {
 		);
 	}
 
+	#[test]
+	fn indexer_progress_can_be_set() {
+		let indexer = Indexer::new(
+			IndexerId::from("123"),
+			Network::Starknet(StarknetChain::Devnet),
+			42,
+			Vec::new(),
+		)
+		.indexed_to_block(1234);
+
+		assert_eq!(Some(1234), indexer.indexed_to_block);
+	}
+
 	#[test]
 	fn id_can_be_created_from_and_transformed_into_a_string() {
 		assert_eq!("11", Id::from("11").to_string());
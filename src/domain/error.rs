@@ -5,4 +5,8 @@ use thiserror::Error;
 pub enum Error {
 	#[error(transparent)]
 	IndexerRepository(#[from] IndexerRepositoryError),
+	#[error(transparent)]
+	CheckpointStore(#[from] CheckpointStoreError),
+	#[error("indexer `{0}` already exists")]
+	IndexerAlreadyExists(IndexerId),
 }
@@ -15,6 +15,8 @@ pub use error::Error;
 
 mod obervers;
 pub use obervers::{
-	Logger as BlockchainLogger, MockObserver as MockBlockchainObserver,
-	Observer as BlockchainObserver, ObserverComposite as BlockchainObserverComposite,
+	CheckpointRecorder, DeadLetterRecorder, Error as BlockchainObserverError,
+	InMemory as InMemoryBlockchainObserver, Logger as BlockchainLogger,
+	MockObserver as MockBlockchainObserver, Observer as BlockchainObserver,
+	ObserverComposite as BlockchainObserverComposite,
 };
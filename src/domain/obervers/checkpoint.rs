@@ -0,0 +1,106 @@
+use std::sync::Arc;
+
+use super::*;
+
+/**
+ * Saves the last block fully processed by the indexer into a checkpoint store.
+ * On chain reorg, the checkpoint is moved back to the new head if it was above it.
+ */
+pub struct CheckpointRecorder {
+	store: Arc<dyn CheckpointStore>,
+	indexer_id: IndexerId,
+}
+
+impl CheckpointRecorder {
+	pub fn new(store: Arc<dyn CheckpointStore>, indexer_id: IndexerId) -> Self {
+		Self { store, indexer_id }
+	}
+}
+
+#[async_trait]
+impl Observer for CheckpointRecorder {
+	async fn on_block_processed(&self, block: &BlockHeader) -> Result<()> {
+		self.store.save(&self.indexer_id, block).await?;
+		Ok(())
+	}
+
+	async fn on_reorg(&self, new_head: &BlockHeader) -> Result<()> {
+		match self.store.load(&self.indexer_id).await? {
+			Some(checkpoint) if checkpoint.number > new_head.number =>
+				self.store.save(&self.indexer_id, new_head).await?,
+			_ => (),
+		}
+		Ok(())
+	}
+}
+
+impl From<CheckpointStoreError> for Error {
+	fn from(error: CheckpointStoreError) -> Self {
+		Self::Infrastructure(Box::new(error))
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use mockall::predicate::*;
+	use rstest::*;
+
+	#[fixture]
+	fn store() -> MockCheckpointStore {
+		MockCheckpointStore::new()
+	}
+
+	fn block(number: u64) -> BlockHeader {
+		BlockHeader::new(BlockHash::from(vec![number as u8]), number)
+	}
+
+	#[rstest]
+	#[tokio::test]
+	async fn saves_processed_blocks(mut store: MockCheckpointStore) {
+		store
+			.expect_save()
+			.with(eq(IndexerId::from("ID")), eq(block(42)))
+			.returning(|_, _| Ok(()));
+
+		let recorder = CheckpointRecorder::new(Arc::new(store), "ID".into());
+		recorder.on_block_processed(&block(42)).await.unwrap();
+	}
+
+	#[rstest]
+	#[tokio::test]
+	async fn moves_the_checkpoint_back_on_reorg(mut store: MockCheckpointStore) {
+		store.expect_load().returning(|_| Ok(Some(block(42))));
+		store
+			.expect_save()
+			.with(eq(IndexerId::from("ID")), eq(block(40)))
+			.returning(|_, _| Ok(()));
+
+		let recorder = CheckpointRecorder::new(Arc::new(store), "ID".into());
+		recorder.on_reorg(&block(40)).await.unwrap();
+	}
+
+	#[rstest]
+	#[tokio::test]
+	async fn keeps_the_checkpoint_on_reorg_above_it(mut store: MockCheckpointStore) {
+		store.expect_load().returning(|_| Ok(Some(block(42))));
+		store.expect_save().never();
+
+		let recorder = CheckpointRecorder::new(Arc::new(store), "ID".into());
+		recorder.on_reorg(&block(50)).await.unwrap();
+	}
+
+	#[rstest]
+	#[tokio::test]
+	async fn forwards_store_errors(mut store: MockCheckpointStore) {
+		store.expect_save().returning(|id, _| {
+			Err(CheckpointStoreError::Save {
+				id: id.clone(),
+				details: String::new(),
+			})
+		});
+
+		let recorder = CheckpointRecorder::new(Arc::new(store), "ID".into());
+		assert!(recorder.on_block_processed(&block(42)).await.is_err());
+	}
+}
@@ -2,6 +2,10 @@ use std::sync::Arc;
 
 use super::*;
 
+/**
+ * Forwards every notification to its observers, in order.
+ * Stops at the first observer that fails, so that the error can be reported to the caller.
+ */
 pub struct ObserverComposite(Vec<Arc<dyn Observer>>);
 
 impl ObserverComposite {
@@ -10,21 +14,74 @@ impl ObserverComposite {
 	}
 }
 
+#[async_trait]
 impl Observer for ObserverComposite {
-	fn on_connect(&self, indexer_id: &IndexerId) {
-		self.0.iter().for_each(|observer| observer.on_connect(indexer_id))
+	async fn on_connect(&self, indexer_id: &IndexerId) -> Result<()> {
+		for observer in &self.0 {
+			observer.on_connect(indexer_id).await?;
+		}
+		Ok(())
 	}
 
-	fn on_new_event(&self, event: &Event) {
-		self.0.iter().for_each(|observer| observer.on_new_event(event))
+	async fn on_new_event(&self, event: &Event, metadata: &EventMetadata) -> Result<()> {
+		for observer in &self.0 {
+			observer.on_new_event(event, metadata).await?;
+		}
+		Ok(())
 	}
 
-	fn on_new_block(&self, block_hash: &BlockHash) {
-		self.0.iter().for_each(|observer| observer.on_new_block(block_hash))
+	async fn on_undecodable_event(
+		&self,
+		event: &RawEvent,
+		metadata: &EventMetadata,
+		error: &DecodingError,
+	) -> Result<()> {
+		for observer in &self.0 {
+			observer.on_undecodable_event(event, metadata, error).await?;
+		}
+		Ok(())
 	}
 
-	fn on_reorg(&self) {
-		self.0.iter().for_each(|observer| observer.on_reorg())
+	async fn on_new_block(&self, block_hash: &BlockHash) -> Result<()> {
+		for observer in &self.0 {
+			observer.on_new_block(block_hash).await?;
+		}
+		Ok(())
+	}
+
+	async fn on_reorg(&self, new_head: &BlockHeader) -> Result<()> {
+		for observer in &self.0 {
+			observer.on_reorg(new_head).await?;
+		}
+		Ok(())
+	}
+
+	async fn on_block_processed(&self, block: &BlockHeader) -> Result<()> {
+		for observer in &self.0 {
+			observer.on_block_processed(block).await?;
+		}
+		Ok(())
+	}
+
+	async fn on_disconnect(&self, indexer_id: &IndexerId) -> Result<()> {
+		for observer in &self.0 {
+			observer.on_disconnect(indexer_id).await?;
+		}
+		Ok(())
+	}
+
+	async fn on_reconnect(&self, indexer_id: &IndexerId) -> Result<()> {
+		for observer in &self.0 {
+			observer.on_reconnect(indexer_id).await?;
+		}
+		Ok(())
+	}
+
+	async fn on_shutdown(&self) -> Result<()> {
+		for observer in &self.0 {
+			observer.on_shutdown().await?;
+		}
+		Ok(())
 	}
 }
 
@@ -35,55 +92,199 @@ mod test {
 	use super::*;
 	use mockall::predicate::*;
 
-	#[test]
-	fn on_new_event() {
+	#[tokio::test]
+	async fn on_new_event() {
 		let event = Event::GithubIdentifierRegistered(Default::default());
+		let metadata = EventMetadata {
+			block_number: 42,
+			..Default::default()
+		};
+
+		let mut observer1 = MockObserver::new();
+		observer1
+			.expect_on_new_event()
+			.with(eq(event.clone()), eq(metadata.clone()))
+			.returning(|_, _| Ok(()));
+
+		let mut observer2 = MockObserver::new();
+		observer2
+			.expect_on_new_event()
+			.with(eq(event.clone()), eq(metadata.clone()))
+			.returning(|_, _| Ok(()));
+
+		let composite = ObserverComposite::new(vec![Arc::new(observer1), Arc::new(observer2)]);
+		composite.on_new_event(&event, &metadata).await.unwrap();
+	}
+
+	#[tokio::test]
+	async fn on_new_unregistered_event() {
+		let event = Event::GithubIdentifierUnregistered(Default::default());
+
+		let mut observer1 = MockObserver::new();
+		observer1
+			.expect_on_new_event()
+			.with(eq(event.clone()), always())
+			.returning(|_, _| Ok(()));
+
+		let mut observer2 = MockObserver::new();
+		observer2
+			.expect_on_new_event()
+			.with(eq(event.clone()), always())
+			.returning(|_, _| Ok(()));
+
+		let composite = ObserverComposite::new(vec![Arc::new(observer1), Arc::new(observer2)]);
+		composite.on_new_event(&event, &Default::default()).await.unwrap();
+	}
+
+	#[tokio::test]
+	async fn on_undecodable_event() {
+		let error = DecodingError::Unsupported;
 
 		let mut observer1 = MockObserver::new();
-		observer1.expect_on_new_event().with(eq(event.clone())).return_const(());
+		observer1
+			.expect_on_undecodable_event()
+			.with(always(), always(), eq(error.clone()))
+			.returning(|_, _, _| Ok(()));
 
 		let mut observer2 = MockObserver::new();
-		observer2.expect_on_new_event().with(eq(event.clone())).return_const(());
+		observer2
+			.expect_on_undecodable_event()
+			.with(always(), always(), eq(error.clone()))
+			.returning(|_, _, _| Ok(()));
 
 		let composite = ObserverComposite::new(vec![Arc::new(observer1), Arc::new(observer2)]);
-		composite.on_new_event(&event);
+		composite
+			.on_undecodable_event(&Default::default(), &Default::default(), &error)
+			.await
+			.unwrap();
 	}
 
-	#[test]
-	fn on_connect() {
+	#[tokio::test]
+	async fn on_connect() {
 		let mut observer1 = MockObserver::new();
-		observer1.expect_on_connect().with(eq(IndexerId::from("ID"))).return_const(());
+		observer1
+			.expect_on_connect()
+			.with(eq(IndexerId::from("ID")))
+			.returning(|_| Ok(()));
 
 		let mut observer2 = MockObserver::new();
-		observer2.expect_on_connect().with(eq(IndexerId::from("ID"))).return_const(());
+		observer2
+			.expect_on_connect()
+			.with(eq(IndexerId::from("ID")))
+			.returning(|_| Ok(()));
 
 		let composite = ObserverComposite::new(vec![Arc::new(observer1), Arc::new(observer2)]);
-		composite.on_connect(&IndexerId::from("ID"));
+		composite.on_connect(&IndexerId::from("ID")).await.unwrap();
 	}
 
-	#[test]
-	fn on_new_block() {
+	#[tokio::test]
+	async fn on_new_block() {
 		let block_hash = BlockHash::from_str("0x1234").unwrap();
 
 		let mut observer1 = MockObserver::new();
-		observer1.expect_on_new_block().with(eq(block_hash.clone())).return_const(());
+		observer1.expect_on_new_block().with(eq(block_hash.clone())).returning(|_| Ok(()));
+
+		let mut observer2 = MockObserver::new();
+		observer2.expect_on_new_block().with(eq(block_hash.clone())).returning(|_| Ok(()));
+
+		let composite = ObserverComposite::new(vec![Arc::new(observer1), Arc::new(observer2)]);
+		composite.on_new_block(&block_hash).await.unwrap();
+	}
+
+	#[tokio::test]
+	async fn on_reorg() {
+		let new_head = BlockHeader::new(BlockHash::from_str("0x1234").unwrap(), 42);
+
+		let mut observer1 = MockObserver::new();
+		observer1.expect_on_reorg().with(eq(new_head.clone())).returning(|_| Ok(()));
+
+		let mut observer2 = MockObserver::new();
+		observer2.expect_on_reorg().with(eq(new_head.clone())).returning(|_| Ok(()));
+
+		let composite = ObserverComposite::new(vec![Arc::new(observer1), Arc::new(observer2)]);
+		composite.on_reorg(&new_head).await.unwrap();
+	}
+
+	#[tokio::test]
+	async fn on_block_processed() {
+		let block = BlockHeader::new(BlockHash::from_str("0x1234").unwrap(), 42);
+
+		let mut observer1 = MockObserver::new();
+		observer1.expect_on_block_processed().with(eq(block.clone())).returning(|_| Ok(()));
+
+		let mut observer2 = MockObserver::new();
+		observer2.expect_on_block_processed().with(eq(block.clone())).returning(|_| Ok(()));
+
+		let composite = ObserverComposite::new(vec![Arc::new(observer1), Arc::new(observer2)]);
+		composite.on_block_processed(&block).await.unwrap();
+	}
+
+	#[tokio::test]
+	async fn on_disconnect() {
+		let mut observer1 = MockObserver::new();
+		observer1
+			.expect_on_disconnect()
+			.with(eq(IndexerId::from("ID")))
+			.returning(|_| Ok(()));
+
+		let mut observer2 = MockObserver::new();
+		observer2
+			.expect_on_disconnect()
+			.with(eq(IndexerId::from("ID")))
+			.returning(|_| Ok(()));
+
+		let composite = ObserverComposite::new(vec![Arc::new(observer1), Arc::new(observer2)]);
+		composite.on_disconnect(&IndexerId::from("ID")).await.unwrap();
+	}
+
+	#[tokio::test]
+	async fn on_reconnect() {
+		let mut observer1 = MockObserver::new();
+		observer1
+			.expect_on_reconnect()
+			.with(eq(IndexerId::from("ID")))
+			.returning(|_| Ok(()));
 
 		let mut observer2 = MockObserver::new();
-		observer2.expect_on_new_block().with(eq(block_hash.clone())).return_const(());
+		observer2
+			.expect_on_reconnect()
+			.with(eq(IndexerId::from("ID")))
+			.returning(|_| Ok(()));
 
 		let composite = ObserverComposite::new(vec![Arc::new(observer1), Arc::new(observer2)]);
-		composite.on_new_block(&block_hash);
+		composite.on_reconnect(&IndexerId::from("ID")).await.unwrap();
 	}
 
-	#[test]
-	fn on_reorg() {
+	#[tokio::test]
+	async fn on_shutdown() {
 		let mut observer1 = MockObserver::new();
-		observer1.expect_on_reorg().return_const(());
+		observer1.expect_on_shutdown().times(1).returning(|| Ok(()));
 
 		let mut observer2 = MockObserver::new();
-		observer2.expect_on_reorg().return_const(());
+		observer2.expect_on_shutdown().times(1).returning(|| Ok(()));
 
 		let composite = ObserverComposite::new(vec![Arc::new(observer1), Arc::new(observer2)]);
-		composite.on_reorg();
+		composite.on_shutdown().await.unwrap();
+	}
+
+	#[tokio::test]
+	async fn stops_at_the_first_failing_observer() {
+		let mut observer1 = MockObserver::new();
+		observer1
+			.expect_on_new_event()
+			.returning(|_, _| Err(Error::Infrastructure("database unavailable".into())));
+
+		let mut observer2 = MockObserver::new();
+		observer2.expect_on_new_event().never();
+
+		let composite = ObserverComposite::new(vec![Arc::new(observer1), Arc::new(observer2)]);
+		let result = composite
+			.on_new_event(
+				&Event::GithubIdentifierRegistered(Default::default()),
+				&Default::default(),
+			)
+			.await;
+
+		assert!(result.is_err());
 	}
 }
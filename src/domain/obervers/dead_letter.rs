@@ -0,0 +1,112 @@
+use std::sync::Arc;
+
+use super::*;
+
+/**
+ * Saves the events which could not be decoded into a dead letter store
+ */
+pub struct DeadLetterRecorder {
+	store: Arc<dyn DeadLetterStore>,
+	indexer_id: IndexerId,
+}
+
+impl DeadLetterRecorder {
+	pub fn new(store: Arc<dyn DeadLetterStore>, indexer_id: IndexerId) -> Self {
+		Self { store, indexer_id }
+	}
+}
+
+#[async_trait]
+impl Observer for DeadLetterRecorder {
+	async fn on_undecodable_event(
+		&self,
+		event: &RawEvent,
+		metadata: &EventMetadata,
+		error: &DecodingError,
+	) -> Result<()> {
+		let dead_letter = DeadLetter {
+			event: event.clone(),
+			metadata: metadata.clone(),
+			error: error.clone(),
+		};
+		self.store.save(&self.indexer_id, &dead_letter).await?;
+		Ok(())
+	}
+
+	async fn on_shutdown(&self) -> Result<()> {
+		self.store.flush().await?;
+		Ok(())
+	}
+}
+
+impl From<DeadLetterStoreError> for Error {
+	fn from(error: DeadLetterStoreError) -> Self {
+		Self::Infrastructure(Box::new(error))
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use mockall::predicate::*;
+
+	#[tokio::test]
+	async fn saves_undecodable_events() {
+		let event = RawEvent {
+			topics: vec![HexaString::from(vec![1])],
+			data: vec![HexaString::from(vec![2])],
+		};
+		let metadata = EventMetadata {
+			block_number: 42,
+			..Default::default()
+		};
+		let error = DecodingError::Invalid(String::from("missing field"));
+
+		let mut store = MockDeadLetterStore::new();
+		store
+			.expect_save()
+			.with(
+				eq(IndexerId::from("ID")),
+				eq(DeadLetter {
+					event: event.clone(),
+					metadata: metadata.clone(),
+					error: error.clone(),
+				}),
+			)
+			.times(1)
+			.returning(|_, _| Ok(()));
+
+		let recorder = DeadLetterRecorder::new(Arc::new(store), "ID".into());
+		recorder.on_undecodable_event(&event, &metadata, &error).await.unwrap();
+	}
+
+	#[tokio::test]
+	async fn forwards_store_errors() {
+		let mut store = MockDeadLetterStore::new();
+		store.expect_save().returning(|id, _| {
+			Err(DeadLetterStoreError::Save {
+				id: id.clone(),
+				details: String::from("disk full"),
+			})
+		});
+
+		let recorder = DeadLetterRecorder::new(Arc::new(store), "ID".into());
+		let result = recorder
+			.on_undecodable_event(
+				&Default::default(),
+				&Default::default(),
+				&DecodingError::Unsupported,
+			)
+			.await;
+		assert!(matches!(result, Err(Error::Infrastructure(_))));
+	}
+
+	#[tokio::test]
+	async fn flushes_the_store_on_shutdown() {
+		let mut store = MockDeadLetterStore::new();
+		store.expect_flush().times(1).returning(|| Ok(()));
+
+		let recorder = DeadLetterRecorder::new(Arc::new(store), "ID".into());
+		recorder.on_shutdown().await.unwrap();
+	}
+}
@@ -0,0 +1,93 @@
+use std::sync::RwLock;
+
+use super::*;
+
+/**
+ * Keeps the received events in memory, along with their metadata.
+ * On chain reorg, the events of the blocks above the new head are forgotten.
+ */
+#[derive(Default)]
+pub struct InMemory(RwLock<Vec<(Event, EventMetadata)>>);
+
+impl InMemory {
+	pub fn events(&self) -> Vec<(Event, EventMetadata)> {
+		self.0.read().expect("events lock is poisoned").clone()
+	}
+}
+
+#[async_trait]
+impl Observer for InMemory {
+	async fn on_new_event(&self, event: &Event, metadata: &EventMetadata) -> Result<()> {
+		self.0
+			.write()
+			.expect("events lock is poisoned")
+			.push((event.clone(), metadata.clone()));
+		Ok(())
+	}
+
+	async fn on_reorg(&self, new_head: &BlockHeader) -> Result<()> {
+		self.0
+			.write()
+			.expect("events lock is poisoned")
+			.retain(|(_, metadata)| metadata.block_number <= new_head.number);
+		Ok(())
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use rstest::*;
+
+	fn metadata(block_number: u64) -> EventMetadata {
+		EventMetadata {
+			block_number,
+			..Default::default()
+		}
+	}
+
+	#[fixture]
+	fn event() -> Event {
+		Event::GithubIdentifierRegistered(Default::default())
+	}
+
+	#[rstest]
+	#[tokio::test]
+	async fn on_new_event(event: Event) {
+		let observer = InMemory::default();
+		observer.on_new_event(&event, &metadata(1)).await.unwrap();
+		observer.on_new_event(&event, &metadata(2)).await.unwrap();
+
+		assert_eq!(
+			vec![(event.clone(), metadata(1)), (event, metadata(2))],
+			observer.events()
+		);
+	}
+
+	#[rstest]
+	#[tokio::test]
+	async fn on_reorg_forgets_events_above_the_new_head(event: Event) {
+		let observer = InMemory::default();
+		for block_number in 1..=4 {
+			observer.on_new_event(&event, &metadata(block_number)).await.unwrap();
+		}
+
+		observer.on_reorg(&BlockHeader::new(Default::default(), 2)).await.unwrap();
+
+		assert_eq!(
+			vec![(event.clone(), metadata(1)), (event, metadata(2))],
+			observer.events()
+		);
+	}
+
+	#[rstest]
+	#[tokio::test]
+	async fn on_reorg_above_the_last_event_forgets_nothing(event: Event) {
+		let observer = InMemory::default();
+		observer.on_new_event(&event, &metadata(1)).await.unwrap();
+
+		observer.on_reorg(&BlockHeader::new(Default::default(), 5)).await.unwrap();
+
+		assert_eq!(vec![(event, metadata(1))], observer.events());
+	}
+}
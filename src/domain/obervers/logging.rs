@@ -11,21 +11,60 @@ impl<'a> Logger<'a> {
 	}
 }
 
+#[async_trait]
 impl Observer for Logger<'_> {
-	fn on_connect(&self, indexer_id: &IndexerId) {
+	async fn on_connect(&self, indexer_id: &IndexerId) -> Result<()> {
 		self.0(format!("🔗 Indexer `{indexer_id}` connected"));
+		Ok(())
 	}
 
-	fn on_new_event(&self, event: &Event) {
-		self.0(format!("⚡ New event: {}", event));
+	async fn on_new_event(&self, event: &Event, metadata: &EventMetadata) -> Result<()> {
+		self.0(format!(
+			"⚡ New event: {} (block: {}, log: {})",
+			event, metadata.block_number, metadata.log_index
+		));
+		Ok(())
 	}
 
-	fn on_new_block(&self, block_hash: &BlockHash) {
+	async fn on_undecodable_event(
+		&self,
+		_event: &RawEvent,
+		metadata: &EventMetadata,
+		error: &DecodingError,
+	) -> Result<()> {
+		self.0(format!(
+			"🧐 Undecodable event from {}: {} (block: {}, log: {})",
+			metadata.contract_address, error, metadata.block_number, metadata.log_index
+		));
+		Ok(())
+	}
+
+	async fn on_new_block(&self, block_hash: &BlockHash) -> Result<()> {
 		self.0(format!("⛏️ New block: {block_hash}"));
+		Ok(())
 	}
 
-	fn on_reorg(&self) {
-		self.0("🤕 Chain reorg".to_string());
+	async fn on_reorg(&self, new_head: &BlockHeader) -> Result<()> {
+		self.0(format!(
+			"🤕 Chain reorg, new head: {} (block: {})",
+			new_head.hash, new_head.number
+		));
+		Ok(())
+	}
+
+	async fn on_block_processed(&self, block: &BlockHeader) -> Result<()> {
+		self.0(format!("✅ Block processed: {} (block: {})", block.hash, block.number));
+		Ok(())
+	}
+
+	async fn on_disconnect(&self, indexer_id: &IndexerId) -> Result<()> {
+		self.0(format!("💔 Indexer `{indexer_id}` disconnected"));
+		Ok(())
+	}
+
+	async fn on_reconnect(&self, indexer_id: &IndexerId) -> Result<()> {
+		self.0(format!("🔁 Indexer `{indexer_id}` reconnected"));
+		Ok(())
 	}
 }
 
@@ -53,7 +92,8 @@ mod test {
 	}
 
 	#[rstest]
-	fn on_new_event(mut logger: MockLoggerCallback) {
+	#[tokio::test]
+	async fn on_new_event(mut logger: MockLoggerCallback) {
 		logger
 			.expect_log()
 			.withf(|msg| msg.starts_with("⚡ New event: "))
@@ -62,11 +102,70 @@ mod test {
 
 		let event = Event::GithubIdentifierRegistered(Default::default());
 		let handler = Logger::new(&logging_callback);
-		handler.on_new_event(&event);
+		handler.on_new_event(&event, &Default::default()).await.unwrap();
+	}
+
+	#[rstest]
+	#[tokio::test]
+	async fn on_new_unregistered_event(mut logger: MockLoggerCallback) {
+		logger
+			.expect_log()
+			.withf(|msg| msg.starts_with("⚡ New event: GithubIdentifierUnregistered"))
+			.return_const(());
+		let logging_callback = move |message| logger.log(message);
+
+		let event = Event::GithubIdentifierUnregistered(Default::default());
+		let handler = Logger::new(&logging_callback);
+		handler.on_new_event(&event, &Default::default()).await.unwrap();
+	}
+
+	#[rstest]
+	#[tokio::test]
+	async fn on_undecodable_event(mut logger: MockLoggerCallback) {
+		logger
+			.expect_log()
+			.withf(|msg| {
+				msg.starts_with("🧐 Undecodable event from 0x01: unsupported event")
+					&& msg.ends_with("(block: 42, log: 3)")
+			})
+			.return_const(());
+		let logging_callback = move |message| logger.log(message);
+
+		let metadata = EventMetadata {
+			block_number: 42,
+			log_index: 3,
+			contract_address: ContractAddress::from(vec![1]),
+			..Default::default()
+		};
+		let handler = Logger::new(&logging_callback);
+		handler
+			.on_undecodable_event(&Default::default(), &metadata, &DecodingError::Unsupported)
+			.await
+			.unwrap();
+	}
+
+	#[rstest]
+	#[tokio::test]
+	async fn on_new_event_with_metadata(mut logger: MockLoggerCallback) {
+		logger
+			.expect_log()
+			.withf(|msg| msg.ends_with("(block: 42, log: 3)"))
+			.return_const(());
+		let logging_callback = move |message| logger.log(message);
+
+		let event = Event::GithubIdentifierRegistered(Default::default());
+		let metadata = EventMetadata {
+			block_number: 42,
+			log_index: 3,
+			..Default::default()
+		};
+		let handler = Logger::new(&logging_callback);
+		handler.on_new_event(&event, &metadata).await.unwrap();
 	}
 
 	#[rstest]
-	fn on_connect(mut logger: MockLoggerCallback) {
+	#[tokio::test]
+	async fn on_connect(mut logger: MockLoggerCallback) {
 		logger
 			.expect_log()
 			.with(eq(String::from("🔗 Indexer `ID` connected")))
@@ -74,11 +173,12 @@ mod test {
 		let logging_callback = move |message| logger.log(message);
 
 		let handler = Logger::new(&logging_callback);
-		handler.on_connect(&IndexerId::from("ID"));
+		handler.on_connect(&IndexerId::from("ID")).await.unwrap();
 	}
 
 	#[rstest]
-	fn on_new_block(mut logger: MockLoggerCallback) {
+	#[tokio::test]
+	async fn on_new_block(mut logger: MockLoggerCallback) {
 		logger
 			.expect_log()
 			.with(eq(String::from("⛏️ New block: 0x1234")))
@@ -86,21 +186,83 @@ mod test {
 		let logging_callback = move |message| logger.log(message);
 
 		let handler = Logger::new(&logging_callback);
-		handler.on_new_block(&BlockHash::from_str("0x1234").unwrap());
+		handler.on_new_block(&BlockHash::from_str("0x1234").unwrap()).await.unwrap();
 	}
 
 	#[rstest]
-	fn on_reorg(mut logger: MockLoggerCallback) {
-		logger.expect_log().with(eq(String::from("🤕 Chain reorg"))).return_const(());
+	#[tokio::test]
+	async fn on_reorg(mut logger: MockLoggerCallback) {
+		logger
+			.expect_log()
+			.with(eq(String::from(
+				"🤕 Chain reorg, new head: 0x1234 (block: 42)",
+			)))
+			.return_const(());
+		let logging_callback = move |message| logger.log(message);
+
+		let handler = Logger::new(&logging_callback);
+		handler
+			.on_reorg(&BlockHeader::new(
+				BlockHash::from_str("0x1234").unwrap(),
+				42,
+			))
+			.await
+			.unwrap();
+	}
+
+	#[rstest]
+	#[tokio::test]
+	async fn on_block_processed(mut logger: MockLoggerCallback) {
+		logger
+			.expect_log()
+			.with(eq(String::from("✅ Block processed: 0x1234 (block: 42)")))
+			.return_const(());
+		let logging_callback = move |message| logger.log(message);
+
+		let handler = Logger::new(&logging_callback);
+		handler
+			.on_block_processed(&BlockHeader::new(
+				BlockHash::from_str("0x1234").unwrap(),
+				42,
+			))
+			.await
+			.unwrap();
+	}
+
+	#[rstest]
+	#[tokio::test]
+	async fn on_disconnect(mut logger: MockLoggerCallback) {
+		logger
+			.expect_log()
+			.with(eq(String::from("💔 Indexer `ID` disconnected")))
+			.return_const(());
+		let logging_callback = move |message| logger.log(message);
+
+		let handler = Logger::new(&logging_callback);
+		handler.on_disconnect(&IndexerId::from("ID")).await.unwrap();
+	}
+
+	#[rstest]
+	#[tokio::test]
+	async fn on_reconnect(mut logger: MockLoggerCallback) {
+		logger
+			.expect_log()
+			.with(eq(String::from("🔁 Indexer `ID` reconnected")))
+			.return_const(());
 		let logging_callback = move |message| logger.log(message);
 
 		let handler = Logger::new(&logging_callback);
-		handler.on_reorg();
+		handler.on_reconnect(&IndexerId::from("ID")).await.unwrap();
 	}
 
-	#[test]
-	fn handler_can_be_created_using_default() {
+	#[tokio::test]
+	async fn handler_can_be_created_using_default() {
 		let handler = Logger::default();
-		handler.on_new_event(&Event::GithubIdentifierRegistered(Default::default()));
+		handler.on_new_event(
+			&Event::GithubIdentifierRegistered(Default::default()),
+			&Default::default(),
+		)
+		.await
+		.unwrap();
 	}
 }
@@ -4,13 +4,82 @@ pub use composite::ObserverComposite;
 mod logging;
 pub use logging::Logger;
 
+mod in_memory;
+pub use in_memory::InMemory;
+
+mod checkpoint;
+pub use checkpoint::CheckpointRecorder;
+
+mod dead_letter;
+pub use dead_letter::DeadLetterRecorder;
+
 use crate::domain::*;
+use async_trait::async_trait;
 use mockall::automock;
+use thiserror::Error as ThisError;
 
+#[derive(ThisError, Debug)]
+pub enum Error {
+	#[error("observer failed to handle the notification: {0}")]
+	Infrastructure(#[from] Box<dyn std::error::Error + Send + Sync>),
+}
+
+type Result<T> = std::result::Result<T, Error>;
+
+/**
+ * An Observer is notified of everything happening on the indexing stream.
+ * Every hook does nothing by default, so that observers only implement what they care about.
+ */
 #[automock]
+#[async_trait]
 pub trait Observer: Send + Sync {
-	fn on_connect(&self, indexer_id: &IndexerId);
-	fn on_new_event(&self, event: &Event);
-	fn on_new_block(&self, block_hash: &BlockHash);
-	fn on_reorg(&self);
+	async fn on_connect(&self, _indexer_id: &IndexerId) -> Result<()> {
+		Ok(())
+	}
+
+	async fn on_new_event(&self, _event: &Event, _metadata: &EventMetadata) -> Result<()> {
+		Ok(())
+	}
+
+	/**
+	 * Called instead of `on_new_event` for the events which could not be decoded
+	 */
+	async fn on_undecodable_event(
+		&self,
+		_event: &RawEvent,
+		_metadata: &EventMetadata,
+		_error: &DecodingError,
+	) -> Result<()> {
+		Ok(())
+	}
+
+	async fn on_new_block(&self, _block_hash: &BlockHash) -> Result<()> {
+		Ok(())
+	}
+
+	async fn on_reorg(&self, _new_head: &BlockHeader) -> Result<()> {
+		Ok(())
+	}
+
+	/**
+	 * Called once all the events of a block have been handled and the block has been acked
+	 */
+	async fn on_block_processed(&self, _block: &BlockHeader) -> Result<()> {
+		Ok(())
+	}
+
+	async fn on_disconnect(&self, _indexer_id: &IndexerId) -> Result<()> {
+		Ok(())
+	}
+
+	async fn on_reconnect(&self, _indexer_id: &IndexerId) -> Result<()> {
+		Ok(())
+	}
+
+	/**
+	 * Called once the indexing stopped, before the process exits, so that nothing is lost
+	 */
+	async fn on_shutdown(&self) -> Result<()> {
+		Ok(())
+	}
 }
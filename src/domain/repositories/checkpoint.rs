@@ -0,0 +1,37 @@
+use crate::domain::*;
+use async_trait::async_trait;
+use mockall::automock;
+use thiserror::Error as ThisError;
+
+#[derive(ThisError, Debug)]
+pub enum Error {
+	#[error("unable to load the checkpoint of indexer `{id}`: {details}")]
+	Load { id: IndexerId, details: String },
+	#[error("unable to save the checkpoint of indexer `{id}`: {details}")]
+	Save { id: IndexerId, details: String },
+}
+
+type Result<T> = std::result::Result<T, Error>;
+
+/**
+ * Keeps track of the last block fully processed by each indexer
+ */
+#[automock]
+#[async_trait]
+pub trait Store: Send + Sync {
+	async fn load(&self, indexer_id: &IndexerId) -> Result<Option<BlockHeader>>;
+	async fn save(&self, indexer_id: &IndexerId, checkpoint: &BlockHeader) -> Result<()>;
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use rstest::*;
+
+	#[rstest]
+	#[case(Error::Load{id: IndexerId::from("ID"), details: String::from("details")}, "unable to load the checkpoint of indexer `ID`: details")]
+	#[case(Error::Save{id: IndexerId::from("ID"), details: String::from("details")}, "unable to save the checkpoint of indexer `ID`: details")]
+	fn error_messages_are_well_formatted(#[case] error: Error, #[case] expected_message: &str) {
+		assert_eq!(expected_message, error.to_string());
+	}
+}
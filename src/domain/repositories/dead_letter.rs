@@ -0,0 +1,58 @@
+use crate::domain::*;
+use async_trait::async_trait;
+use mockall::automock;
+use thiserror::Error as ThisError;
+
+#[derive(ThisError, Debug)]
+pub enum Error {
+	#[error("unable to list the dead letters of indexer `{id}`: {details}")]
+	List { id: IndexerId, details: String },
+	#[error("unable to save a dead letter of indexer `{id}`: {details}")]
+	Save { id: IndexerId, details: String },
+	#[error("unable to remove a dead letter of indexer `{id}`: {details}")]
+	Remove { id: IndexerId, details: String },
+	#[error("unable to flush the dead letters: {0}")]
+	Flush(String),
+}
+
+type Result<T> = std::result::Result<T, Error>;
+
+/**
+ * Keeps the events that could not be decoded, for each indexer.
+ * An event is identified by the hash of its block and its log index, so that an event delivered
+ * again is only kept once.
+ */
+#[automock]
+#[async_trait]
+pub trait Store: Send + Sync {
+	async fn save(&self, indexer_id: &IndexerId, dead_letter: &DeadLetter) -> Result<()>;
+	/**
+	 * Forget the dead letter of the event, once it could be decoded
+	 */
+	async fn remove(&self, indexer_id: &IndexerId, metadata: &EventMetadata) -> Result<()>;
+	/**
+	 * Returns the dead letters of the indexer, in the order they were saved
+	 */
+	async fn list(&self, indexer_id: &IndexerId) -> Result<Vec<DeadLetter>>;
+	/**
+	 * Make sure the saved dead letters are persisted, before the process exits
+	 */
+	async fn flush(&self) -> Result<()> {
+		Ok(())
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use rstest::*;
+
+	#[rstest]
+	#[case(Error::List{id: IndexerId::from("ID"), details: String::from("details")}, "unable to list the dead letters of indexer `ID`: details")]
+	#[case(Error::Save{id: IndexerId::from("ID"), details: String::from("details")}, "unable to save a dead letter of indexer `ID`: details")]
+	#[case(Error::Remove{id: IndexerId::from("ID"), details: String::from("details")}, "unable to remove a dead letter of indexer `ID`: details")]
+	#[case(Error::Flush(String::from("details")), "unable to flush the dead letters: details")]
+	fn error_messages_are_well_formatted(#[case] error: Error, #[case] expected_message: &str) {
+		assert_eq!(expected_message, error.to_string());
+	}
+}
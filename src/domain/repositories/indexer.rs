@@ -13,6 +13,12 @@ pub enum Error {
 	GetIndexer { id: IndexerId, details: String },
 	#[error("unable to delete the indexer `{id}`: {details}")]
 	DeleteIndexer { id: IndexerId, details: String },
+	#[error("unable to list the indexers: {0}")]
+	ListIndexers(String),
+	#[error("invalid network: {0}")]
+	InvalidNetwork(String),
+	#[error("the indexer `{id}` returned by the indexing service is invalid: {details}")]
+	InvalidIndexer { id: IndexerId, details: String },
 }
 
 type Result<T> = std::result::Result<T, Error>;
@@ -23,6 +29,7 @@ pub trait Repository {
 	async fn create(&self, indexer: &Indexer) -> Result<()>;
 	async fn by_id(&self, indexer_id: &IndexerId) -> Result<Option<Indexer>>;
 	async fn delete(&self, indexer_id: &IndexerId) -> Result<()>;
+	async fn list(&self) -> Result<Vec<Indexer>>;
 }
 
 #[cfg(test)]
@@ -34,6 +41,15 @@ mod test {
 	#[case(Error::CreateIndexer{id: IndexerId::from("ID"), details: String::from("details")}, "unable to create the indexer `ID`: details")]
 	#[case(Error::GetIndexer{id: IndexerId::from("ID"), details: String::from("details")}, "unable to get the indexer `ID`: details")]
 	#[case(Error::DeleteIndexer{id: IndexerId::from("ID"), details: String::from("details")}, "unable to delete the indexer `ID`: details")]
+	#[case(
+		Error::ListIndexers(String::from("details")),
+		"unable to list the indexers: details"
+	)]
+	#[case(
+		Error::InvalidNetwork(String::from("details")),
+		"invalid network: details"
+	)]
+	#[case(Error::InvalidIndexer{id: IndexerId::from("ID"), details: String::from("details")}, "the indexer `ID` returned by the indexing service is invalid: details")]
 	fn error_messages_are_well_formatted(#[case] error: Error, #[case] expected_message: &str) {
 		assert_eq!(expected_message, error.to_string());
 	}
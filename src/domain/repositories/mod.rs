@@ -3,3 +3,13 @@ pub use indexer::{
 	Error as IndexerRepositoryError, MockRepository as MockIndexerRepository,
 	Repository as IndexerRepository,
 };
+
+mod checkpoint;
+pub use checkpoint::{
+	Error as CheckpointStoreError, MockStore as MockCheckpointStore, Store as CheckpointStore,
+};
+
+mod dead_letter;
+pub use dead_letter::{
+	Error as DeadLetterStoreError, MockStore as MockDeadLetterStore, Store as DeadLetterStore,
+};
@@ -1,9 +1,10 @@
-use std::sync::Arc;
+use std::{sync::Arc, time::Duration};
 
 use crate::domain::*;
 use async_trait::async_trait;
 use mockall::automock;
 use thiserror::Error as ThisError;
+use tokio_util::sync::CancellationToken;
 
 #[derive(ThisError, Debug)]
 pub enum Error {
@@ -13,16 +14,32 @@ pub enum Error {
 	Send(String),
 	#[error("error while receiving message from indexing server: {0}")]
 	Receive(String),
+	#[error("the indexing server closed the stream")]
+	StreamClosed,
+	#[error("nothing received from indexing server for {0:?}")]
+	Idle(Duration),
+	#[error("the indexing server sent a reorg without its new head")]
+	ReorgWithoutHead,
+	#[error(transparent)]
+	Observer(#[from] BlockchainObserverError),
 }
 
 type Result<T> = std::result::Result<T, Error>;
 
 #[automock]
 #[async_trait]
-pub trait Service {
+pub trait Service: Send + Sync {
+	/**
+	 * Stream the events of the indexer to the observer.
+	 * A block is acked only once the observer handled all its events successfully:
+	 * if it fails, streaming stops with the observer error and the block is not acked.
+	 * Once `shutdown` is cancelled, the events being handled are finished and acked, then
+	 * streaming stops successfully.
+	 */
 	async fn fetch_new_events(
 		&self,
 		indexer: &Indexer,
 		observers: Arc<dyn BlockchainObserver>,
+		shutdown: CancellationToken,
 	) -> Result<()>;
 }
@@ -1,2 +1,11 @@
 mod indexing;
-pub use indexing::{Error as IndexingServiceError, Service as IndexingService};
+pub use indexing::{
+	Error as IndexingServiceError, MockService as MockIndexingService, Service as IndexingService,
+};
+
+mod streaming;
+pub use streaming::{
+	AckHandle, Acknowledger as BlockAcknowledger, EventStream, Message as IndexerMessage,
+	MockAcknowledger as MockBlockAcknowledger, MockService as MockStreamingService,
+	Service as StreamingService,
+};
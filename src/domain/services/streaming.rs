@@ -0,0 +1,113 @@
+use std::{fmt, sync::Arc};
+
+use crate::domain::*;
+use async_trait::async_trait;
+use futures::stream::BoxStream;
+use mockall::automock;
+
+type Result<T> = std::result::Result<T, IndexingServiceError>;
+
+/**
+ * The events of an indexer, in the order they are received from the indexing server.
+ * The stream ends when the indexing server closes the connection.
+ */
+pub type EventStream = BoxStream<'static, Result<Message>>;
+
+#[derive(Debug)]
+pub enum Message {
+	Connected(IndexerId),
+	NewBlock(BlockHash),
+	/**
+	 * The events of a block, which is acked only once `ack` is called.
+	 * `ack` is None if the indexing server did not tell which block the events belong to.
+	 */
+	NewEvents {
+		block: BlockHeader,
+		events: Vec<(Event, EventMetadata)>,
+		/** The events of the block which could not be decoded */
+		undecodable_events: Vec<DeadLetter>,
+		ack: Option<AckHandle>,
+	},
+	Reorg(BlockHeader),
+}
+
+#[automock]
+#[async_trait]
+pub trait Acknowledger: Send + Sync {
+	async fn ack(&self, block_hash: &BlockHash) -> Result<()>;
+}
+
+/**
+ * Lets the consumer of an event stream ack a block once it handled all its events
+ */
+pub struct AckHandle {
+	block_hash: BlockHash,
+	acknowledger: Arc<dyn Acknowledger>,
+}
+
+impl AckHandle {
+	pub fn new(block_hash: BlockHash, acknowledger: Arc<dyn Acknowledger>) -> Self {
+		Self {
+			block_hash,
+			acknowledger,
+		}
+	}
+
+	pub fn block_hash(&self) -> &BlockHash {
+		&self.block_hash
+	}
+
+	pub async fn ack(self) -> Result<()> {
+		self.acknowledger.ack(&self.block_hash).await
+	}
+}
+
+impl fmt::Debug for AckHandle {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.debug_struct("AckHandle").field("block_hash", &self.block_hash).finish()
+	}
+}
+
+#[automock]
+#[async_trait]
+pub trait Service: Send + Sync {
+	/**
+	 * Connect the indexer and return its events as a stream.
+	 * Unlike `IndexingService::fetch_new_events`, blocks are not acked automatically and the
+	 * stream does not reconnect: both are left to the consumer.
+	 */
+	async fn event_stream(&self, indexer: &Indexer) -> Result<EventStream>;
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use mockall::predicate::eq;
+
+	#[tokio::test]
+	async fn ack_handle_acks_its_block() {
+		let mut acknowledger = MockAcknowledger::new();
+		acknowledger
+			.expect_ack()
+			.with(eq(BlockHash::from(vec![1])))
+			.times(1)
+			.returning(|_| Ok(()));
+
+		let handle = AckHandle::new(vec![1].into(), Arc::new(acknowledger));
+		assert_eq!(&BlockHash::from(vec![1]), handle.block_hash());
+
+		let result = handle.ack().await;
+		assert!(result.is_ok(), "{}", result.err().unwrap());
+	}
+
+	#[tokio::test]
+	async fn ack_handle_forwards_errors() {
+		let mut acknowledger = MockAcknowledger::new();
+		acknowledger
+			.expect_ack()
+			.returning(|_| Err(IndexingServiceError::Send("channel closed".to_string())));
+
+		let result = AckHandle::new(vec![1].into(), Arc::new(acknowledger)).ack().await;
+		assert!(matches!(result, Err(IndexingServiceError::Send(_))));
+	}
+}
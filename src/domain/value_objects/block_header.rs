@@ -0,0 +1,24 @@
+use crate::domain::*;
+
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct BlockHeader {
+	pub hash: BlockHash,
+	pub number: u64,
+	/** Seconds since the Unix epoch, if known */
+	pub timestamp: Option<u64>,
+}
+
+impl BlockHeader {
+	pub fn new(hash: BlockHash, number: u64) -> Self {
+		Self {
+			hash,
+			number,
+			timestamp: None,
+		}
+	}
+
+	pub fn with_timestamp(mut self, timestamp: u64) -> Self {
+		self.timestamp = Some(timestamp);
+		self
+	}
+}
@@ -0,0 +1,153 @@
+use std::fmt::Display;
+
+use crypto_bigint::{Encoding, U256};
+
+use super::HexaString;
+
+/**
+ * An event decoded from the ABI of its contract, rather than mapped to a dedicated type
+ */
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct DecodedEvent {
+	pub name: String,
+	pub fields: Vec<(String, Value)>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Value {
+	Felt(HexaString),
+	Uint256(U256),
+	Array(Vec<Value>),
+	Struct(Vec<(String, Value)>),
+	Address(HexaString),
+	Bool(bool),
+	/** Two's complement, as encoded by Solidity */
+	Int256(U256),
+	Bytes(HexaString),
+	String(String),
+}
+
+impl DecodedEvent {
+	pub fn field(&self, name: &str) -> Option<&Value> {
+		self.fields.iter().find(|(field, _)| field == name).map(|(_, value)| value)
+	}
+}
+
+fn fmt_fields(f: &mut std::fmt::Formatter<'_>, fields: &[(String, Value)]) -> std::fmt::Result {
+	write!(f, "{{ ")?;
+	for (index, (name, value)) in fields.iter().enumerate() {
+		if index > 0 {
+			write!(f, ", ")?;
+		}
+		write!(f, "{}: {}", name, value)?;
+	}
+	write!(f, " }}")
+}
+
+impl Display for DecodedEvent {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		write!(f, "{} ", self.name)?;
+		fmt_fields(f, &self.fields)
+	}
+}
+
+impl Display for Value {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match self {
+			Self::Felt(felt) => write!(f, "{}", felt),
+			Self::Uint256(value) => write!(f, "0x{:x}", value),
+			Self::Array(values) => {
+				write!(f, "[")?;
+				for (index, value) in values.iter().enumerate() {
+					if index > 0 {
+						write!(f, ", ")?;
+					}
+					write!(f, "{}", value)?;
+				}
+				write!(f, "]")
+			},
+			Self::Struct(fields) => fmt_fields(f, fields),
+			Self::Address(address) => write!(f, "{}", address),
+			Self::Bool(value) => write!(f, "{}", value),
+			Self::Int256(value) if value.to_be_bytes()[0] & 0x80 != 0 =>
+				write!(f, "-0x{:x}", U256::ZERO.wrapping_sub(value)),
+			Self::Int256(value) => write!(f, "0x{:x}", value),
+			Self::Bytes(bytes) => write!(f, "{}", bytes),
+			Self::String(string) => write!(f, "{:?}", string),
+		}
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+
+	#[test]
+	fn display() {
+		let event = DecodedEvent {
+			name: String::from("Transfer"),
+			fields: vec![
+				(String::from("from"), Value::Felt(HexaString::from(vec![1]))),
+				(String::from("amount"), Value::Uint256(U256::from_u8(255))),
+				(
+					String::from("ids"),
+					Value::Array(vec![
+						Value::Felt(HexaString::from(vec![2])),
+						Value::Felt(HexaString::from(vec![3])),
+					]),
+				),
+				(
+					String::from("point"),
+					Value::Struct(vec![(
+						String::from("x"),
+						Value::Felt(HexaString::from(vec![4])),
+					)]),
+				),
+			],
+		};
+
+		assert_eq!(
+			"Transfer { from: 0x01, amount: 0x00000000000000000000000000000000000000000000000000000000000000ff, ids: [0x02, 0x03], point: { x: 0x04 } }",
+			event.to_string()
+		);
+	}
+
+	#[test]
+	fn display_solidity_values() {
+		let event = DecodedEvent {
+			name: String::from("Deposited"),
+			fields: vec![
+				(
+					String::from("to"),
+					Value::Address(HexaString::from(vec![1])),
+				),
+				(String::from("done"), Value::Bool(true)),
+				(String::from("delta"), Value::Int256(U256::MAX)),
+				(
+					String::from("payload"),
+					Value::Bytes(HexaString::from(vec![2, 3])),
+				),
+				(String::from("memo"), Value::String(String::from("hi"))),
+			],
+		};
+
+		assert_eq!(
+			"Deposited { to: 0x01, done: true, delta: -0x0000000000000000000000000000000000000000000000000000000000000001, payload: 0x0203, memo: \"hi\" }",
+			event.to_string()
+		);
+	}
+
+	#[test]
+	fn fields_can_be_looked_up_by_name() {
+		let event = DecodedEvent {
+			name: String::from("Transfer"),
+			fields: vec![(String::from("from"), Value::Felt(HexaString::from(vec![1])))],
+		};
+
+		assert_eq!(
+			Some(&Value::Felt(HexaString::from(vec![1]))),
+			event.field("from")
+		);
+		assert_eq!(None, event.field("to"));
+	}
+}
@@ -1,27 +1,69 @@
 use std::fmt::Display;
 
 use crypto_bigint::U256;
+use marketplace_indexer_macros::StarknetEvent;
 
-use super::ContractAddress;
+use super::{ContractAddress, DecodedEvent};
 
 pub type ContributorId = U256;
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum Event {
 	GithubIdentifierRegistered(GithubIdentifierRegisteredEvent),
+	GithubIdentifierUnregistered(GithubIdentifierUnregisteredEvent),
+	/**
+	 * Any other event, decoded from the ABI of its contract
+	 */
+	Decoded(DecodedEvent),
 }
 
-#[derive(Debug, Default, Clone, PartialEq, Eq)]
+#[derive(Debug, Default, Clone, PartialEq, Eq, StarknetEvent)]
+#[starknet_event(name = "GithubIdentifierRegistered")]
 pub struct GithubIdentifierRegisteredEvent {
 	pub profile_contract: ContractAddress,
 	pub contributor_id: ContributorId,
 	pub identifier: u128,
 }
 
+#[derive(Debug, Default, Clone, PartialEq, Eq, StarknetEvent)]
+#[starknet_event(name = "GithubIdentifierUnregistered")]
+pub struct GithubIdentifierUnregisteredEvent {
+	pub profile_contract: ContractAddress,
+	pub contributor_id: ContributorId,
+	pub identifier: u128,
+}
+
+impl Event {
+	/**
+	 * Name of the event type, as emitted on chain
+	 */
+	pub fn name(&self) -> &str {
+		match self {
+			Self::GithubIdentifierRegistered(_) => "GithubIdentifierRegistered",
+			Self::GithubIdentifierUnregistered(_) => "GithubIdentifierUnregistered",
+			Self::Decoded(event) => &event.name,
+		}
+	}
+}
+
+impl From<GithubIdentifierRegisteredEvent> for Event {
+	fn from(event: GithubIdentifierRegisteredEvent) -> Self {
+		Self::GithubIdentifierRegistered(event)
+	}
+}
+
+impl From<GithubIdentifierUnregisteredEvent> for Event {
+	fn from(event: GithubIdentifierUnregisteredEvent) -> Self {
+		Self::GithubIdentifierUnregistered(event)
+	}
+}
+
 impl Display for Event {
 	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
 		match self {
 			Self::GithubIdentifierRegistered(event) => event.fmt(f),
+			Self::GithubIdentifierUnregistered(event) => event.fmt(f),
+			Self::Decoded(event) => event.fmt(f),
 		}
 	}
 }
@@ -0,0 +1,14 @@
+use crate::domain::*;
+
+/**
+ * Context of the block and transaction log an event was emitted in
+ */
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct EventMetadata {
+	pub block_number: u64,
+	pub block_hash: BlockHash,
+	/// Seconds since the UNIX epoch
+	pub block_timestamp: u64,
+	pub contract_address: ContractAddress,
+	pub log_index: u64,
+}
@@ -0,0 +1,248 @@
+use std::{fmt::Display, str::FromStr};
+
+use thiserror::Error;
+
+/**
+ * A Solidity event signature, such as `Transfer(address indexed from, address to, uint256)`.
+ * Parameter names and `indexed` keywords are accepted but not kept: the signature displays in
+ * its canonical form, `Transfer(address,address,uint256)`, which is hashed into the event
+ * topic.
+ */
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EventSignature {
+	pub name: String,
+	pub params: Vec<SolidityType>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SolidityType {
+	Address,
+	Bool,
+	/** Size in bits */
+	Uint(usize),
+	/** Size in bits */
+	Int(usize),
+	/** Size in bytes */
+	FixedBytes(usize),
+	Bytes,
+	String,
+	Array(Box<SolidityType>),
+	FixedArray(Box<SolidityType>, usize),
+}
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum ParseEventSignatureError {
+	#[error("expected `<name>(<type>,...)`")]
+	InvalidFormat,
+	#[error("invalid event name `{0}`")]
+	InvalidName(String),
+	#[error("unsupported type `{0}`")]
+	UnsupportedType(String),
+}
+
+impl SolidityType {
+	/**
+	 * Dynamic types are encoded after the static ones, and hashed when indexed
+	 */
+	pub fn is_dynamic(&self) -> bool {
+		match self {
+			Self::Bytes | Self::String | Self::Array(_) => true,
+			Self::FixedArray(element_type, _) => element_type.is_dynamic(),
+			_ => false,
+		}
+	}
+}
+
+impl FromStr for SolidityType {
+	type Err = ParseEventSignatureError;
+
+	fn from_str(type_name: &str) -> Result<Self, Self::Err> {
+		let unsupported = || ParseEventSignatureError::UnsupportedType(type_name.to_owned());
+		let size = |digits: &str, default: usize| match digits {
+			"" => Ok(default),
+			digits => digits.parse::<usize>().map_err(|_| unsupported()),
+		};
+
+		if let Some(element_type) = type_name.strip_suffix(']') {
+			let (element_type, length) = element_type.rsplit_once('[').ok_or_else(unsupported)?;
+			let element_type = Box::new(element_type.parse()?);
+			return match length {
+				"" => Ok(Self::Array(element_type)),
+				// Zero-size arrays take no room in the encoding, they cannot be decoded
+				length => match length.parse() {
+					Ok(0) | Err(_) => Err(unsupported()),
+					Ok(length) => Ok(Self::FixedArray(element_type, length)),
+				},
+			};
+		}
+
+		let solidity_type = match type_name {
+			"address" => Self::Address,
+			"bool" => Self::Bool,
+			"bytes" => Self::Bytes,
+			"string" => Self::String,
+			_ =>
+				if let Some(bits) = type_name.strip_prefix("uint") {
+					Self::Uint(size(bits, 256)?)
+				} else if let Some(bits) = type_name.strip_prefix("int") {
+					Self::Int(size(bits, 256)?)
+				} else if let Some(bytes) = type_name.strip_prefix("bytes") {
+					Self::FixedBytes(size(bytes, 0)?)
+				} else {
+					return Err(unsupported());
+				},
+		};
+
+		match solidity_type {
+			Self::Uint(bits) | Self::Int(bits) if bits == 0 || bits > 256 || bits % 8 != 0 =>
+				Err(unsupported()),
+			Self::FixedBytes(bytes) if bytes == 0 || bytes > 32 => Err(unsupported()),
+			solidity_type => Ok(solidity_type),
+		}
+	}
+}
+
+impl Display for SolidityType {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match self {
+			Self::Address => write!(f, "address"),
+			Self::Bool => write!(f, "bool"),
+			Self::Uint(bits) => write!(f, "uint{}", bits),
+			Self::Int(bits) => write!(f, "int{}", bits),
+			Self::FixedBytes(bytes) => write!(f, "bytes{}", bytes),
+			Self::Bytes => write!(f, "bytes"),
+			Self::String => write!(f, "string"),
+			Self::Array(element_type) => write!(f, "{}[]", element_type),
+			Self::FixedArray(element_type, length) => write!(f, "{}[{}]", element_type, length),
+		}
+	}
+}
+
+impl FromStr for EventSignature {
+	type Err = ParseEventSignatureError;
+
+	fn from_str(signature: &str) -> Result<Self, Self::Err> {
+		let (name, params) = signature
+			.trim()
+			.strip_suffix(')')
+			.and_then(|signature| signature.split_once('('))
+			.ok_or(ParseEventSignatureError::InvalidFormat)?;
+
+		let name = name.trim();
+		let is_identifier = matches!(name.chars().next(), Some(c) if !c.is_ascii_digit())
+			&& name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '$');
+		if !is_identifier {
+			return Err(ParseEventSignatureError::InvalidName(name.to_owned()));
+		}
+
+		let params = match params.trim() {
+			"" => Vec::new(),
+			params => params
+				.split(',')
+				.map(|param| {
+					// The type comes first, optionally followed by `indexed` and a name
+					param
+						.split_whitespace()
+						.next()
+						.ok_or(ParseEventSignatureError::InvalidFormat)?
+						.parse()
+				})
+				.collect::<Result<_, _>>()?,
+		};
+
+		Ok(Self {
+			name: name.to_owned(),
+			params,
+		})
+	}
+}
+
+impl Display for EventSignature {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		write!(f, "{}(", self.name)?;
+		for (index, param) in self.params.iter().enumerate() {
+			if index > 0 {
+				write!(f, ",")?;
+			}
+			write!(f, "{}", param)?;
+		}
+		write!(f, ")")
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use rstest::*;
+
+	#[rstest]
+	#[case(
+		"Transfer(address,address,uint256)",
+		"Transfer(address,address,uint256)"
+	)]
+	#[case(
+		"Transfer(address indexed from, address indexed to, uint value)",
+		"Transfer(address,address,uint256)"
+	)]
+	#[case(" Paused() ", "Paused()")]
+	#[case(
+		"Deposited(bytes32 id, uint8[] amounts, string[2] names, int)",
+		"Deposited(bytes32,uint8[],string[2],int256)"
+	)]
+	fn signatures_are_displayed_in_canonical_form(#[case] signature: &str, #[case] expected: &str) {
+		assert_eq!(
+			expected,
+			signature.parse::<EventSignature>().unwrap().to_string()
+		);
+	}
+
+	#[rstest]
+	#[case("Transfer", ParseEventSignatureError::InvalidFormat)]
+	#[case("Transfer(address,)", ParseEventSignatureError::InvalidFormat)]
+	#[case("(address)", ParseEventSignatureError::InvalidName(String::new()))]
+	#[case(
+		"1Transfer()",
+		ParseEventSignatureError::InvalidName(String::from("1Transfer"))
+	)]
+	#[case(
+		"Transfer(felt)",
+		ParseEventSignatureError::UnsupportedType(String::from("felt"))
+	)]
+	#[case(
+		"Transfer(uint7)",
+		ParseEventSignatureError::UnsupportedType(String::from("uint7"))
+	)]
+	#[case(
+		"Transfer(bytes33)",
+		ParseEventSignatureError::UnsupportedType(String::from("bytes33"))
+	)]
+	#[case(
+		"Transfer(uint[x])",
+		ParseEventSignatureError::UnsupportedType(String::from("uint[x]"))
+	)]
+	#[case(
+		"Transfer(uint8[0][])",
+		ParseEventSignatureError::UnsupportedType(String::from("uint8[0]"))
+	)]
+	fn invalid_signatures_are_rejected(
+		#[case] signature: &str,
+		#[case] expected: ParseEventSignatureError,
+	) {
+		assert_eq!(expected, signature.parse::<EventSignature>().unwrap_err());
+	}
+
+	#[rstest]
+	#[case("uint256", false)]
+	#[case("bytes32", false)]
+	#[case("uint8[3]", false)]
+	#[case("bytes", true)]
+	#[case("string", true)]
+	#[case("address[]", true)]
+	#[case("string[3]", true)]
+	fn dynamic_types(#[case] type_name: &str, #[case] expected: bool) {
+		assert_eq!(
+			expected,
+			type_name.parse::<SolidityType>().unwrap().is_dynamic()
+		);
+	}
+}
@@ -1,11 +1,28 @@
 mod network;
-pub use network::{Network, StarknetChain};
+pub use network::{CustomNetwork, EthereumChain, Network, ParseNetworkError, StarknetChain};
 
 mod event_filter;
 pub use event_filter::EventFilter;
 
+mod event_signature;
+pub use event_signature::{EventSignature, ParseEventSignatureError, SolidityType};
+
 mod hexa_string;
-pub use hexa_string::{BlockHash, ContractAddress, HexaString};
+pub use hexa_string::{BlockHash, ContractAddress, HexaString, ParseHexaStringError};
+
+mod block_header;
+pub use block_header::BlockHeader;
+
+mod event_metadata;
+pub use event_metadata::EventMetadata;
 
 mod event;
-pub use event::{ContributorId, Event, GithubIdentifierRegisteredEvent};
+pub use event::{
+	ContributorId, Event, GithubIdentifierRegisteredEvent, GithubIdentifierUnregisteredEvent,
+};
+
+mod decoded_event;
+pub use decoded_event::{DecodedEvent, Value};
+
+mod raw_event;
+pub use raw_event::{DeadLetter, DecodingError, RawEvent};
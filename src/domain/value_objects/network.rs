@@ -1,6 +1,11 @@
+use std::{fmt::Display, str::FromStr};
+
+use thiserror::Error;
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum Network {
 	Starknet(StarknetChain),
+	Ethereum(EthereumChain),
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -8,4 +13,200 @@ pub enum StarknetChain {
 	Devnet,
 	Goerli,
 	Mainnet,
+	/** Any other network declared in the `apibara.toml` file of the server */
+	Custom(CustomNetwork),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EthereumChain {
+	Goerli,
+	Mainnet,
+	/** Any other network declared in the `apibara.toml` file of the server */
+	Custom(CustomNetwork),
+}
+
+/**
+ * The name of a custom network, only built by parsing so that it never names a known network
+ */
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CustomNetwork(String);
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum ParseNetworkError {
+	#[error("unknown network `{0}`, expected a `starknet-` or `ethereum-` prefixed name")]
+	Unknown(String),
+	#[error("unknown network `{name}`, did you mean `{known}`?")]
+	Misspelled { name: String, known: String },
+}
+
+impl Display for CustomNetwork {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		write!(f, "{}", self.0)
+	}
+}
+
+// Hardcoded strings are referenced in the server configuration.toml file
+impl Display for StarknetChain {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match self {
+			Self::Devnet => write!(f, "starknet-devnet"),
+			Self::Goerli => write!(f, "starknet-goerli"),
+			Self::Mainnet => write!(f, "starknet-mainnet"),
+			Self::Custom(name) => write!(f, "{}", name),
+		}
+	}
+}
+
+impl Display for EthereumChain {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match self {
+			Self::Goerli => write!(f, "ethereum-goerli"),
+			Self::Mainnet => write!(f, "ethereum-mainnet"),
+			Self::Custom(name) => write!(f, "{}", name),
+		}
+	}
+}
+
+impl Display for Network {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match self {
+			Self::Starknet(chain) => chain.fmt(f),
+			Self::Ethereum(chain) => chain.fmt(f),
+		}
+	}
+}
+
+impl StarknetChain {
+	const KNOWN: [Self; 3] = [Self::Devnet, Self::Goerli, Self::Mainnet];
+}
+
+impl EthereumChain {
+	const KNOWN: [Self; 2] = [Self::Goerli, Self::Mainnet];
+}
+
+/**
+ * A name this close to the one of a known network is taken for a typo, rather than for a custom
+ * network
+ */
+const MAX_TYPO_DISTANCE: usize = 2;
+
+/**
+ * The name of the custom network, unless it is the one of a known network or a typo of it
+ */
+fn custom<T: Display>(name: &str, known: &[T]) -> Result<CustomNetwork, ParseNetworkError> {
+	match known
+		.iter()
+		.map(ToString::to_string)
+		.find(|known| edit_distance(name, known) <= MAX_TYPO_DISTANCE)
+	{
+		Some(known) => Err(ParseNetworkError::Misspelled {
+			name: name.to_owned(),
+			known,
+		}),
+		None => Ok(CustomNetwork(name.to_owned())),
+	}
+}
+
+/**
+ * The Levenshtein distance between two names
+ */
+fn edit_distance(a: &str, b: &str) -> usize {
+	let b: Vec<char> = b.chars().collect();
+	let mut previous: Vec<usize> = (0..=b.len()).collect();
+	for (i, a) in a.chars().enumerate() {
+		let mut current = vec![i + 1];
+		for (j, b) in b.iter().enumerate() {
+			let substitution = previous[j] + usize::from(a != *b);
+			current.push(substitution.min(previous[j + 1] + 1).min(current[j] + 1));
+		}
+		previous = current;
+	}
+	previous[b.len()]
+}
+
+/**
+ * Custom networks are told apart by the prefix of their name
+ */
+impl FromStr for Network {
+	type Err = ParseNetworkError;
+
+	fn from_str(name: &str) -> Result<Self, Self::Err> {
+		match name {
+			"starknet-devnet" => Ok(Self::Starknet(StarknetChain::Devnet)),
+			"starknet-goerli" => Ok(Self::Starknet(StarknetChain::Goerli)),
+			"starknet-mainnet" => Ok(Self::Starknet(StarknetChain::Mainnet)),
+			"ethereum-goerli" => Ok(Self::Ethereum(EthereumChain::Goerli)),
+			"ethereum-mainnet" => Ok(Self::Ethereum(EthereumChain::Mainnet)),
+			name if name.starts_with("starknet-") => Ok(Self::Starknet(StarknetChain::Custom(
+				custom(name, &StarknetChain::KNOWN)?,
+			))),
+			name if name.starts_with("ethereum-") => Ok(Self::Ethereum(EthereumChain::Custom(
+				custom(name, &EthereumChain::KNOWN)?,
+			))),
+			name => Err(ParseNetworkError::Unknown(name.to_owned())),
+		}
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use rstest::*;
+
+	#[rstest]
+	#[case(Network::Starknet(StarknetChain::Devnet), "starknet-devnet")]
+	#[case(Network::Starknet(StarknetChain::Goerli), "starknet-goerli")]
+	#[case(Network::Starknet(StarknetChain::Mainnet), "starknet-mainnet")]
+	#[case(Network::Ethereum(EthereumChain::Goerli), "ethereum-goerli")]
+	#[case(Network::Ethereum(EthereumChain::Mainnet), "ethereum-mainnet")]
+	fn networks_can_be_parsed_and_displayed(#[case] network: Network, #[case] name: &str) {
+		assert_eq!(name, network.to_string());
+		assert_eq!(network, Network::from_str(name).unwrap());
+	}
+
+	#[rstest]
+	#[case("starknet-local")]
+	#[case("starknet-testnet2")]
+	#[case("ethereum-sepolia")]
+	fn custom_networks_keep_their_name(#[case] name: &str) {
+		let network = Network::from_str(name).unwrap();
+
+		assert!(
+			matches!(
+				&network,
+				Network::Starknet(StarknetChain::Custom(_))
+					| Network::Ethereum(EthereumChain::Custom(_))
+			),
+			"{:?}",
+			network
+		);
+		assert_eq!(name, network.to_string());
+	}
+
+	#[rstest]
+	#[case("starknet-goerlii", "starknet-goerli")]
+	#[case("starknet-mainet", "starknet-mainnet")]
+	#[case("starknet-devent", "starknet-devnet")]
+	#[case("ethereum-gorli", "ethereum-goerli")]
+	#[case("ethereum-Mainnet", "ethereum-mainnet")]
+	fn misspelled_networks_are_rejected(#[case] name: &str, #[case] known: &str) {
+		assert_eq!(
+			ParseNetworkError::Misspelled {
+				name: name.to_owned(),
+				known: known.to_owned(),
+			},
+			Network::from_str(name).unwrap_err()
+		);
+	}
+
+	#[rstest]
+	#[case("")]
+	#[case("goerli")]
+	#[case("solana-mainnet")]
+	fn unknown_networks_are_rejected(#[case] name: &str) {
+		assert_eq!(
+			ParseNetworkError::Unknown(name.to_owned()),
+			Network::from_str(name).unwrap_err()
+		);
+	}
 }
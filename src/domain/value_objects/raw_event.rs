@@ -0,0 +1,34 @@
+use thiserror::Error;
+
+use super::{EventMetadata, HexaString};
+
+/**
+ * An event as emitted on chain, before it is decoded
+ */
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct RawEvent {
+	pub topics: Vec<HexaString>,
+	pub data: Vec<HexaString>,
+}
+
+/**
+ * Why an event could not be decoded
+ */
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum DecodingError {
+	#[error("unsupported event")]
+	Unsupported,
+	#[error("invalid event: {0}")]
+	Invalid(String),
+}
+
+/**
+ * An event which could not be decoded, kept along with its context so that it can be investigated
+ * and replayed once the decoder is fixed
+ */
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DeadLetter {
+	pub event: RawEvent,
+	pub metadata: EventMetadata,
+	pub error: DecodingError,
+}
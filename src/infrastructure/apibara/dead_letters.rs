@@ -0,0 +1,79 @@
+use super::events::{self, Abi, ContractAbis};
+use crate::domain::*;
+
+/**
+ * Decodes the dead letters again, once the decoder or the ABIs of their contracts are fixed
+ */
+#[derive(Default)]
+pub struct DeadLetterDecoder {
+	abis: ContractAbis,
+}
+
+impl DeadLetterDecoder {
+	/**
+	 * Decode the events of the contract with the given address as described by the ABI, see
+	 * `Client::with_abi`
+	 */
+	pub fn with_abi(mut self, contract_address: ContractAddress, abi: Abi) -> Self {
+		self.abis.add(contract_address, abi);
+		self
+	}
+
+	/**
+	 * Decode the event of a dead letter kept by an indexer of the given network
+	 */
+	pub fn decode(
+		&self,
+		network: &Network,
+		dead_letter: &DeadLetter,
+	) -> Result<Event, DecodingError> {
+		let event = events::from_raw(network, &dead_letter.event, &dead_letter.metadata);
+		Ok(events::decode(&event, &self.abis)?)
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use starknet::core::utils::get_selector_from_name;
+
+	fn dead_letter(name: &str) -> DeadLetter {
+		DeadLetter {
+			event: RawEvent {
+				topics: vec![get_selector_from_name(name).unwrap().to_bytes_be().to_vec().into()],
+				data: vec![HexaString::from(vec![42])],
+			},
+			metadata: EventMetadata {
+				contract_address: ContractAddress::from(vec![86, 120]),
+				..Default::default()
+			},
+			error: DecodingError::Unsupported,
+		}
+	}
+
+	#[test]
+	fn decodes_dead_letters_with_the_abis() {
+		let abi: Abi = r#"[{
+			"type": "event",
+			"name": "Renamed",
+			"data": [{ "name": "name", "type": "felt" }]
+		}]"#
+		.parse()
+		.unwrap();
+		let decoder =
+			DeadLetterDecoder::default().with_abi(ContractAddress::from(vec![86, 120]), abi);
+		let network = Network::Starknet(StarknetChain::Goerli);
+
+		let event = decoder.decode(&network, &dead_letter("Renamed")).unwrap();
+		assert!(
+			matches!(&event, Event::Decoded(event) if event.name == "Renamed"),
+			"{:?}",
+			event
+		);
+
+		assert_eq!(
+			Err(DecodingError::Unsupported),
+			decoder.decode(&network, &dead_letter("Unknown"))
+		);
+	}
+}
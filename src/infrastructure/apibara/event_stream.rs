@@ -0,0 +1,206 @@
+use async_trait::async_trait;
+use futures::{StreamExt, TryStreamExt};
+use std::sync::Arc;
+use tokio::sync::mpsc::Sender;
+
+use super::{
+	apibara::{
+		connect_indexer_response::Message as ResponseMessage, ConnectIndexerRequest,
+		ConnectIndexerResponse, IndexerConnected, NewBlock, NewEvents, Reorg,
+	},
+	indexing_service::send_ack_request,
+	*,
+};
+use crate::domain::*;
+
+#[async_trait]
+impl StreamingService for Client {
+	async fn event_stream(&self, indexer: &Indexer) -> Result<EventStream, IndexingServiceError> {
+		let (tx, response_stream) = self.connect(&indexer.id).await?;
+		let acknowledger: Arc<dyn BlockAcknowledger> = Arc::new(Acknowledger(tx));
+		let recorder = self.recorder.clone();
+		let abis = self.abis.clone();
+		let indexer_id = indexer.id.clone();
+
+		let stream = response_stream
+			.map_err(|error| IndexingServiceError::Receive(error.to_string()))
+			.try_filter_map(move |response| {
+				let recorder = recorder.clone();
+				let abis = abis.clone();
+				let acknowledger = acknowledger.clone();
+				let indexer_id = indexer_id.clone();
+				async move {
+					if let Some(recorder) = &recorder {
+						recorder.record(&indexer_id, &response).await;
+					}
+					message(response, &abis, &acknowledger)
+				}
+			});
+
+		Ok(stream.boxed())
+	}
+}
+
+/**
+ * Acks the blocks on the indexing stream they were received from
+ */
+pub(super) struct Acknowledger(pub(super) Sender<ConnectIndexerRequest>);
+
+#[async_trait]
+impl BlockAcknowledger for Acknowledger {
+	async fn ack(&self, block_hash: &BlockHash) -> Result<(), IndexingServiceError> {
+		send_ack_request(&self.0, block_hash).await
+	}
+}
+
+/**
+ * Convert a response of the indexing server into a message of the event stream. This is the
+ * only place where responses are decoded, the indexing service consumes the same messages.
+ * Returns None for the responses carrying nothing to consume.
+ */
+pub(super) fn message(
+	response: ConnectIndexerResponse,
+	abis: &ContractAbis,
+	acknowledger: &Arc<dyn BlockAcknowledger>,
+) -> Result<Option<IndexerMessage>, IndexingServiceError> {
+	let message = match response.message {
+		Some(message) => message,
+		None => return Ok(None),
+	};
+
+	let message = match message {
+		ResponseMessage::Connected(IndexerConnected {
+			indexer: Some(indexer),
+			version: _,
+		}) => Some(IndexerMessage::Connected(indexer.id.into())),
+
+		ResponseMessage::NewBlock(NewBlock {
+			new_head: Some(new_head),
+		}) => Some(IndexerMessage::NewBlock(new_head.hash.into())),
+
+		ResponseMessage::Reorg(Reorg {
+			new_head: Some(new_head),
+		}) => Some(IndexerMessage::Reorg(new_head.into())),
+
+		// Projections could not be rolled back without knowing where to
+		ResponseMessage::Reorg(Reorg { new_head: None }) =>
+			return Err(IndexingServiceError::ReorgWithoutHead),
+
+		ResponseMessage::NewEvents(NewEvents { block, events }) => {
+			let header = block.clone().unwrap_or_default();
+			let mut decoded_events = Vec::new();
+			let mut undecodable_events = Vec::new();
+			for apibara_event in events {
+				let metadata = events::metadata(&header, &apibara_event);
+				match events::decode(&apibara_event, abis) {
+					Ok(event) => decoded_events.push((event, metadata)),
+					Err(error) => undecodable_events.push(DeadLetter {
+						event: events::raw(&apibara_event),
+						metadata,
+						error: error.into(),
+					}),
+				}
+			}
+
+			let ack = block
+				.as_ref()
+				.map(|header| AckHandle::new(header.hash.clone().into(), acknowledger.clone()));
+
+			Some(IndexerMessage::NewEvents {
+				block: header.into(),
+				events: decoded_events,
+				undecodable_events,
+				ack,
+			})
+		},
+
+		_ => None,
+	};
+	Ok(message)
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use crate::infrastructure::apibara::fake_server::{self, FakeServer, Step};
+
+	fn indexer() -> Indexer {
+		Indexer::new(
+			"ID".into(),
+			Network::Starknet(StarknetChain::Devnet),
+			0,
+			Vec::new(),
+		)
+	}
+
+	#[tokio::test]
+	async fn streams_messages_and_acks_on_demand_end_to_end() {
+		let server = FakeServer::start(vec![vec![
+			fake_server::connected("ID"),
+			fake_server::new_block(vec![1], 1),
+			fake_server::new_events(vec![1], 1, vec![]),
+			Step::WaitForAck(vec![1]),
+			fake_server::reorg(vec![0], 0),
+			Step::Close,
+		]])
+		.await;
+		let client = server.client();
+
+		let mut stream = client.event_stream(&indexer()).await.unwrap();
+
+		assert!(matches!(
+			stream.next().await,
+			Some(Ok(IndexerMessage::Connected(id))) if id == IndexerId::from("ID")
+		));
+		assert!(matches!(
+			stream.next().await,
+			Some(Ok(IndexerMessage::NewBlock(hash))) if hash == BlockHash::from(vec![1])
+		));
+		match stream.next().await {
+			Some(Ok(IndexerMessage::NewEvents {
+				block, events, ack, ..
+			})) => {
+				assert_eq!(1, block.number);
+				assert!(events.is_empty());
+				assert!(server.acks().is_empty());
+				ack.unwrap().ack().await.unwrap();
+			},
+			message => panic!("unexpected message: {:?}", message),
+		}
+		assert!(matches!(
+			stream.next().await,
+			Some(Ok(IndexerMessage::Reorg(new_head))) if new_head.number == 0
+		));
+		assert!(stream.next().await.is_none());
+
+		assert_eq!(vec![BlockHash::from(vec![1])], server.acks());
+	}
+
+	#[test]
+	fn fails_on_a_reorg_without_new_head() {
+		let acknowledger: Arc<dyn BlockAcknowledger> = Arc::new(MockBlockAcknowledger::new());
+		let response = ConnectIndexerResponse {
+			message: Some(ResponseMessage::Reorg(Reorg { new_head: None })),
+		};
+
+		let result = message(response, &ContractAbis::default(), &acknowledger);
+		assert!(matches!(
+			result,
+			Err(IndexingServiceError::ReorgWithoutHead)
+		));
+	}
+
+	#[tokio::test]
+	async fn forwards_connection_errors() {
+		let channel = tonic::transport::Endpoint::from_static("http://127.0.0.1:1").connect_lazy();
+		let client = Client::new(apibara::indexer_manager_client::IndexerManagerClient::new(
+			channel,
+		));
+
+		let result = client.event_stream(&indexer()).await;
+		assert!(matches!(
+			result,
+			Err(IndexingServiceError::Connection { .. })
+		));
+	}
+}
@@ -0,0 +1,693 @@
+use std::{collections::VecDeque, path::Path, str::FromStr};
+
+use super::{solidity, topics::*, FromEventError};
+use crate::{domain::*, infrastructure::apibara::proto::TopicValue};
+use crypto_bigint::U256;
+use serde::Deserialize;
+use starknet::core::{types::FieldElement, utils::get_selector_from_name};
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum Error {
+	#[error("unable to read the ABI file `{path}`: {details}")]
+	Read { path: String, details: String },
+	#[error("invalid ABI: {0}")]
+	Parse(#[from] serde_json::Error),
+	#[error("unsupported type `{type_name}` in the ABI of `{name}`")]
+	UnsupportedType { name: String, type_name: String },
+	#[error("invalid name `{0}` in the ABI")]
+	InvalidName(String),
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum Entry {
+	/**
+	 * Cairo events list their `data`, Solidity events their `inputs`
+	 */
+	Event {
+		name: String,
+		data: Option<Vec<Member>>,
+		inputs: Option<Vec<solidity::Input>>,
+		#[serde(default)]
+		anonymous: bool,
+	},
+	Struct {
+		name: String,
+		members: Vec<Member>,
+	},
+	#[serde(other)]
+	Other,
+}
+
+#[derive(Debug, Deserialize)]
+struct Member {
+	name: String,
+	#[serde(rename = "type")]
+	type_name: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Type {
+	Felt,
+	Uint256,
+	/**
+	 * A Cairo `T*` along with its `<name>_len` member: the length comes first in the data
+	 */
+	Array(Box<Type>),
+	Struct(Vec<(String, Type)>),
+}
+
+impl Type {
+	/**
+	 * Whether the type takes no data, like a struct without members
+	 */
+	fn is_empty(&self) -> bool {
+		match self {
+			Self::Struct(fields) => fields.iter().all(|(_, field_type)| field_type.is_empty()),
+			_ => false,
+		}
+	}
+}
+
+#[derive(Debug, Clone)]
+struct EventDecoder {
+	name: String,
+	selector: FieldElement,
+	fields: Vec<(String, Type)>,
+}
+
+/**
+ * Decodes the events of a Cairo or Solidity contract, as described by its JSON ABI.
+ * Supported Cairo types are `felt`, `Uint256`, structs and arrays. Supported Solidity types are
+ * the elementary ones and their arrays, tuples are not. Anonymous Solidity events are ignored,
+ * as they cannot be told apart.
+ */
+#[derive(Debug, Default, Clone)]
+pub struct Abi {
+	events: Vec<EventDecoder>,
+	solidity_events: Vec<solidity::EventDecoder>,
+}
+
+impl Abi {
+	pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self, Error> {
+		let content = std::fs::read_to_string(&path).map_err(|error| Error::Read {
+			path: path.as_ref().display().to_string(),
+			details: error.to_string(),
+		})?;
+		content.parse()
+	}
+
+	/**
+	 * Add the events of another ABI. Events already known are kept as is.
+	 */
+	pub fn merge(&mut self, other: Abi) {
+		for event in other.events {
+			if !self.events.iter().any(|known| known.selector == event.selector) {
+				self.events.push(event);
+			}
+		}
+		for event in other.solidity_events {
+			if !self.solidity_events.iter().any(|known| known.topic == event.topic) {
+				self.solidity_events.push(event);
+			}
+		}
+	}
+
+	/**
+	 * Returns None if the selector does not match any event of the ABI
+	 */
+	pub fn decode(
+		&self,
+		selector: &FieldElement,
+		data: VecDeque<TopicValue>,
+	) -> Option<Result<DecodedEvent, FromEventError>> {
+		let decoder = self.events.iter().find(|event| &event.selector == selector)?;
+		Some(decoder.decode(data))
+	}
+
+	/**
+	 * Decode an Ethereum log. Returns None if its first topic does not match any event of the
+	 * ABI.
+	 */
+	pub fn decode_log(
+		&self,
+		topics: &[TopicValue],
+		data: &[u8],
+	) -> Option<Result<DecodedEvent, FromEventError>> {
+		let topic = &topics.first()?.value;
+		let decoder = self.solidity_events.iter().find(|event| &event.topic == topic)?;
+		Some(decoder.decode(topics, data))
+	}
+}
+
+/**
+ * The ABIs of the decoded contracts. Events are decoded with the ABI of the contract which
+ * emitted them, as several contracts may declare events with the same selector but different
+ * data, like the `Transfer` events of ERC20 and ERC721 contracts.
+ */
+#[derive(Debug, Default, Clone)]
+pub struct ContractAbis {
+	contracts: Vec<(ContractAddress, Abi)>,
+}
+
+impl ContractAbis {
+	/**
+	 * Decode the events of the given contract with the ABI. ABIs added for the same contract
+	 * are merged.
+	 */
+	pub fn add(&mut self, contract_address: ContractAddress, abi: Abi) {
+		match self.contracts.iter_mut().find(|(address, _)| address == &contract_address) {
+			Some((_, known)) => known.merge(abi),
+			None => self.contracts.push((contract_address, abi)),
+		}
+	}
+
+	fn of(&self, contract_address: &ContractAddress) -> Option<&Abi> {
+		self.contracts
+			.iter()
+			.find(|(address, _)| address == contract_address)
+			.map(|(_, abi)| abi)
+	}
+
+	/**
+	 * Returns None if the contract has no ABI or if the selector does not match any of its
+	 * events
+	 */
+	pub fn decode(
+		&self,
+		contract_address: &ContractAddress,
+		selector: &FieldElement,
+		data: VecDeque<TopicValue>,
+	) -> Option<Result<DecodedEvent, FromEventError>> {
+		self.of(contract_address)?.decode(selector, data)
+	}
+
+	/**
+	 * Returns None if the contract has no ABI or if the first topic does not match any of its
+	 * events
+	 */
+	pub fn decode_log(
+		&self,
+		contract_address: &ContractAddress,
+		topics: &[TopicValue],
+		data: &[u8],
+	) -> Option<Result<DecodedEvent, FromEventError>> {
+		self.of(contract_address)?.decode_log(topics, data)
+	}
+}
+
+impl FromStr for Abi {
+	type Err = Error;
+
+	fn from_str(content: &str) -> Result<Self, Self::Err> {
+		let entries: Vec<Entry> = serde_json::from_str(content)?;
+
+		let structs: Vec<(&str, &[Member])> = entries
+			.iter()
+			.filter_map(|entry| match entry {
+				Entry::Struct { name, members } => Some((name.as_str(), members.as_slice())),
+				_ => None,
+			})
+			.collect();
+
+		let events = entries
+			.iter()
+			.filter_map(|entry| match entry {
+				Entry::Event {
+					name,
+					data: Some(data),
+					inputs: None,
+					..
+				} => Some((name, data)),
+				_ => None,
+			})
+			.map(|(name, data)| {
+				Ok(EventDecoder {
+					name: name.clone(),
+					selector: get_selector_from_name(name)
+						.map_err(|_| Error::InvalidName(name.clone()))?,
+					fields: resolve_members(name, data, &structs, &mut Vec::new())?,
+				})
+			})
+			.collect::<Result<_, Error>>()?;
+
+		let solidity_events = entries
+			.iter()
+			.filter_map(|entry| match entry {
+				Entry::Event {
+					name,
+					inputs: Some(inputs),
+					anonymous: false,
+					..
+				} => Some((name, inputs)),
+				_ => None,
+			})
+			.map(|(name, inputs)| {
+				let inputs = inputs
+					.iter()
+					.map(|input| {
+						let param_type =
+							input.type_name.parse().map_err(|_| Error::UnsupportedType {
+								name: name.clone(),
+								type_name: input.type_name.clone(),
+							})?;
+						Ok((input.name.clone(), param_type, input.indexed))
+					})
+					.collect::<Result<_, Error>>()?;
+				Ok(solidity::EventDecoder::new(name.clone(), inputs))
+			})
+			.collect::<Result<_, Error>>()?;
+
+		Ok(Self {
+			events,
+			solidity_events,
+		})
+	}
+}
+
+/**
+ * Resolve the types of the members of an event or a struct.
+ * `resolving` holds the structs being resolved, to reject recursive definitions.
+ */
+fn resolve_members<'a>(
+	name: &str,
+	members: &'a [Member],
+	structs: &[(&'a str, &'a [Member])],
+	resolving: &mut Vec<&'a str>,
+) -> Result<Vec<(String, Type)>, Error> {
+	let unsupported = |type_name: &str| Error::UnsupportedType {
+		name: name.to_owned(),
+		type_name: type_name.to_owned(),
+	};
+
+	let mut fields: Vec<(String, Type)> = Vec::new();
+	for member in members {
+		let field_type = match member.type_name.strip_suffix('*') {
+			Some(element_type) => {
+				let length_name = format!("{}_len", member.name);
+				match fields.pop() {
+					Some((name, Type::Felt)) if name == length_name => (),
+					_ => return Err(unsupported(&member.type_name)),
+				}
+				match resolve_type(element_type, structs, resolving)? {
+					Some(element_type) if !element_type.is_empty() =>
+						Type::Array(Box::new(element_type)),
+					_ => return Err(unsupported(&member.type_name)),
+				}
+			},
+			None => resolve_type(&member.type_name, structs, resolving)?
+				.ok_or_else(|| unsupported(&member.type_name))?,
+		};
+		fields.push((member.name.clone(), field_type));
+	}
+
+	Ok(fields)
+}
+
+fn resolve_type<'a>(
+	type_name: &str,
+	structs: &[(&'a str, &'a [Member])],
+	resolving: &mut Vec<&'a str>,
+) -> Result<Option<Type>, Error> {
+	let resolved = match type_name {
+		"felt" => Some(Type::Felt),
+		"Uint256" => Some(Type::Uint256),
+		_ => match structs.iter().find(|(name, _)| *name == type_name) {
+			Some((name, _)) if resolving.contains(name) => None,
+			Some((name, members)) => {
+				resolving.push(name);
+				let fields = resolve_members(name, members, structs, resolving)?;
+				resolving.pop();
+				Some(Type::Struct(fields))
+			},
+			None => None,
+		},
+	};
+	Ok(resolved)
+}
+
+impl EventDecoder {
+	fn decode(&self, mut data: VecDeque<TopicValue>) -> Result<DecodedEvent, FromEventError> {
+		let fields = decode_fields(&self.fields, &mut data)?;
+		if !data.is_empty() {
+			return Err(FromEventError::Invalid);
+		}
+
+		Ok(DecodedEvent {
+			name: self.name.clone(),
+			fields,
+		})
+	}
+}
+
+fn decode_fields(
+	fields: &[(String, Type)],
+	data: &mut VecDeque<TopicValue>,
+) -> Result<Vec<(String, Value)>, TopicError> {
+	fields
+		.iter()
+		.map(|(name, field_type)| Ok((name.clone(), decode_value(field_type, data)?)))
+		.collect()
+}
+
+fn decode_value(value_type: &Type, data: &mut VecDeque<TopicValue>) -> Result<Value, TopicError> {
+	let value = match value_type {
+		Type::Felt => Value::Felt(data.pop_front_as()?),
+		Type::Uint256 => {
+			let value: U256 = data.pop_front_as()?;
+			Value::Uint256(value)
+		},
+		Type::Array(element_type) => {
+			let length: u128 = data.pop_front_as()?;
+			// Elements are never empty, so each one takes at least a felt of the data
+			if length > data.len() as u128 {
+				return Err(TopicError::Missing);
+			}
+			let values = (0..length)
+				.map(|_| decode_value(element_type, data))
+				.collect::<Result<_, _>>()?;
+			Value::Array(values)
+		},
+		Type::Struct(fields) => Value::Struct(decode_fields(fields, data)?),
+	};
+	Ok(value)
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use rstest::*;
+
+	const ABI: &str = r#"[
+		{
+			"type": "struct",
+			"name": "Uint256",
+			"size": 2,
+			"members": [
+				{ "name": "low", "offset": 0, "type": "felt" },
+				{ "name": "high", "offset": 1, "type": "felt" }
+			]
+		},
+		{
+			"type": "struct",
+			"name": "Point",
+			"size": 2,
+			"members": [
+				{ "name": "x", "offset": 0, "type": "felt" },
+				{ "name": "y", "offset": 1, "type": "felt" }
+			]
+		},
+		{
+			"type": "event",
+			"name": "Moved",
+			"keys": [],
+			"data": [
+				{ "name": "owner", "type": "felt" },
+				{ "name": "amount", "type": "Uint256" },
+				{ "name": "path_len", "type": "felt" },
+				{ "name": "path", "type": "Point*" }
+			]
+		},
+		{
+			"type": "function",
+			"name": "move",
+			"inputs": [],
+			"outputs": []
+		}
+	]"#;
+
+	fn felt(value: u8) -> TopicValue {
+		let mut bytes = vec![0; 32];
+		bytes[31] = value;
+		TopicValue { value: bytes }
+	}
+
+	fn felt_value(value: u8) -> Value {
+		Value::Felt(HexaString::from(vec![value]))
+	}
+
+	fn moved_selector() -> FieldElement {
+		get_selector_from_name("Moved").unwrap()
+	}
+
+	#[fixture]
+	fn abi() -> Abi {
+		ABI.parse().unwrap()
+	}
+
+	#[rstest]
+	fn decodes_felts_uint256_arrays_and_structs(abi: Abi) {
+		let data = vec![
+			felt(1),
+			felt(2),
+			felt(0),
+			felt(2),
+			felt(3),
+			felt(4),
+			felt(5),
+			felt(6),
+		];
+
+		let event = abi.decode(&moved_selector(), data.into()).unwrap().unwrap();
+
+		assert_eq!(
+			DecodedEvent {
+				name: String::from("Moved"),
+				fields: vec![
+					(String::from("owner"), felt_value(1)),
+					(String::from("amount"), Value::Uint256(U256::from_u8(2))),
+					(
+						String::from("path"),
+						Value::Array(vec![
+							Value::Struct(vec![
+								(String::from("x"), felt_value(3)),
+								(String::from("y"), felt_value(4)),
+							]),
+							Value::Struct(vec![
+								(String::from("x"), felt_value(5)),
+								(String::from("y"), felt_value(6)),
+							]),
+						])
+					),
+				],
+			},
+			event
+		);
+	}
+
+	#[rstest]
+	fn unknown_selectors_are_not_decoded(abi: Abi) {
+		let selector = get_selector_from_name("Unknown").unwrap();
+		assert!(abi.decode(&selector, VecDeque::new()).is_none());
+	}
+
+	#[rstest]
+	#[case(vec![felt(1), felt(2)])]
+	#[case(vec![felt(1), felt(2), felt(0), felt(1), felt(3)])]
+	#[case(vec![felt(1), felt(2), felt(0), felt(0), felt(9)])]
+	fn data_not_matching_the_abi_is_invalid(abi: Abi, #[case] data: Vec<TopicValue>) {
+		let result = abi.decode(&moved_selector(), data.into()).unwrap();
+		assert!(
+			matches!(
+				result,
+				Err(FromEventError::Invalid | FromEventError::InvalidTopic(_))
+			),
+			"{:?}",
+			result
+		);
+	}
+
+	#[rstest]
+	fn array_lengths_beyond_the_data_are_invalid(abi: Abi) {
+		let length = TopicValue {
+			value: [vec![0; 16], vec![255; 16]].concat(),
+		};
+		let data = vec![felt(1), felt(2), felt(0), length, felt(3), felt(4)];
+
+		let result = abi.decode(&moved_selector(), data.into()).unwrap();
+		assert!(
+			matches!(
+				result,
+				Err(FromEventError::InvalidTopic(TopicError::Missing))
+			),
+			"{:?}",
+			result
+		);
+	}
+
+	#[rstest]
+	#[case(
+		r#"[{ "type": "event", "name": "E", "data": [{ "name": "a", "type": "(felt, felt)" }] }]"#
+	)]
+	#[case(r#"[{ "type": "event", "name": "E", "data": [{ "name": "a", "type": "felt*" }] }]"#)]
+	#[case(r#"[{ "type": "event", "name": "E", "data": [{ "name": "a", "type": "Unknown" }] }]"#)]
+	#[case(
+		r#"[
+			{ "type": "struct", "name": "S", "members": [{ "name": "s", "type": "S" }] },
+			{ "type": "event", "name": "E", "data": [{ "name": "a", "type": "S" }] }
+		]"#
+	)]
+	#[case(
+		r#"[
+			{ "type": "struct", "name": "S", "members": [] },
+			{ "type": "event", "name": "E", "data": [
+				{ "name": "a_len", "type": "felt" },
+				{ "name": "a", "type": "S*" }
+			] }
+		]"#
+	)]
+	#[case(
+		r#"[
+			{ "type": "struct", "name": "S", "members": [] },
+			{ "type": "struct", "name": "T", "members": [{ "name": "s", "type": "S" }] },
+			{ "type": "event", "name": "E", "data": [
+				{ "name": "a_len", "type": "felt" },
+				{ "name": "a", "type": "T*" }
+			] }
+		]"#
+	)]
+	fn unsupported_types_are_rejected(#[case] abi: &str) {
+		let result = abi.parse::<Abi>();
+		assert!(
+			matches!(result, Err(Error::UnsupportedType { .. })),
+			"{:?}",
+			result
+		);
+	}
+
+	const SOLIDITY_ABI: &str = r#"[
+		{
+			"type": "event",
+			"name": "Transfer",
+			"anonymous": false,
+			"inputs": [
+				{ "name": "from", "type": "address", "indexed": true },
+				{ "name": "to", "type": "address", "indexed": true },
+				{ "name": "value", "type": "uint256", "indexed": false }
+			]
+		},
+		{
+			"type": "event",
+			"name": "Anonymous",
+			"anonymous": true,
+			"inputs": []
+		},
+		{
+			"type": "function",
+			"name": "transfer",
+			"inputs": [{ "name": "to", "type": "address" }],
+			"outputs": []
+		}
+	]"#;
+
+	#[test]
+	fn decodes_solidity_logs() {
+		let abi: Abi = SOLIDITY_ABI.parse().unwrap();
+		let topics = vec![
+			TopicValue {
+				value: solidity::topic(&"Transfer(address,address,uint256)".parse().unwrap()),
+			},
+			felt(1),
+			felt(2),
+		];
+
+		let event = abi.decode_log(&topics, &felt(3).value).unwrap().unwrap();
+
+		assert_eq!("Transfer", event.name);
+		assert_eq!(
+			Some(&Value::Uint256(U256::from_u8(3))),
+			event.field("value")
+		);
+		assert_eq!(1, abi.solidity_events.len());
+		assert!(abi.events.is_empty());
+	}
+
+	#[rstest]
+	fn unknown_topics_are_not_decoded(abi: Abi) {
+		assert!(abi.decode_log(&[felt(1)], &[]).is_none());
+		assert!(abi.decode_log(&[], &[]).is_none());
+	}
+
+	#[test]
+	fn unsupported_solidity_types_are_rejected() {
+		let result = r#"[{
+			"type": "event",
+			"name": "E",
+			"inputs": [{ "name": "a", "type": "tuple", "indexed": false }]
+		}]"#
+		.parse::<Abi>();
+
+		assert!(
+			matches!(result, Err(Error::UnsupportedType { .. })),
+			"{:?}",
+			result
+		);
+	}
+
+	#[test]
+	fn invalid_json_is_rejected() {
+		assert!(matches!("{}".parse::<Abi>(), Err(Error::Parse(_))));
+	}
+
+	#[rstest]
+	fn merged_abis_keep_the_known_events(mut abi: Abi) {
+		let other: Abi = r#"[
+			{ "type": "event", "name": "Moved", "data": [] },
+			{ "type": "event", "name": "Stopped", "data": [] }
+		]"#
+		.parse()
+		.unwrap();
+
+		abi.merge(other);
+
+		assert_eq!(2, abi.events.len());
+		assert_eq!(3, abi.events[0].fields.len());
+	}
+
+	#[rstest]
+	fn events_are_decoded_with_the_abi_of_their_contract(abi: Abi) {
+		let other: Abi =
+			r#"[{ "type": "event", "name": "Moved", "data": [{ "name": "to", "type": "felt" }] }]"#
+				.parse()
+				.unwrap();
+		let mut abis = ContractAbis::default();
+		abis.add(ContractAddress::from(vec![1]), abi);
+		abis.add(ContractAddress::from(vec![2]), other);
+
+		let event = abis
+			.decode(
+				&ContractAddress::from(vec![2]),
+				&moved_selector(),
+				vec![felt(7)].into(),
+			)
+			.unwrap()
+			.unwrap();
+		assert_eq!(Some(&felt_value(7)), event.field("to"));
+
+		let result = abis.decode(
+			&ContractAddress::from(vec![1]),
+			&moved_selector(),
+			vec![felt(7)].into(),
+		);
+		assert!(matches!(
+			result,
+			Some(Err(FromEventError::InvalidTopic(TopicError::Missing)))
+		));
+
+		let unknown = ContractAddress::from(vec![3]);
+		assert!(abis.decode(&unknown, &moved_selector(), vec![felt(7)].into()).is_none());
+		assert!(abis.decode_log(&unknown, &[felt(1)], &[]).is_none());
+	}
+
+	#[rstest]
+	fn abis_of_the_same_contract_are_merged(abi: Abi) {
+		let other: Abi = r#"[{ "type": "event", "name": "Stopped", "data": [] }]"#.parse().unwrap();
+		let mut abis = ContractAbis::default();
+		abis.add(ContractAddress::from(vec![1]), abi);
+		abis.add(ContractAddress::from(vec![0, 1]), other);
+
+		assert_eq!(1, abis.contracts.len());
+		assert_eq!(2, abis.contracts[0].1.events.len());
+	}
+}
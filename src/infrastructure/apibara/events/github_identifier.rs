@@ -1,44 +1,30 @@
-use std::{collections::VecDeque, fmt::Display};
+use std::fmt::Display;
 
-use super::{topics::*, FromEventError};
 use crate::domain::*;
-use crate::infrastructure::apibara::proto::TopicValue;
-use crypto_bigint::U256;
-use starknet::core::{types::FieldElement, utils::get_selector_from_name};
 
-pub fn selector() -> FieldElement {
-	get_selector_from_name("GithubIdentifierRegistered").unwrap()
-}
-
-impl TryFrom<VecDeque<TopicValue>> for GithubIdentifierRegisteredEvent {
-	type Error = FromEventError;
-
-	fn try_from(mut data: VecDeque<TopicValue>) -> Result<Self, Self::Error> {
-		let profile_contract: ContractAddress =
-			data.pop_front_as().map_err(|_| Self::Error::Invalid)?;
-		let contributor_id: U256 = data.pop_front_as().map_err(|_| Self::Error::Invalid)?;
-		let identifier: u128 = data.pop_front_as().map_err(|_| Self::Error::Invalid)?;
-
-		Ok(Self {
-			profile_contract,
-			contributor_id,
-			identifier,
-		})
+impl Display for GithubIdentifierRegisteredEvent {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		write!(f, "GithubIdentifierRegistered {{ profile_contract: {}, contributor_id: 0x{:x}, identifier: {} }}", self.profile_contract, self.contributor_id, self.identifier)
 	}
 }
 
-impl Display for GithubIdentifierRegisteredEvent {
+impl Display for GithubIdentifierUnregisteredEvent {
 	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-		write!(f, "GithubIdentifierRegistered {{ profile_contract: {}, contributor_id: 0x{:x}, identifier: {} }}", self.profile_contract, self.contributor_id, self.identifier)
+		write!(f, "GithubIdentifierUnregistered {{ profile_contract: {}, contributor_id: 0x{:x}, identifier: {} }}", self.profile_contract, self.contributor_id, self.identifier)
 	}
 }
 
 #[cfg(test)]
 mod test {
-	use std::str::FromStr;
+	use std::{collections::VecDeque, str::FromStr};
 
-	use super::*;
+	use super::{
+		super::{topics::TopicError, FromEventError, StarknetEvent},
+		*,
+	};
+	use crate::infrastructure::apibara::proto::TopicValue;
 	use rstest::*;
+	use starknet::core::utils::get_selector_from_name;
 
 	#[fixture]
 	fn apibara_event_data() -> VecDeque<TopicValue> {
@@ -73,18 +59,72 @@ mod test {
 
 	#[rstest]
 	fn create_event_from_apibara(apibara_event_data: VecDeque<TopicValue>) {
-		let result = apibara_event_data.try_into();
+		let result: Result<GithubIdentifierRegisteredEvent, _> = apibara_event_data.try_into();
 		assert!(result.is_ok(), "{}", result.err().unwrap());
 		assert_eq!(
-			GithubIdentifierRegisteredEvent {
+			Event::GithubIdentifierRegistered(GithubIdentifierRegisteredEvent {
 				profile_contract: ContractAddress::from_str(
 					"0x004176872b71583cb9bc3671db28f26e7f426a7c0764613a0838bb99ef373aa6"
 				)
 				.unwrap(),
 				contributor_id: ContributorId::from_u128(203),
 				identifier: 107289007
-			},
-			result.unwrap()
+			}),
+			result.unwrap().into()
+		);
+	}
+
+	#[rstest]
+	fn create_unregistered_event_from_apibara(apibara_event_data: VecDeque<TopicValue>) {
+		let result: Result<GithubIdentifierUnregisteredEvent, _> = apibara_event_data.try_into();
+		assert!(result.is_ok(), "{}", result.err().unwrap());
+		assert_eq!(
+			Event::GithubIdentifierUnregistered(GithubIdentifierUnregisteredEvent {
+				profile_contract: ContractAddress::from_str(
+					"0x004176872b71583cb9bc3671db28f26e7f426a7c0764613a0838bb99ef373aa6"
+				)
+				.unwrap(),
+				contributor_id: ContributorId::from_u128(203),
+				identifier: 107289007
+			}),
+			result.unwrap().into()
+		);
+	}
+
+	#[rstest]
+	fn create_event_from_invalid_apibara_data() {
+		let result: Result<GithubIdentifierUnregisteredEvent, _> = VecDeque::new().try_into();
+		assert!(matches!(
+			result,
+			Err(FromEventError::InvalidField {
+				event: "GithubIdentifierUnregistered",
+				field: "profile_contract",
+				details: TopicError::Missing,
+			})
+		));
+	}
+
+	#[rstest]
+	fn decoding_errors_name_the_failing_field(mut apibara_event_data: VecDeque<TopicValue>) {
+		apibara_event_data.pop_back();
+
+		let result = GithubIdentifierRegisteredEvent::decode(apibara_event_data);
+		assert_eq!(
+			"Invalid field `identifier` in event `GithubIdentifierRegistered`: Missing topic in \
+			 event",
+			result.unwrap_err().to_string()
+		);
+	}
+
+	#[test]
+	fn selectors_are_computed_from_the_event_names() {
+		assert_eq!(
+			get_selector_from_name("GithubIdentifierRegistered").unwrap(),
+			GithubIdentifierRegisteredEvent::selector()
+		);
+		assert_eq!(
+			get_selector_from_name("GithubIdentifierUnregistered").unwrap(),
+			GithubIdentifierUnregisteredEvent::selector()
 		);
 	}
 
@@ -93,4 +133,10 @@ mod test {
 		let event_as_string = format!("{}", GithubIdentifierRegisteredEvent::default());
 		assert_eq!(format!("GithubIdentifierRegistered {{ profile_contract: {}, contributor_id: 0x{}, identifier: {} }}", ContractAddress::default(), ContributorId::default(), u128::default()), event_as_string);
 	}
+
+	#[rstest]
+	fn display_unregistered() {
+		let event_as_string = format!("{}", GithubIdentifierUnregisteredEvent::default());
+		assert_eq!(format!("GithubIdentifierUnregistered {{ profile_contract: {}, contributor_id: 0x{}, identifier: {} }}", ContractAddress::default(), ContributorId::default(), u128::default()), event_as_string);
+	}
 }
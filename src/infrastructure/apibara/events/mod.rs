@@ -1,13 +1,20 @@
+mod abi;
+pub use abi::{Abi, ContractAbis, Error as AbiError};
+
 mod github_identifier;
+mod solidity;
 mod topics;
 
 use std::collections::VecDeque;
 
-use self::topics::StarknetTopics;
+use self::topics::{StarknetTopics, TopicError};
 
-use super::apibara::{event::Event as ApibaraEventInner, Event as ApibaraEvent, StarkNetEvent};
+use super::apibara::{
+	event::Event as ApibaraEventInner, BlockHeader, EthereumEvent, Event as ApibaraEvent,
+	StarkNetEvent, TopicValue,
+};
 use crate::domain::*;
-use starknet::core::types::FieldElement;
+use starknet::core::{types::FieldElement, utils::get_selector_from_name};
 use thiserror::Error;
 
 #[derive(Debug, Error)]
@@ -16,8 +23,63 @@ pub enum FromEventError {
 	Unsupported,
 	#[error("Invalid event")]
 	Invalid,
+	#[error("Invalid event: {0}")]
+	InvalidTopic(#[from] TopicError),
+	#[error("Invalid field `{field}` in event `{event}`: {details}")]
+	InvalidField {
+		event: &'static str,
+		field: &'static str,
+		details: TopicError,
+	},
 }
 
+/**
+ * An event with a dedicated type, usually implemented with `#[derive(StarknetEvent)]` on the
+ * domain event
+ */
+pub trait StarknetEvent: Sized + Into<Event> {
+	const NAME: &'static str;
+
+	fn decode(data: VecDeque<TopicValue>) -> Result<Self, FromEventError>;
+
+	fn selector() -> FieldElement {
+		get_selector_from_name(Self::NAME).expect("event names are valid selectors")
+	}
+
+	/**
+	 * Returns None if the selector is not the one of this event
+	 */
+	fn dispatch(
+		selector: &FieldElement,
+		data: &VecDeque<TopicValue>,
+	) -> Option<Result<Event, FromEventError>> {
+		(selector == &Self::selector()).then(|| Self::decode(data.clone()).map(Into::into))
+	}
+}
+
+/**
+ * What the code generated by `#[derive(StarknetEvent)]` and `#[derive(StarknetStruct)]` relies
+ * on
+ */
+pub mod codegen {
+	pub use super::{
+		super::proto::TopicValue,
+		topics::{StarknetTopics, TopicError},
+		Dispatcher, FromEventError, StarknetEvent,
+	};
+	pub use inventory;
+}
+
+/**
+ * Decodes an event with a dedicated type, registered by `#[derive(StarknetEvent)]`.
+ * Any other event is decoded from the loaded ABIs.
+ */
+pub struct Dispatcher(pub Dispatch);
+
+type Dispatch = fn(&FieldElement, &VecDeque<TopicValue>) -> Option<Result<Event, FromEventError>>;
+
+inventory::collect!(Dispatcher);
+
 impl TryFrom<ApibaraEvent> for Event {
 	type Error = FromEventError;
 
@@ -29,14 +91,12 @@ impl TryFrom<ApibaraEvent> for Event {
 				topics,
 				data,
 			})) => {
-				let selector: FieldElement =
-					VecDeque::from(topics).pop_front_as().map_err(|_| Self::Error::Invalid)?;
-				match selector {
-					_ if selector == github_identifier::selector() => Ok(
-						Self::GithubIdentifierRegistered(VecDeque::from(data).try_into()?),
-					),
-					_ => Err(Self::Error::Unsupported),
-				}
+				let selector: FieldElement = VecDeque::from(topics).pop_front_as()?;
+				let data = VecDeque::from(data);
+				inventory::iter::<Dispatcher>
+					.into_iter()
+					.find_map(|Dispatcher(dispatch)| dispatch(&selector, &data))
+					.unwrap_or(Err(Self::Error::Unsupported))
 			},
 			None => Err(Self::Error::Invalid),
 			_ => Err(Self::Error::Unsupported),
@@ -44,52 +104,434 @@ impl TryFrom<ApibaraEvent> for Event {
 	}
 }
 
+/**
+ * Decode an event into its dedicated type if any, or else from the ABI of its contract
+ */
+pub fn decode(event: &ApibaraEvent, abis: &ContractAbis) -> Result<Event, FromEventError> {
+	match Event::try_from(event.clone()) {
+		Err(FromEventError::Unsupported) => match &event.event {
+			Some(ApibaraEventInner::Starknet(StarkNetEvent {
+				address,
+				topics,
+				data,
+				..
+			})) => {
+				let selector: FieldElement = VecDeque::from(topics.clone())
+					.pop_front_as()
+					.map_err(FromEventError::InvalidTopic)?;
+				abis.decode(&address.clone().into(), &selector, data.clone().into())
+					.unwrap_or(Err(FromEventError::Unsupported))
+					.map(Event::Decoded)
+			},
+			Some(ApibaraEventInner::Ethereum(EthereumEvent {
+				address,
+				topics,
+				data,
+				..
+			})) => abis
+				.decode_log(&address.clone().into(), topics, data)
+				.unwrap_or(Err(FromEventError::Unsupported))
+				.map(Event::Decoded),
+			_ => Err(FromEventError::Unsupported),
+		},
+		result => result,
+	}
+}
+
+/**
+ * The event as emitted on chain, to be kept when it cannot be decoded
+ */
+pub fn raw(event: &ApibaraEvent) -> RawEvent {
+	let hexa_strings =
+		|values: &[TopicValue]| values.iter().map(|value| value.value.clone().into()).collect();
+
+	match &event.event {
+		Some(ApibaraEventInner::Starknet(event)) => RawEvent {
+			topics: hexa_strings(&event.topics),
+			data: hexa_strings(&event.data),
+		},
+		Some(ApibaraEventInner::Ethereum(event)) => RawEvent {
+			topics: hexa_strings(&event.topics),
+			data: vec![event.data.clone().into()],
+		},
+		None => RawEvent::default(),
+	}
+}
+
+/**
+ * The event as streamed by Apibara, rebuilt from the one kept when it could not be decoded
+ */
+pub fn from_raw(network: &Network, event: &RawEvent, metadata: &EventMetadata) -> ApibaraEvent {
+	let topic_values = |values: &[HexaString]| {
+		values
+			.iter()
+			.map(|value| TopicValue {
+				value: value.bytes(),
+			})
+			.collect()
+	};
+	let address = metadata.contract_address.bytes();
+	let log_index = metadata.log_index;
+
+	let event = match network {
+		Network::Starknet(_) => ApibaraEventInner::Starknet(StarkNetEvent {
+			address,
+			log_index,
+			topics: topic_values(&event.topics),
+			data: topic_values(&event.data),
+		}),
+		Network::Ethereum(_) => ApibaraEventInner::Ethereum(EthereumEvent {
+			address,
+			log_index,
+			topics: topic_values(&event.topics),
+			data: event.data.first().map(HexaString::bytes).unwrap_or_default(),
+		}),
+	};
+	ApibaraEvent { event: Some(event) }
+}
+
+impl From<FromEventError> for DecodingError {
+	fn from(error: FromEventError) -> Self {
+		match error {
+			FromEventError::Unsupported => Self::Unsupported,
+			error => Self::Invalid(error.to_string()),
+		}
+	}
+}
+
+pub fn metadata(block: &BlockHeader, event: &ApibaraEvent) -> EventMetadata {
+	let (contract_address, log_index) = match &event.event {
+		Some(ApibaraEventInner::Starknet(StarkNetEvent {
+			address, log_index, ..
+		})) => (address.clone().into(), *log_index),
+		Some(ApibaraEventInner::Ethereum(event)) => (event.address.clone().into(), event.log_index),
+		None => Default::default(),
+	};
+
+	EventMetadata {
+		block_number: block.number,
+		block_hash: block.hash.clone().into(),
+		block_timestamp: block
+			.timestamp
+			.as_ref()
+			.and_then(|timestamp| u64::try_from(timestamp.seconds).ok())
+			.unwrap_or_default(),
+		contract_address,
+		log_index,
+	}
+}
+
 #[cfg(test)]
 mod test {
-	use super::super::apibara::TopicValue;
-	use super::*;
+	use super::{super::apibara::TopicValue, *};
 	use rstest::*;
 
+	#[fixture]
+	fn apibara_event_data() -> Vec<TopicValue> {
+		vec![
+			TopicValue {
+				value: vec![
+					0, 65, 118, 135, 43, 113, 88, 60, 185, 188, 54, 113, 219, 40, 242, 110, 127,
+					66, 106, 124, 7, 100, 97, 58, 8, 56, 187, 153, 239, 55, 58, 166,
+				],
+			},
+			TopicValue {
+				value: vec![
+					0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+					0, 0, 0, 0, 0, 203,
+				],
+			},
+			TopicValue {
+				value: vec![
+					0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+					0, 0, 0, 0, 0, 0,
+				],
+			},
+			TopicValue {
+				value: vec![
+					0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+					0, 0, 6, 101, 25, 175,
+				],
+			},
+		]
+	}
+
+	/**
+	 * The ABIs of the contract emitting the events built by `apibara_event`
+	 */
+	fn contract_abis(abi: Abi) -> ContractAbis {
+		let mut abis = ContractAbis::default();
+		abis.add(ContractAddress::from(Vec::new()), abi);
+		abis
+	}
+
+	fn apibara_event(selector: Vec<u8>, data: Vec<TopicValue>) -> ApibaraEvent {
+		ApibaraEvent {
+			event: Some(ApibaraEventInner::Starknet(StarkNetEvent {
+				topics: vec![TopicValue { value: selector }],
+				data,
+				..Default::default()
+			})),
+		}
+	}
+
+	#[rstest]
+	fn github_identifier_registered(apibara_event_data: Vec<TopicValue>) {
+		let apibara_event = apibara_event(
+			vec![
+				2, 124, 191, 99, 112, 72, 67, 173, 80, 238, 22, 11, 250, 185, 65, 12, 49, 1, 103,
+				176, 70, 145, 84, 215, 212, 99, 214, 168, 222, 6, 146, 25,
+			],
+			apibara_event_data,
+		);
+
+		assert!(matches!(
+			Event::try_from(apibara_event).unwrap(),
+			Event::GithubIdentifierRegistered(_)
+		));
+	}
+
+	#[rstest]
+	fn github_identifier_unregistered(apibara_event_data: Vec<TopicValue>) {
+		let apibara_event = apibara_event(
+			vec![
+				2, 185, 53, 115, 203, 98, 172, 34, 152, 124, 228, 215, 47, 94, 157, 42, 191, 237,
+				27, 129, 10, 157, 236, 231, 106, 67, 162, 254, 135, 138, 153, 51,
+			],
+			apibara_event_data,
+		);
+
+		assert!(matches!(
+			Event::try_from(apibara_event).unwrap(),
+			Event::GithubIdentifierUnregistered(_)
+		));
+	}
+
 	#[rstest]
-	fn github_identifier_registered() {
+	fn metadata_from_apibara(apibara_event_data: Vec<TopicValue>) {
+		let block = BlockHeader {
+			hash: vec![18, 52],
+			number: 42,
+			timestamp: Some(prost_types::Timestamp {
+				seconds: 1660000000,
+				nanos: 0,
+			}),
+			..Default::default()
+		};
 		let apibara_event = ApibaraEvent {
 			event: Some(ApibaraEventInner::Starknet(StarkNetEvent {
-				topics: vec![TopicValue {
-					value: vec![
-						2, 124, 191, 99, 112, 72, 67, 173, 80, 238, 22, 11, 250, 185, 65, 12, 49,
-						1, 103, 176, 70, 145, 84, 215, 212, 99, 214, 168, 222, 6, 146, 25,
-					],
-				}],
-				data: vec![
-					TopicValue {
-						value: vec![
-							0, 65, 118, 135, 43, 113, 88, 60, 185, 188, 54, 113, 219, 40, 242, 110,
-							127, 66, 106, 124, 7, 100, 97, 58, 8, 56, 187, 153, 239, 55, 58, 166,
-						],
-					},
-					TopicValue {
-						value: vec![
-							0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
-							0, 0, 0, 0, 0, 0, 0, 203,
-						],
-					},
-					TopicValue {
-						value: vec![
-							0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
-							0, 0, 0, 0, 0, 0, 0, 0,
-						],
-					},
+				address: vec![86, 120],
+				log_index: 3,
+				data: apibara_event_data,
+				..Default::default()
+			})),
+		};
+
+		assert_eq!(
+			EventMetadata {
+				block_number: 42,
+				block_hash: BlockHash::from(vec![18, 52]),
+				block_timestamp: 1660000000,
+				contract_address: ContractAddress::from(vec![86, 120]),
+				log_index: 3,
+			},
+			metadata(&block, &apibara_event)
+		);
+	}
+
+	#[rstest]
+	fn metadata_without_timestamp() {
+		let block = BlockHeader {
+			number: 42,
+			..Default::default()
+		};
+
+		let metadata = metadata(&block, &ApibaraEvent::default());
+		assert_eq!(42, metadata.block_number);
+		assert_eq!(0, metadata.block_timestamp);
+	}
+
+	#[rstest]
+	fn event_decoded_from_abi(apibara_event_data: Vec<TopicValue>) {
+		let abi: Abi = r#"[{
+			"type": "event",
+			"name": "Renamed",
+			"data": [
+				{ "name": "contract", "type": "felt" },
+				{ "name": "id", "type": "Uint256" },
+				{ "name": "name", "type": "felt" }
+			]
+		}]"#
+		.parse()
+		.unwrap();
+		let selector = get_selector_from_name("Renamed").unwrap().to_bytes_be().to_vec();
+		let apibara_event = apibara_event(selector, apibara_event_data);
+
+		let event = decode(&apibara_event, &contract_abis(abi)).unwrap();
+
+		assert!(
+			matches!(&event, Event::Decoded(event) if event.name == "Renamed" && event.fields.len() == 3),
+			"{:?}",
+			event
+		);
+	}
+
+	#[rstest]
+	fn dedicated_types_take_precedence_over_abi(apibara_event_data: Vec<TopicValue>) {
+		let abi: Abi = r#"[{ "type": "event", "name": "GithubIdentifierRegistered", "data": [] }]"#
+			.parse()
+			.unwrap();
+		let selector = GithubIdentifierRegisteredEvent::selector().to_bytes_be().to_vec();
+		let apibara_event = apibara_event(selector, apibara_event_data);
+
+		let event = decode(&apibara_event, &contract_abis(abi)).unwrap();
+
+		assert!(matches!(event, Event::GithubIdentifierRegistered(_)));
+	}
+
+	#[test]
+	fn ethereum_event_decoded_from_abi() {
+		let abi: Abi = r#"[{
+			"type": "event",
+			"name": "Withdrawn",
+			"inputs": [{ "name": "amount", "type": "uint64", "indexed": true }]
+		}]"#
+		.parse()
+		.unwrap();
+		let mut amount = vec![0; 32];
+		amount[31] = 42;
+		let apibara_event = ApibaraEvent {
+			event: Some(ApibaraEventInner::Ethereum(EthereumEvent {
+				topics: vec![
 					TopicValue {
-						value: vec![
-							0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
-							0, 0, 0, 0, 6, 101, 25, 175,
-						],
+						value: solidity::topic(&"Withdrawn(uint64)".parse().unwrap()),
 					},
+					TopicValue { value: amount },
 				],
 				..Default::default()
 			})),
 		};
 
-		Event::try_from(apibara_event).unwrap();
+		let event = decode(&apibara_event, &contract_abis(abi)).unwrap();
+
+		assert!(
+			matches!(&event, Event::Decoded(event) if event.name == "Withdrawn"),
+			"{:?}",
+			event
+		);
+		assert!(matches!(
+			decode(&apibara_event, &ContractAbis::default()),
+			Err(FromEventError::Unsupported)
+		));
+	}
+
+	#[rstest]
+	fn event_missing_from_abi_is_unsupported(apibara_event_data: Vec<TopicValue>) {
+		let apibara_event = apibara_event([vec![0; 31], vec![1]].concat(), apibara_event_data);
+
+		assert!(matches!(
+			decode(&apibara_event, &ContractAbis::default()),
+			Err(FromEventError::Unsupported)
+		));
+	}
+
+	#[rstest]
+	fn abis_of_other_contracts_are_ignored(apibara_event_data: Vec<TopicValue>) {
+		let abi: Abi = r#"[{ "type": "event", "name": "Renamed", "data": [] }]"#.parse().unwrap();
+		let mut abis = ContractAbis::default();
+		abis.add(ContractAddress::from(vec![86, 120]), abi);
+		let selector = get_selector_from_name("Renamed").unwrap().to_bytes_be().to_vec();
+
+		assert!(matches!(
+			decode(&apibara_event(selector, apibara_event_data), &abis),
+			Err(FromEventError::Unsupported)
+		));
+	}
+
+	#[rstest]
+	fn raw_event(apibara_event_data: Vec<TopicValue>) {
+		let apibara_event = apibara_event(vec![1], apibara_event_data);
+
+		let raw = raw(&apibara_event);
+
+		assert_eq!(vec![HexaString::from(vec![1])], raw.topics);
+		assert_eq!(4, raw.data.len());
+		assert_eq!(HexaString::from(vec![203]), raw.data[1]);
+	}
+
+	#[rstest]
+	#[case(Network::Starknet(StarknetChain::Goerli), ApibaraEventInner::Starknet(StarkNetEvent {
+		address: vec![86, 120],
+		log_index: 6,
+		topics: vec![TopicValue { value: vec![1] }],
+		data: vec![TopicValue { value: vec![2] }, TopicValue { value: vec![3] }],
+	}))]
+	#[case(Network::Ethereum(EthereumChain::Mainnet), ApibaraEventInner::Ethereum(EthereumEvent {
+		address: vec![86, 120],
+		log_index: 6,
+		topics: vec![TopicValue { value: vec![1] }],
+		data: vec![2, 3],
+	}))]
+	fn event_rebuilt_from_raw(#[case] network: Network, #[case] event: ApibaraEventInner) {
+		let apibara_event = ApibaraEvent { event: Some(event) };
+		let metadata = metadata(&BlockHeader::default(), &apibara_event);
+
+		assert_eq!(
+			apibara_event,
+			from_raw(&network, &raw(&apibara_event), &metadata)
+		);
+	}
+
+	#[rstest]
+	#[case(FromEventError::Unsupported, DecodingError::Unsupported)]
+	#[case(
+		FromEventError::Invalid,
+		DecodingError::Invalid(String::from("Invalid event"))
+	)]
+	#[case(
+		FromEventError::InvalidTopic(TopicError::Missing),
+		DecodingError::Invalid(String::from("Invalid event: Missing topic in event"))
+	)]
+	fn decoding_errors_from_event_errors(
+		#[case] error: FromEventError,
+		#[case] expected: DecodingError,
+	) {
+		assert_eq!(expected, DecodingError::from(error));
+	}
+
+	#[rstest]
+	fn unsupported_event(apibara_event_data: Vec<TopicValue>) {
+		let apibara_event = apibara_event([vec![0; 31], vec![1]].concat(), apibara_event_data);
+
+		assert!(matches!(
+			Event::try_from(apibara_event),
+			Err(FromEventError::Unsupported)
+		));
+	}
+
+	#[rstest]
+	fn invalid_selector(apibara_event_data: Vec<TopicValue>) {
+		let apibara_event = apibara_event(vec![1], apibara_event_data);
+
+		assert!(matches!(
+			Event::try_from(apibara_event.clone()),
+			Err(FromEventError::InvalidTopic(TopicError::Invalid))
+		));
+		assert!(matches!(
+			decode(&apibara_event, &ContractAbis::default()),
+			Err(FromEventError::InvalidTopic(TopicError::Invalid))
+		));
+	}
+
+	#[test]
+	fn missing_selector() {
+		let apibara_event = ApibaraEvent {
+			event: Some(ApibaraEventInner::Starknet(StarkNetEvent::default())),
+		};
+
+		assert!(matches!(
+			Event::try_from(apibara_event),
+			Err(FromEventError::InvalidTopic(TopicError::Missing))
+		));
 	}
 }
@@ -0,0 +1,392 @@
+use super::FromEventError;
+use crate::{domain::*, infrastructure::apibara::proto::TopicValue};
+use crypto_bigint::U256;
+use serde::Deserialize;
+use sha3::{Digest, Keccak256};
+
+const WORD: usize = 32;
+
+/**
+ * An event input, as found in a Solidity JSON ABI
+ */
+#[derive(Debug, Deserialize)]
+pub(super) struct Input {
+	pub name: String,
+	#[serde(rename = "type")]
+	pub type_name: String,
+	#[serde(default)]
+	pub indexed: bool,
+}
+
+#[derive(Debug, Clone)]
+pub(super) struct EventDecoder {
+	pub name: String,
+	pub topic: Vec<u8>,
+	inputs: Vec<(String, SolidityType, bool)>,
+}
+
+/**
+ * The first topic of the logs emitted by a non-anonymous event
+ */
+pub fn topic(signature: &EventSignature) -> Vec<u8> {
+	Keccak256::digest(signature.to_string().as_bytes()).to_vec()
+}
+
+impl EventDecoder {
+	pub fn new(name: String, inputs: Vec<(String, SolidityType, bool)>) -> Self {
+		let signature = EventSignature {
+			name: name.clone(),
+			params: inputs.iter().map(|(_, param_type, _)| param_type.clone()).collect(),
+		};
+
+		Self {
+			name,
+			topic: topic(&signature),
+			inputs,
+		}
+	}
+
+	/**
+	 * Indexed inputs are read from the topics following the event topic, the others are
+	 * ABI-encoded in the data. Indexed dynamic values are only available as their hash.
+	 */
+	pub fn decode(
+		&self,
+		topics: &[TopicValue],
+		data: &[u8],
+	) -> Result<DecodedEvent, FromEventError> {
+		let indexed_count = self.inputs.iter().filter(|(_, _, indexed)| *indexed).count();
+		if topics.len() != indexed_count + 1 {
+			return Err(FromEventError::Invalid);
+		}
+
+		let mut topics = topics[1..].iter();
+		let mut position = 0;
+		let fields = self
+			.inputs
+			.iter()
+			.map(|(name, param_type, indexed)| {
+				let value = if *indexed {
+					let topic = topics.next().ok_or(FromEventError::Invalid)?;
+					decode_topic(param_type, &topic.value)?
+				} else {
+					let value = decode_value(param_type, data, position)?;
+					position += head_size(param_type);
+					value
+				};
+				Ok((name.clone(), value))
+			})
+			.collect::<Result<_, FromEventError>>()?;
+
+		Ok(DecodedEvent {
+			name: self.name.clone(),
+			fields,
+		})
+	}
+}
+
+fn decode_topic(param_type: &SolidityType, topic: &[u8]) -> Result<Value, FromEventError> {
+	match param_type {
+		SolidityType::Bytes
+		| SolidityType::String
+		| SolidityType::Array(_)
+		| SolidityType::FixedArray(..) => Ok(Value::Bytes(topic.to_vec().into())),
+		_ => decode_word(param_type, topic),
+	}
+}
+
+/**
+ * Size of a value in the head of its enclosing tuple: dynamic values are replaced by an offset
+ */
+fn head_size(param_type: &SolidityType) -> usize {
+	match param_type {
+		SolidityType::FixedArray(element_type, length) if !element_type.is_dynamic() =>
+			length.saturating_mul(head_size(element_type)),
+		_ => WORD,
+	}
+}
+
+fn word(data: &[u8], position: usize) -> Result<&[u8], FromEventError> {
+	position
+		.checked_add(WORD)
+		.and_then(|end| data.get(position..end))
+		.ok_or(FromEventError::Invalid)
+}
+
+fn read_usize(data: &[u8], position: usize) -> Result<usize, FromEventError> {
+	let word = word(data, position)?;
+	let (high, low) = word.split_at(WORD - 8);
+	if high.iter().any(|byte| *byte != 0) {
+		return Err(FromEventError::Invalid);
+	}
+	let value = u64::from_be_bytes(low.try_into().expect("low has 8 bytes"));
+	usize::try_from(value).map_err(|_| FromEventError::Invalid)
+}
+
+fn decode_word(param_type: &SolidityType, word: &[u8]) -> Result<Value, FromEventError> {
+	let zero_padded = |bytes: &[u8]| bytes.iter().all(|byte| *byte == 0);
+	if word.len() != WORD {
+		return Err(FromEventError::Invalid);
+	}
+
+	let value = match param_type {
+		SolidityType::Address if zero_padded(&word[..12]) =>
+			Value::Address(word[12..].to_vec().into()),
+		SolidityType::Bool if zero_padded(&word[..WORD - 1]) && word[WORD - 1] <= 1 =>
+			Value::Bool(word[WORD - 1] == 1),
+		SolidityType::Uint(bits) if zero_padded(&word[..WORD - bits / 8]) =>
+			Value::Uint256(U256::from_be_slice(word)),
+		SolidityType::Int(bits) => {
+			let padding = WORD - bits / 8;
+			let sign = if word[padding] & 0x80 == 0 { 0 } else { 0xff };
+			if word[..padding].iter().any(|byte| *byte != sign) {
+				return Err(FromEventError::Invalid);
+			}
+			Value::Int256(U256::from_be_slice(word))
+		},
+		SolidityType::FixedBytes(size) if zero_padded(&word[*size..]) =>
+			Value::Bytes(word[..*size].to_vec().into()),
+		_ => return Err(FromEventError::Invalid),
+	};
+	Ok(value)
+}
+
+/**
+ * Decode the value whose head is at `position` in `data`, the encoding of its enclosing tuple
+ */
+fn decode_value(
+	param_type: &SolidityType,
+	data: &[u8],
+	position: usize,
+) -> Result<Value, FromEventError> {
+	if param_type.is_dynamic() {
+		let offset = read_usize(data, position)?;
+		let tail = data.get(offset..).ok_or(FromEventError::Invalid)?;
+		return decode_dynamic(param_type, tail);
+	}
+
+	match param_type {
+		SolidityType::FixedArray(element_type, length) =>
+			decode_sequence(element_type, *length, data, position),
+		_ => decode_word(param_type, word(data, position)?),
+	}
+}
+
+fn decode_dynamic(param_type: &SolidityType, data: &[u8]) -> Result<Value, FromEventError> {
+	match param_type {
+		SolidityType::Bytes | SolidityType::String => {
+			let length = read_usize(data, 0)?;
+			let bytes = WORD
+				.checked_add(length)
+				.and_then(|end| data.get(WORD..end))
+				.ok_or(FromEventError::Invalid)?;
+			match param_type {
+				SolidityType::String => String::from_utf8(bytes.to_vec())
+					.map(Value::String)
+					.map_err(|_| FromEventError::Invalid),
+				_ => Ok(Value::Bytes(bytes.to_vec().into())),
+			}
+		},
+		SolidityType::Array(element_type) => {
+			let length = read_usize(data, 0)?;
+			decode_sequence(element_type, length, &data[WORD..], 0)
+		},
+		SolidityType::FixedArray(element_type, length) =>
+			decode_sequence(element_type, *length, data, 0),
+		_ => Err(FromEventError::Invalid),
+	}
+}
+
+fn decode_sequence(
+	element_type: &SolidityType,
+	length: usize,
+	data: &[u8],
+	position: usize,
+) -> Result<Value, FromEventError> {
+	// Every element takes at least a word, reject lengths the data cannot hold before looping
+	let size = length.checked_mul(head_size(element_type)).ok_or(FromEventError::Invalid)?;
+	match position.checked_add(size) {
+		Some(end) if end <= data.len() => (),
+		_ => return Err(FromEventError::Invalid),
+	}
+
+	let mut values = Vec::new();
+	let mut position = position;
+	for _ in 0..length {
+		values.push(decode_value(element_type, data, position)?);
+		position += head_size(element_type);
+	}
+	Ok(Value::Array(values))
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use rstest::*;
+
+	fn word_of(value: u8) -> Vec<u8> {
+		let mut word = vec![0; WORD];
+		word[WORD - 1] = value;
+		word
+	}
+
+	fn topic_of(bytes: Vec<u8>) -> TopicValue {
+		TopicValue { value: bytes }
+	}
+
+	fn transfer() -> EventDecoder {
+		EventDecoder::new(
+			String::from("Transfer"),
+			vec![
+				(String::from("from"), SolidityType::Address, true),
+				(String::from("to"), SolidityType::Address, true),
+				(String::from("value"), SolidityType::Uint(256), false),
+			],
+		)
+	}
+
+	#[test]
+	fn topic_is_the_hash_of_the_canonical_signature() {
+		assert_eq!(
+			"0xddf252ad1be2c89b69c2b068fc378daa952ba7f163c4a11628f55a4df523b3ef",
+			HexaString::from(transfer().topic).to_string()
+		);
+	}
+
+	#[test]
+	fn decodes_indexed_and_data_inputs() {
+		let event = transfer();
+		let topics = vec![
+			topic_of(event.topic.clone()),
+			topic_of(word_of(1)),
+			topic_of(word_of(2)),
+		];
+
+		assert_eq!(
+			DecodedEvent {
+				name: String::from("Transfer"),
+				fields: vec![
+					(
+						String::from("from"),
+						Value::Address(HexaString::from(word_of(1)[12..].to_vec()))
+					),
+					(
+						String::from("to"),
+						Value::Address(HexaString::from(word_of(2)[12..].to_vec()))
+					),
+					(String::from("value"), Value::Uint256(U256::from_u8(3))),
+				],
+			},
+			event.decode(&topics, &word_of(3)).unwrap()
+		);
+	}
+
+	#[test]
+	fn decodes_dynamic_inputs() {
+		let event = EventDecoder::new(
+			String::from("Deposited"),
+			vec![
+				(String::from("memo"), SolidityType::String, true),
+				(String::from("id"), SolidityType::FixedBytes(4), false),
+				(
+					String::from("amounts"),
+					SolidityType::Array(Box::new(SolidityType::Int(8))),
+					false,
+				),
+				(String::from("note"), SolidityType::String, false),
+				(String::from("done"), SolidityType::Bool, false),
+			],
+		);
+		let topics = vec![topic_of(event.topic.clone()), topic_of(word_of(9))];
+		let mut id = vec![0; WORD];
+		id[..4].copy_from_slice(&[1, 2, 3, 4]);
+		let mut note = vec![0; WORD];
+		note[..2].copy_from_slice(b"hi");
+		let data = [
+			id,
+			word_of(4 * 32),
+			word_of(7 * 32),
+			word_of(1),
+			// amounts
+			word_of(2),
+			word_of(5),
+			vec![0xff; WORD],
+			// note
+			word_of(2),
+			note,
+		]
+		.concat();
+
+		assert_eq!(
+			vec![
+				(
+					String::from("memo"),
+					Value::Bytes(HexaString::from(word_of(9)))
+				),
+				(
+					String::from("id"),
+					Value::Bytes(HexaString::from(vec![1, 2, 3, 4]))
+				),
+				(
+					String::from("amounts"),
+					Value::Array(vec![
+						Value::Int256(U256::from_u8(5)),
+						Value::Int256(U256::MAX)
+					])
+				),
+				(String::from("note"), Value::String(String::from("hi"))),
+				(String::from("done"), Value::Bool(true)),
+			],
+			event.decode(&topics, &data).unwrap().fields
+		);
+	}
+
+	#[rstest]
+	#[case(vec![word_of(1), word_of(2)], word_of(3))]
+	#[case(vec![word_of(1), word_of(2), word_of(3)], Vec::new())]
+	#[case(vec![word_of(1), vec![1; WORD], word_of(2)], word_of(3))]
+	fn logs_not_matching_the_event_are_invalid(
+		#[case] topics: Vec<Vec<u8>>,
+		#[case] data: Vec<u8>,
+	) {
+		let topics: Vec<_> = topics.into_iter().map(topic_of).collect();
+		assert!(matches!(
+			transfer().decode(&topics, &data),
+			Err(FromEventError::Invalid)
+		));
+	}
+
+	#[rstest]
+	#[case(SolidityType::Bool, word_of(2))]
+	#[case(SolidityType::Uint(8), [vec![1], vec![0; WORD - 1]].concat())]
+	#[case(SolidityType::Int(8), [vec![0; WORD - 1], vec![0x80]].concat())]
+	#[case(SolidityType::FixedBytes(1), word_of(1))]
+	fn out_of_range_words_are_invalid(#[case] param_type: SolidityType, #[case] word: Vec<u8>) {
+		assert!(matches!(
+			decode_word(&param_type, &word),
+			Err(FromEventError::Invalid)
+		));
+	}
+
+	#[rstest]
+	#[case("uint8[]")]
+	#[case("uint8[2][]")]
+	#[case("string[]")]
+	fn lengths_exceeding_the_data_are_invalid(#[case] type_name: &str) {
+		let param_type: SolidityType = type_name.parse().unwrap();
+		let data = [word_of(WORD as u8), vec![0xff; WORD]].concat();
+		assert!(matches!(
+			decode_value(&param_type, &data, 0),
+			Err(FromEventError::Invalid)
+		));
+	}
+
+	#[test]
+	fn out_of_bounds_offsets_are_invalid() {
+		let data = [word_of(0xff), word_of(1)].concat();
+		assert!(matches!(
+			decode_value(&SolidityType::Bytes, &data, 0),
+			Err(FromEventError::Invalid)
+		));
+	}
+}
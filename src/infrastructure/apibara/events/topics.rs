@@ -1,6 +1,6 @@
-use super::{super::apibara::TopicValue, FromEventError};
+use super::super::apibara::TopicValue;
 use crate::domain::HexaString;
-use crypto_bigint::{Encoding, Split, U256};
+use crypto_bigint::{Encoding, U256};
 use starknet::core::types::FieldElement;
 use std::{collections::VecDeque, convert::TryInto};
 use thiserror::Error;
@@ -11,12 +11,15 @@ pub enum TopicError {
 	Missing,
 	#[error("Invalid topic in event")]
 	Invalid,
-}
-
-impl From<TopicError> for FromEventError {
-	fn from(_: TopicError) -> Self {
-		Self::Invalid
-	}
+	#[error("Expected a {expected} in event, got {value}")]
+	OutOfRange {
+		expected: &'static str,
+		value: HexaString,
+	},
+	#[error("Expected a bool (0 or 1) in event, got {0}")]
+	NotABool(HexaString),
+	#[error("Expected an ASCII short string in event, got {0}")]
+	NotAShortString(HexaString),
 }
 
 pub trait StarknetTopics<T> {
@@ -43,28 +46,101 @@ impl StarknetTopics<FieldElement> for VecDeque<TopicValue> {
 	}
 }
 
+/**
+ * Cairo `Uint256` are made of their low then high 128 bits, each half must fit in them
+ */
 impl StarknetTopics<U256> for VecDeque<TopicValue> {
 	fn pop_front_as(&mut self) -> Result<U256, TopicError> {
-		let low: FieldElement = self.pop_front_as()?;
-		let high: FieldElement = self.pop_front_as()?;
+		let low: u128 = self.pop_front_as()?;
+		let high: u128 = self.pop_front_as()?;
 
-		let (_, low) = U256::from_be_bytes(low.to_bytes_be()).split();
-		let (_, high) = U256::from_be_bytes(high.to_bytes_be()).split();
-		Ok(U256::from((high, low)))
+		let mut bytes = [0; 32];
+		bytes[..16].copy_from_slice(&high.to_be_bytes());
+		bytes[16..].copy_from_slice(&low.to_be_bytes());
+		Ok(U256::from_be_bytes(bytes))
 	}
 }
 
-impl StarknetTopics<u128> for VecDeque<TopicValue> {
-	fn pop_front_as(&mut self) -> Result<u128, TopicError> {
+/**
+ * Decode an unsigned integer, rejecting the felts that do not fit in it
+ */
+macro_rules! impl_unsigned_integer {
+	($($integer:ty),*) => {
+		$(
+			impl StarknetTopics<$integer> for VecDeque<TopicValue> {
+				fn pop_front_as(&mut self) -> Result<$integer, TopicError> {
+					let value: FieldElement = self.pop_front_as()?;
+					let bytes = value.to_bytes_be();
+					let (high, low) = bytes.split_at(bytes.len() - std::mem::size_of::<$integer>());
+
+					if high.iter().any(|byte| *byte != 0) {
+						return Err(TopicError::OutOfRange {
+							expected: stringify!($integer),
+							value: bytes.to_vec().into(),
+						});
+					}
+
+					Ok(<$integer>::from_be_bytes(low.try_into().expect("sizes match")))
+				}
+			}
+		)*
+	};
+}
+
+impl_unsigned_integer!(u8, u16, u32, u64, u128);
+
+impl StarknetTopics<bool> for VecDeque<TopicValue> {
+	fn pop_front_as(&mut self) -> Result<bool, TopicError> {
 		let value: FieldElement = self.pop_front_as()?;
-		let (_, value) = U256::from_be_bytes(value.to_bytes_be()).split();
-		Ok(value.into())
+		let bytes = value.to_bytes_be();
+		match bytes.split_last() {
+			Some((last, high)) if *last <= 1 && high.iter().all(|byte| *byte == 0) =>
+				Ok(*last == 1),
+			_ => Err(TopicError::NotABool(bytes.to_vec().into())),
+		}
+	}
+}
+
+/**
+ * Cairo short strings are up to 31 ASCII characters, encoded in a single felt
+ */
+impl StarknetTopics<String> for VecDeque<TopicValue> {
+	fn pop_front_as(&mut self) -> Result<String, TopicError> {
+		let value: FieldElement = self.pop_front_as()?;
+		let bytes: Vec<u8> =
+			value.to_bytes_be().into_iter().skip_while(|byte| *byte == 0).collect();
+
+		if !bytes.is_ascii() {
+			return Err(TopicError::NotAShortString(
+				value.to_bytes_be().to_vec().into(),
+			));
+		}
+
+		Ok(String::from_utf8(bytes).expect("ASCII is valid UTF-8"))
+	}
+}
+
+/**
+ * Cairo arrays are made of their length followed by their elements
+ */
+impl<T> StarknetTopics<Vec<T>> for VecDeque<TopicValue>
+where
+	VecDeque<TopicValue>: StarknetTopics<T>,
+{
+	fn pop_front_as(&mut self) -> Result<Vec<T>, TopicError> {
+		let length = <Self as StarknetTopics<u64>>::pop_front_as(self)?;
+		if length > self.len() as u64 {
+			return Err(TopicError::Missing);
+		}
+
+		(0..length).map(|_| <Self as StarknetTopics<T>>::pop_front_as(self)).collect()
 	}
 }
 
 #[cfg(test)]
 mod test {
 	use super::*;
+	use marketplace_indexer_macros::StarknetStruct;
 	use rstest::*;
 
 	#[fixture]
@@ -93,6 +169,33 @@ mod test {
 		assert_eq!(0, topics.len());
 	}
 
+	#[test]
+	fn u256_rejects_overflowing_halves() {
+		let result: Result<U256, _> = topics_of(&[&[1; 17], &[0]]).pop_front_as();
+		assert!(matches!(
+			result,
+			Err(TopicError::OutOfRange {
+				expected: "u128",
+				..
+			})
+		));
+
+		let result: Result<U256, _> = topics_of(&[&[0], &[1; 17]]).pop_front_as();
+		assert!(matches!(
+			result,
+			Err(TopicError::OutOfRange {
+				expected: "u128",
+				..
+			})
+		));
+	}
+
+	#[test]
+	fn u256_halves_are_low_then_high() {
+		let value: U256 = topics_of(&[&[2], &[1]]).pop_front_as().unwrap();
+		assert_eq!(U256::from_u128(2).wrapping_add(&U256::ONE.shl_vartime(128)), value);
+	}
+
 	#[rstest]
 	fn topic_to_hex_string(mut topics: VecDeque<TopicValue>) {
 		let value: HexaString =
@@ -108,6 +211,137 @@ mod test {
 		assert_eq!(1, topics.len());
 	}
 
+	fn felt(value: &[u8]) -> TopicValue {
+		let mut bytes = vec![0; 32 - value.len()];
+		bytes.extend_from_slice(value);
+		TopicValue { value: bytes }
+	}
+
+	fn topics_of(values: &[&[u8]]) -> VecDeque<TopicValue> {
+		values.iter().map(|value| felt(value)).collect()
+	}
+
+	#[rstest]
+	#[case(&[1, 0], 256)]
+	#[case(&[255, 255, 255, 255], u32::MAX as u64)]
+	fn topic_to_u64(#[case] bytes: &[u8], #[case] expected: u64) {
+		let value: u64 = topics_of(&[bytes]).pop_front_as().unwrap();
+		assert_eq!(expected, value);
+	}
+
+	#[test]
+	fn integers_reject_overflowing_felts() {
+		let result: Result<u32, _> = topics_of(&[&[1, 0, 0, 0, 0]]).pop_front_as();
+		assert!(matches!(
+			result,
+			Err(TopicError::OutOfRange {
+				expected: "u32",
+				..
+			})
+		));
+		assert_eq!(
+			"Expected a u32 in event, got 0x0000000000000000000000000000000000000000000000000000000100000000",
+			result.unwrap_err().to_string()
+		);
+
+		let result: Result<u64, _> = topics_of(&[&[1, 0, 0, 0, 0, 0, 0, 0, 0]]).pop_front_as();
+		assert!(matches!(
+			result,
+			Err(TopicError::OutOfRange {
+				expected: "u64",
+				..
+			})
+		));
+
+		let result: Result<u128, _> = topics_of(&[&[1; 17]]).pop_front_as();
+		assert!(matches!(
+			result,
+			Err(TopicError::OutOfRange {
+				expected: "u128",
+				..
+			})
+		));
+	}
+
+	#[rstest]
+	#[case(&[0], false)]
+	#[case(&[1], true)]
+	fn topic_to_bool(#[case] bytes: &[u8], #[case] expected: bool) {
+		let value: bool = topics_of(&[bytes]).pop_front_as().unwrap();
+		assert_eq!(expected, value);
+	}
+
+	#[test]
+	fn bools_reject_other_values() {
+		let result: Result<bool, _> = topics_of(&[&[2]]).pop_front_as();
+		assert!(matches!(result, Err(TopicError::NotABool(_))));
+	}
+
+	#[test]
+	fn topic_to_short_string() {
+		let value: String = topics_of(&[b"onlydust"]).pop_front_as().unwrap();
+		assert_eq!("onlydust", value);
+
+		let value: String = topics_of(&[&[0]]).pop_front_as().unwrap();
+		assert_eq!("", value);
+	}
+
+	#[test]
+	fn short_strings_reject_non_ascii_characters() {
+		let result: Result<String, _> = topics_of(&["é".as_bytes()]).pop_front_as();
+		assert!(matches!(result, Err(TopicError::NotAShortString(_))));
+	}
+
+	#[test]
+	fn topic_to_array() {
+		let value: Vec<u64> = topics_of(&[&[3], &[1], &[2], &[3], &[4]]).pop_front_as().unwrap();
+		assert_eq!(vec![1, 2, 3], value);
+	}
+
+	#[test]
+	fn arrays_longer_than_the_event_are_rejected() {
+		let result: Result<Vec<u64>, _> = topics_of(&[&[255, 255], &[1]]).pop_front_as();
+		assert!(matches!(result, Err(TopicError::Missing)));
+
+		let result: Result<Vec<u64>, _> = topics_of(&[&[2], &[1]]).pop_front_as();
+		assert!(matches!(result, Err(TopicError::Missing)));
+	}
+
+	#[derive(Debug, PartialEq, StarknetStruct)]
+	struct Point {
+		x: u64,
+		y: u64,
+	}
+
+	#[derive(Debug, PartialEq, StarknetStruct)]
+	struct Path {
+		name: String,
+		closed: bool,
+		points: Vec<Point>,
+	}
+
+	#[test]
+	fn topic_to_nested_structs() {
+		let value: Path = topics_of(&[b"square", &[1], &[2], &[1], &[2], &[3], &[4]])
+			.pop_front_as()
+			.unwrap();
+
+		assert_eq!(
+			Path {
+				name: String::from("square"),
+				closed: true,
+				points: vec![Point { x: 1, y: 2 }, Point { x: 3, y: 4 }],
+			},
+			value
+		);
+	}
+
+	#[test]
+	fn nested_structs_forward_errors() {
+		let result: Result<Path, _> = topics_of(&[b"square", &[2]]).pop_front_as();
+		assert!(matches!(result, Err(TopicError::NotABool(_))));
+	}
+
 	#[rstest]
 	fn convertion_error() {
 		let mut topics = VecDeque::<TopicValue>::default();
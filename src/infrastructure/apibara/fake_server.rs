@@ -0,0 +1,289 @@
+use super::{
+	apibara::{
+		connect_indexer_request::Message as RequestMessage,
+		connect_indexer_response::Message as ResponseMessage,
+		indexer_manager_client::IndexerManagerClient,
+		indexer_manager_server::{IndexerManager, IndexerManagerServer},
+		network, BlockHeader, ConnectIndexerRequest, ConnectIndexerResponse, CreateIndexerRequest,
+		CreateIndexerResponse, DeleteIndexerRequest, DeleteIndexerResponse, Event,
+		GetIndexerRequest, GetIndexerResponse, Indexer, IndexerConnected, ListIndexerRequest,
+		ListIndexerResponse, Network, NewBlock, NewEvents, Reorg, StarkNetNetwork,
+	},
+	Client,
+};
+use crate::domain::{BlockHash, IndexerId};
+use std::{
+	collections::{HashMap, VecDeque},
+	net::SocketAddr,
+	sync::{Arc, Mutex},
+	time::Duration,
+};
+use tokio::{
+	net::TcpListener,
+	sync::{mpsc, oneshot, Notify},
+	task::JoinHandle,
+};
+use tokio_stream::wrappers::{ReceiverStream, TcpListenerStream};
+use tonic::{Request, Response, Status, Streaming};
+
+const ACK_TIMEOUT: Duration = Duration::from_secs(5);
+
+/**
+ * What the server does on an indexer connection, in order
+ */
+pub enum Step {
+	/// Send a message to the client
+	Send(ConnectIndexerResponse),
+	/// Wait for the client to ack the given block before going on
+	WaitForAck(Vec<u8>),
+	/// Close the stream
+	Close,
+}
+
+#[derive(Default)]
+struct State {
+	scripts: Mutex<VecDeque<Vec<Step>>>,
+	connections: Mutex<Vec<String>>,
+	acks: Mutex<Vec<Vec<u8>>>,
+	ack_received: Notify,
+	indexers: Mutex<HashMap<String, Indexer>>,
+}
+
+/**
+ * Scripted in-process Apibara server, to exercise the real `Client` over a tonic channel
+ */
+pub struct FakeServer {
+	state: Arc<State>,
+	address: SocketAddr,
+	handle: JoinHandle<()>,
+}
+
+impl FakeServer {
+	/**
+	 * Start a server on a random local port.
+	 * Each indexer connection plays the next script. Once its script is over, or if there is no
+	 * script left, the stream stays open without sending anything.
+	 */
+	pub async fn start(scripts: Vec<Vec<Step>>) -> Self {
+		let state = Arc::new(State {
+			scripts: Mutex::new(scripts.into()),
+			..Default::default()
+		});
+
+		let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+		let address = listener.local_addr().unwrap();
+		let service = IndexerManagerServer::new(Service(state.clone()));
+		let handle = tokio::spawn(async move {
+			tonic::transport::Server::builder()
+				.add_service(service)
+				.serve_with_incoming(TcpListenerStream::new(listener))
+				.await
+				.unwrap();
+		});
+
+		Self {
+			state,
+			address,
+			handle,
+		}
+	}
+
+	pub fn client(&self) -> Client {
+		let channel = tonic::transport::Endpoint::from_shared(format!("http://{}", self.address))
+			.unwrap()
+			.connect_lazy();
+		Client::new(IndexerManagerClient::new(channel))
+	}
+
+	/**
+	 * Ids of the indexers which connected, once per connection
+	 */
+	pub fn connections(&self) -> Vec<IndexerId> {
+		self.state.connections.lock().unwrap().iter().map(IndexerId::from).collect()
+	}
+
+	/**
+	 * Hashes of the blocks acked by the client, in order
+	 */
+	pub fn acks(&self) -> Vec<BlockHash> {
+		self.state.acks.lock().unwrap().iter().cloned().map(BlockHash::from).collect()
+	}
+
+	/**
+	 * Register an indexer as is, bypassing the checks of the client
+	 */
+	pub fn insert_indexer(&self, indexer: Indexer) {
+		self.state.indexers.lock().unwrap().insert(indexer.id.clone(), indexer);
+	}
+}
+
+impl Drop for FakeServer {
+	fn drop(&mut self) {
+		self.handle.abort();
+	}
+}
+
+pub fn connected(indexer_id: &str) -> Step {
+	Step::Send(response(ResponseMessage::Connected(IndexerConnected {
+		indexer: Some(Indexer {
+			id: indexer_id.to_owned(),
+			..Default::default()
+		}),
+		version: None,
+	})))
+}
+
+pub fn new_block(hash: Vec<u8>, number: u64) -> Step {
+	Step::Send(response(ResponseMessage::NewBlock(NewBlock {
+		new_head: Some(header(hash, number)),
+	})))
+}
+
+pub fn new_events(hash: Vec<u8>, number: u64, events: Vec<Event>) -> Step {
+	Step::Send(response(ResponseMessage::NewEvents(NewEvents {
+		block: Some(header(hash, number)),
+		events,
+	})))
+}
+
+pub fn reorg(hash: Vec<u8>, number: u64) -> Step {
+	Step::Send(response(ResponseMessage::Reorg(Reorg {
+		new_head: Some(header(hash, number)),
+	})))
+}
+
+fn header(hash: Vec<u8>, number: u64) -> BlockHeader {
+	BlockHeader {
+		hash,
+		number,
+		..Default::default()
+	}
+}
+
+fn response(message: ResponseMessage) -> ConnectIndexerResponse {
+	ConnectIndexerResponse {
+		message: Some(message),
+	}
+}
+
+struct Service(Arc<State>);
+
+#[tonic::async_trait]
+impl IndexerManager for Service {
+	type ConnectIndexerStream = ReceiverStream<Result<ConnectIndexerResponse, Status>>;
+
+	async fn create_indexer(
+		&self,
+		request: Request<CreateIndexerRequest>,
+	) -> Result<Response<CreateIndexerResponse>, Status> {
+		let request = request.into_inner();
+		let mut indexers = self.0.indexers.lock().unwrap();
+		if indexers.contains_key(&request.id) {
+			return Err(Status::already_exists("indexer already exists"));
+		}
+
+		let indexer = Indexer {
+			id: request.id.clone(),
+			network: Some(Network {
+				network: Some(network::Network::Starknet(StarkNetNetwork {
+					name: request.network_name,
+				})),
+			}),
+			indexed_to_block: None,
+			index_from_block: request.index_from_block,
+			filters: request.filters,
+		};
+		indexers.insert(request.id, indexer.clone());
+
+		Ok(Response::new(CreateIndexerResponse {
+			indexer: Some(indexer),
+		}))
+	}
+
+	async fn get_indexer(
+		&self,
+		request: Request<GetIndexerRequest>,
+	) -> Result<Response<GetIndexerResponse>, Status> {
+		let indexer = self.0.indexers.lock().unwrap().get(&request.into_inner().id).cloned();
+		Ok(Response::new(GetIndexerResponse { indexer }))
+	}
+
+	async fn list_indexer(
+		&self,
+		_request: Request<ListIndexerRequest>,
+	) -> Result<Response<ListIndexerResponse>, Status> {
+		let indexers = self.0.indexers.lock().unwrap().values().cloned().collect();
+		Ok(Response::new(ListIndexerResponse { indexers }))
+	}
+
+	async fn delete_indexer(
+		&self,
+		request: Request<DeleteIndexerRequest>,
+	) -> Result<Response<DeleteIndexerResponse>, Status> {
+		let indexer = self.0.indexers.lock().unwrap().remove(&request.into_inner().id);
+		Ok(Response::new(DeleteIndexerResponse { indexer }))
+	}
+
+	async fn connect_indexer(
+		&self,
+		request: Request<Streaming<ConnectIndexerRequest>>,
+	) -> Result<Response<Self::ConnectIndexerStream>, Status> {
+		let mut requests = request.into_inner();
+		let (requests_closed_tx, requests_closed) = oneshot::channel::<()>();
+		let state = self.0.clone();
+		tokio::spawn(async move {
+			// Dropped once the client closes its side of the stream
+			let _requests_closed_tx = requests_closed_tx;
+			while let Ok(Some(request)) = requests.message().await {
+				match request.message {
+					Some(RequestMessage::Connect(connect)) =>
+						state.connections.lock().unwrap().push(connect.id),
+					Some(RequestMessage::Ack(ack)) => {
+						state.acks.lock().unwrap().push(ack.hash);
+						state.ack_received.notify_waiters();
+					},
+					None => (),
+				}
+			}
+		});
+
+		let script = self.0.scripts.lock().unwrap().pop_front().unwrap_or_default();
+		let (tx, rx) = mpsc::channel(64);
+		let state = self.0.clone();
+		tokio::spawn(async move {
+			for step in script {
+				match step {
+					Step::Send(response) =>
+						if tx.send(Ok(response)).await.is_err() {
+							return;
+						},
+					Step::WaitForAck(hash) =>
+						if tokio::time::timeout(ACK_TIMEOUT, wait_for_ack(&state, &hash))
+							.await
+							.is_err()
+						{
+							return;
+						},
+					Step::Close => return,
+				}
+			}
+			// Keep the stream open until the client closes its side, or goes away
+			tokio::select! {
+				_ = tx.closed() => (),
+				_ = requests_closed => (),
+			};
+		});
+
+		Ok(Response::new(ReceiverStream::new(rx)))
+	}
+}
+
+async fn wait_for_ack(state: &State, hash: &[u8]) {
+	loop {
+		let notified = state.ack_received.notified();
+		if state.acks.lock().unwrap().iter().any(|ack| ack == hash) {
+			return;
+		}
+		notified.await;
+	}
+}
@@ -1,5 +1,7 @@
 use super::{
-	apibara::{self, CreateIndexerRequest, DeleteIndexerRequest, GetIndexerRequest},
+	apibara::{
+		self, CreateIndexerRequest, DeleteIndexerRequest, GetIndexerRequest, ListIndexerRequest,
+	},
 	Client,
 };
 use crate::domain::*;
@@ -13,7 +15,7 @@ use itertools::Itertools;
 impl IndexerRepository for Client {
 	async fn create(&self, indexer: &Indexer) -> Result<(), IndexerRepositoryError> {
 		let response = self
-			.0
+			.inner
 			.write()
 			.await
 			.create_indexer(CreateIndexerRequest {
@@ -44,7 +46,7 @@ impl IndexerRepository for Client {
 		indexer_id: &IndexerId,
 	) -> Result<Option<Indexer>, IndexerRepositoryError> {
 		let response = self
-			.0
+			.inner
 			.write()
 			.await
 			.get_indexer(GetIndexerRequest {
@@ -56,11 +58,11 @@ impl IndexerRepository for Client {
 				details: status.to_string(),
 			})?;
 
-		Ok(response.into_inner().indexer.map(Indexer::from))
+		response.into_inner().indexer.map(Indexer::try_from).transpose()
 	}
 
 	async fn delete(&self, indexer_id: &IndexerId) -> Result<(), IndexerRepositoryError> {
-		self.0
+		self.inner
 			.write()
 			.await
 			.delete_indexer(DeleteIndexerRequest {
@@ -74,25 +76,31 @@ impl IndexerRepository for Client {
 
 		Ok(())
 	}
-}
 
-impl ToString for Network {
-	fn to_string(&self) -> String {
-		match self {
-			Network::Starknet(chain) => chain.to_string(),
-		}
-	}
-}
+	async fn list(&self) -> Result<Vec<Indexer>, IndexerRepositoryError> {
+		let response = self
+			.inner
+			.write()
+			.await
+			.list_indexer(ListIndexerRequest {})
+			.await
+			.map_err(|status| IndexerRepositoryError::ListIndexers(status.to_string()))?;
 
-// Hardcoded strings are referenced in the server configuration.toml file
-impl ToString for StarknetChain {
-	fn to_string(&self) -> String {
-		match self {
-			StarknetChain::Devnet => "starknet-devnet",
-			StarknetChain::Goerli => "starknet-goerli",
-			StarknetChain::Mainnet => "starknet-mainnet",
-		}
-		.to_owned()
+		// An indexer this version cannot map, like one of an unknown network, must not hide the
+		// others
+		let indexers = response
+			.into_inner()
+			.indexers
+			.into_iter()
+			.filter_map(|indexer| match Indexer::try_from(indexer) {
+				Ok(indexer) => Some(indexer),
+				Err(error) => {
+					log::warn!("Skipping indexer: {}", error);
+					None
+				},
+			})
+			.collect();
+		Ok(indexers)
 	}
 }
 
@@ -105,20 +113,26 @@ impl From<EventFilter> for apibara::EventFilter {
 	}
 }
 
-impl From<apibara::Network> for Network {
-	fn from(network: apibara::Network) -> Self {
-		match network.network {
-			Some(network) => match network {
-				apibara::network::Network::Starknet(chain) => match chain.name {
-					chain if chain == "starknet-devnet" => Network::Starknet(StarknetChain::Devnet),
-					chain if chain == "starknet-goerli" => Network::Starknet(StarknetChain::Goerli),
-					chain if chain == "starknet-mainnet" =>
-						Network::Starknet(StarknetChain::Mainnet),
-					_ => Network::Starknet(StarknetChain::Devnet),
-				},
-				apibara::network::Network::Ethereum(_) => unimplemented!(),
-			},
-			None => Network::Starknet(StarknetChain::Devnet),
+impl TryFrom<apibara::Network> for Network {
+	type Error = IndexerRepositoryError;
+
+	fn try_from(network: apibara::Network) -> Result<Self, Self::Error> {
+		let invalid = |details: String| IndexerRepositoryError::InvalidNetwork(details);
+
+		let (name, starknet) = match network.network {
+			Some(apibara::network::Network::Starknet(chain)) => (chain.name, true),
+			Some(apibara::network::Network::Ethereum(chain)) => (chain.name, false),
+			None => return Err(invalid(String::from("missing chain"))),
+		};
+
+		// Names are parsed as in the configuration, then must match the kind of the chain
+		match name.parse().map_err(|error: ParseNetworkError| invalid(error.to_string()))? {
+			network @ Network::Starknet(_) if starknet => Ok(network),
+			network @ Network::Ethereum(_) if !starknet => Ok(network),
+			network => Err(invalid(format!(
+				"`{}` does not match the kind of its chain",
+				network
+			))),
 		}
 	}
 }
@@ -132,17 +146,27 @@ impl From<apibara::EventFilter> for EventFilter {
 	}
 }
 
-impl From<apibara::Indexer> for Indexer {
-	fn from(indexer: apibara::Indexer) -> Self {
-		Self {
-			id: indexer.id.into(),
-			network: indexer
-				.network
-				.map(|network| network.into())
-				.unwrap_or_else(|| Network::Starknet(StarknetChain::Devnet)),
+impl TryFrom<apibara::Indexer> for Indexer {
+	type Error = IndexerRepositoryError;
+
+	fn try_from(indexer: apibara::Indexer) -> Result<Self, Self::Error> {
+		let id = IndexerId::from(indexer.id);
+		let network = indexer
+			.network
+			.ok_or_else(|| IndexerRepositoryError::InvalidNetwork(String::from("missing network")))
+			.and_then(Network::try_from)
+			.map_err(|error| IndexerRepositoryError::InvalidIndexer {
+				id: id.clone(),
+				details: error.to_string(),
+			})?;
+
+		Ok(Self {
+			id,
+			network,
 			index_from_block: indexer.index_from_block,
+			indexed_to_block: indexer.indexed_to_block,
 			filters: indexer.filters.into_iter().map_into().collect(),
-		}
+		})
 	}
 }
 
@@ -150,63 +174,87 @@ impl From<apibara::Indexer> for Indexer {
 mod tests {
 	use std::str::FromStr;
 
-	use super::*;
+	use super::{super::fake_server::FakeServer, *};
 	use rstest::*;
 
-	#[test]
-	fn network_to_apibara_string() {
-		assert_eq!(
-			"starknet-devnet",
-			Network::Starknet(StarknetChain::Devnet).to_string()
-		);
-
-		assert_eq!(
-			"starknet-goerli",
-			Network::Starknet(StarknetChain::Goerli).to_string()
-		);
-
-		assert_eq!(
-			"starknet-mainnet",
-			Network::Starknet(StarknetChain::Mainnet).to_string()
-		);
-	}
-
 	#[rstest]
 	#[case(StarknetChain::Devnet, "starknet-devnet")]
 	#[case(StarknetChain::Goerli, "starknet-goerli")]
 	#[case(StarknetChain::Mainnet, "starknet-mainnet")]
-	#[case(StarknetChain::Devnet, "non-existent")]
 	fn network_from_apibara(#[case] chain: StarknetChain, #[case] network_name: &str) {
 		assert_eq!(
 			Network::Starknet(chain),
-			Network::from(apibara::Network {
+			Network::try_from(apibara::Network {
 				network: Some(apibara::network::Network::Starknet(
 					apibara::StarkNetNetwork {
 						name: String::from(network_name)
 					}
 				))
 			})
+			.unwrap()
 		)
 	}
 
-	#[test]
-	fn network_from_apibara_should_default_to_starknet_devnet() {
+	#[rstest]
+	#[case(EthereumChain::Goerli, "ethereum-goerli")]
+	#[case(EthereumChain::Mainnet, "ethereum-mainnet")]
+	fn ethereum_network_from_apibara(#[case] chain: EthereumChain, #[case] network_name: &str) {
 		assert_eq!(
-			Network::Starknet(StarknetChain::Devnet),
-			Network::from(apibara::Network { network: None })
+			Network::Ethereum(chain),
+			Network::try_from(apibara::Network {
+				network: Some(apibara::network::Network::Ethereum(
+					apibara::EthereumNetwork {
+						name: String::from(network_name),
+					},
+				)),
+			})
+			.unwrap()
 		);
 	}
 
-	#[test]
-	#[should_panic(expected = "not implemented")]
-	fn network_from_apibara_should_panic_for_unimplemented() {
-		let _ = Network::from(apibara::Network {
-			network: Some(apibara::network::Network::Ethereum(
-				apibara::EthereumNetwork {
-					name: String::from(""),
-				},
-			)),
-		});
+	#[rstest]
+	#[case(Network::from_str("starknet-local").unwrap(), apibara::network::Network::Starknet(
+		apibara::StarkNetNetwork { name: String::from("starknet-local") }
+	))]
+	#[case(Network::from_str("ethereum-sepolia").unwrap(), apibara::network::Network::Ethereum(
+		apibara::EthereumNetwork { name: String::from("ethereum-sepolia") }
+	))]
+	fn custom_network_from_apibara(
+		#[case] expected: Network,
+		#[case] network: apibara::network::Network,
+	) {
+		assert_eq!(
+			expected,
+			Network::try_from(apibara::Network {
+				network: Some(network)
+			})
+			.unwrap()
+		);
+	}
+
+	#[rstest]
+	#[case(None)]
+	#[case(Some(apibara::network::Network::Starknet(Default::default())))]
+	#[case(Some(apibara::network::Network::Ethereum(Default::default())))]
+	#[case(Some(apibara::network::Network::Starknet(apibara::StarkNetNetwork {
+		name: String::from("non-existent")
+	})))]
+	#[case(Some(apibara::network::Network::Starknet(apibara::StarkNetNetwork {
+		name: String::from("starknet-goerlii")
+	})))]
+	#[case(Some(apibara::network::Network::Starknet(apibara::StarkNetNetwork {
+		name: String::from("ethereum-goerli")
+	})))]
+	#[case(Some(apibara::network::Network::Ethereum(apibara::EthereumNetwork {
+		name: String::from("starknet-goerli")
+	})))]
+	fn unmappable_networks_from_apibara_are_rejected(
+		#[case] network: Option<apibara::network::Network>,
+	) {
+		assert!(matches!(
+			Network::try_from(apibara::Network { network }),
+			Err(IndexerRepositoryError::InvalidNetwork(_))
+		));
 	}
 
 	#[test]
@@ -252,7 +300,7 @@ mod tests {
 
 	#[test]
 	fn indexer_from_apibara() {
-		let indexer = Indexer::from(apibara::Indexer {
+		let indexer = Indexer::try_from(apibara::Indexer {
 			id: String::from("ID"),
 			network: Some(apibara::Network {
 				network: Some(apibara::network::Network::Starknet(
@@ -262,7 +310,7 @@ mod tests {
 				)),
 			}),
 			index_from_block: 1234,
-			indexed_to_block: None,
+			indexed_to_block: Some(1300),
 			filters: vec![
 				apibara::EventFilter {
 					address: vec![18, 52],
@@ -273,7 +321,8 @@ mod tests {
 					signature: String::from("event2"),
 				},
 			],
-		});
+		})
+		.unwrap();
 
 		let expected_indexer = Indexer::new(
 			IndexerId::from("ID"),
@@ -283,14 +332,15 @@ mod tests {
 				EventFilter::new(ContractAddress::from_str("0x1234").unwrap(), "event1"),
 				EventFilter::new(ContractAddress::from_str("0x1234").unwrap(), "event2"),
 			],
-		);
+		)
+		.indexed_to_block(1300);
 
 		assert_eq!(expected_indexer, indexer);
 	}
 
 	#[test]
-	fn indexer_from_apibara_with_no_network() {
-		let indexer = Indexer::from(apibara::Indexer {
+	fn indexer_from_apibara_with_no_network_is_rejected() {
+		let result = Indexer::try_from(apibara::Indexer {
 			id: String::from("ID"),
 			network: None,
 			index_from_block: 1234,
@@ -298,13 +348,70 @@ mod tests {
 			filters: Vec::new(),
 		});
 
-		let expected_indexer = Indexer::new(
+		assert_eq!(
+			"the indexer `ID` returned by the indexing service is invalid: invalid network: missing network",
+			result.unwrap_err().to_string()
+		);
+	}
+
+	#[tokio::test]
+	async fn indexers_can_be_managed_end_to_end() {
+		let server = FakeServer::start(vec![]).await;
+		let client = server.client();
+		let indexer = Indexer::new(
+			IndexerId::from("ID"),
+			Network::Starknet(StarknetChain::Devnet),
+			1234,
+			vec![EventFilter::new(
+				ContractAddress::from_str("0x1234").unwrap(),
+				"event",
+			)],
+		);
+
+		assert_eq!(None, client.by_id(&indexer.id).await.unwrap());
+
+		client.create(&indexer).await.unwrap();
+		assert_eq!(
+			Some(indexer.clone()),
+			client.by_id(&indexer.id).await.unwrap()
+		);
+		assert_eq!(vec![indexer.clone()], client.list().await.unwrap());
+
+		let result = client.create(&indexer).await;
+		assert!(matches!(
+			result,
+			Err(IndexerRepositoryError::CreateIndexer { .. })
+		));
+
+		client.delete(&indexer.id).await.unwrap();
+		assert!(client.list().await.unwrap().is_empty());
+	}
+
+	#[tokio::test]
+	async fn invalid_indexers_are_skipped_when_listing() {
+		let server = FakeServer::start(vec![]).await;
+		let client = server.client();
+		let indexer = Indexer::new(
 			IndexerId::from("ID"),
 			Network::Starknet(StarknetChain::Devnet),
 			1234,
 			Vec::new(),
 		);
+		client.create(&indexer).await.unwrap();
+		server.insert_indexer(apibara::Indexer {
+			id: String::from("INVALID"),
+			network: Some(apibara::Network {
+				network: Some(apibara::network::Network::Starknet(
+					apibara::StarkNetNetwork {
+						name: String::from("non-existent"),
+					},
+				)),
+			}),
+			index_from_block: 1234,
+			indexed_to_block: None,
+			filters: Vec::new(),
+		});
 
-		assert_eq!(expected_indexer, indexer);
+		assert_eq!(vec![indexer], client.list().await.unwrap());
 	}
 }
@@ -1,56 +1,227 @@
 use async_trait::async_trait;
-use std::sync::Arc;
-use tokio::sync::mpsc::{self, Receiver, Sender};
+use std::{future::Future, sync::Arc, time::Duration};
+use tokio::{
+	sync::mpsc::{self, Receiver, Sender},
+	time::Instant,
+};
 use tokio_stream::wrappers::ReceiverStream;
+use tokio_util::sync::CancellationToken;
 
 use super::{
 	apibara::{
 		connect_indexer_request::Message as RequestMessage,
 		connect_indexer_response::Message as ResponseMessage, AckBlock, ConnectIndexer,
-		ConnectIndexerRequest, ConnectIndexerResponse, IndexerConnected, NewBlock, NewEvents,
+		ConnectIndexerRequest, ConnectIndexerResponse,
 	},
+	event_stream::{message, Acknowledger},
 	*,
 };
 use crate::domain::*;
 
+/**
+ * How long to wait for the server to close the stream on shutdown
+ */
+const CLOSE_TIMEOUT: Duration = Duration::from_secs(5);
+
 #[async_trait]
 impl IndexingService for Client {
 	async fn fetch_new_events(
 		&self,
 		indexer: &Indexer,
 		observer: Arc<dyn BlockchainObserver>,
+		shutdown: CancellationToken,
+	) -> Result<(), IndexingServiceError> {
+		let mut retries = 0;
+		let mut reconnecting = false;
+
+		loop {
+			let mut connected = false;
+			let result = self
+				.stream_events(
+					indexer,
+					&*observer,
+					&shutdown,
+					reconnecting,
+					&mut retries,
+					&mut connected,
+				)
+				.await;
+			if let Some(health) = &self.health {
+				health.on_stream_closed(&indexer.id);
+			}
+			let error = match result {
+				// Observer failures are not related to the connection, reconnecting would not help
+				Err(error @ IndexingServiceError::Observer(_)) => return Err(error),
+				Err(error) => error,
+				Ok(()) => return Ok(()),
+			};
+
+			// Failed attempts to connect do not disconnect anything
+			if connected {
+				observer.on_disconnect(&indexer.id).await?;
+				reconnecting = true;
+			}
+			if !self.reconnect_policy.should_retry(retries) {
+				return Err(error);
+			}
+
+			tokio::select! {
+				biased;
+				_ = shutdown.cancelled() => return Ok(()),
+				_ = tokio::time::sleep(self.reconnect_policy.delay(retries)) => (),
+			};
+			retries += 1;
+		}
+	}
+}
+
+impl Client {
+	/**
+	 * Connect the indexer and handle the responses until the connection fails, or until
+	 * shutdown. `retries` is reset as soon as the server sends a response, `connected` is set
+	 * once the stream is established.
+	 */
+	async fn stream_events(
+		&self,
+		indexer: &Indexer,
+		observer: &dyn BlockchainObserver,
+		shutdown: &CancellationToken,
+		reconnecting: bool,
+		retries: &mut u32,
+		connected: &mut bool,
 	) -> Result<(), IndexingServiceError> {
+		let (tx, mut response_stream) = tokio::select! {
+			biased;
+			_ = shutdown.cancelled() => return Ok(()),
+			result = self.connect(&indexer.id) => result?,
+		};
+		*connected = true;
+		if let Some(health) = &self.health {
+			health.on_stream_opened(&indexer.id);
+		}
+
+		if reconnecting {
+			observer.on_reconnect(&indexer.id).await?;
+		}
+
+		let mut watchdog = Watchdog::new(self.idle_timeout);
+		loop {
+			// Responses are only awaited between batches, so that shutdown never interrupts the
+			// handling of a block
+			let message = tokio::select! {
+				biased;
+				_ = shutdown.cancelled() => {
+					close(tx, response_stream).await;
+					return Ok(());
+				},
+				message = watchdog.watch(response_stream.message()) => message?,
+			};
+
+			match message.map_err(|error| IndexingServiceError::Receive(error.to_string()))? {
+				Some(response) => {
+					let received_at = Instant::now();
+					*retries = 0;
+					watchdog.reset();
+					if let Some(recorder) = &self.recorder {
+						recorder.record(&indexer.id, &response).await;
+					}
+					let is_new_events =
+						matches!(response.message, Some(ResponseMessage::NewEvents(_)));
+					handle_response(response, &tx, &self.abis, observer).await?;
+					if let (true, Some(metrics)) = (is_new_events, &self.metrics) {
+						metrics.observe_ack_latency(&indexer.id, received_at.elapsed());
+					}
+				},
+				None => return Err(IndexingServiceError::StreamClosed),
+			}
+		}
+	}
+
+	/**
+	 * Open the indexing stream of the indexer.
+	 * Returns the sender of the requests, to ack the blocks, along with the stream of
+	 * responses.
+	 */
+	pub(super) async fn connect(
+		&self,
+		indexer_id: &IndexerId,
+	) -> Result<
+		(
+			Sender<ConnectIndexerRequest>,
+			tonic::Streaming<ConnectIndexerResponse>,
+		),
+		IndexingServiceError,
+	> {
 		let channel = Channel::new();
-		send_connect_request(&channel.tx, &indexer.id).await?;
+		send_connect_request(&channel.tx, indexer_id).await?;
 
-		let mut response_stream = self
-			.0
+		let response_stream = self
+			.inner
 			.write()
 			.await
 			.connect_indexer(ReceiverStream::new(channel.rx))
 			.await
 			.map_err(|e| IndexingServiceError::Connection {
-				id: indexer.id.clone(),
+				id: indexer_id.clone(),
 				details: e.to_string(),
 			})?
 			.into_inner();
 
-		loop {
-			match response_stream
-				.message()
+		Ok((channel.tx, response_stream))
+	}
+}
+
+/**
+ * Close the requests side of the stream, so that the acks already sent are flushed, then wait
+ * for the server to close the responses side. Responses received meanwhile are not handled, so
+ * their blocks will be sent again on the next connection.
+ */
+async fn close(
+	tx: Sender<ConnectIndexerRequest>,
+	mut response_stream: tonic::Streaming<ConnectIndexerResponse>,
+) {
+	drop(tx);
+	let _ = tokio::time::timeout(CLOSE_TIMEOUT, async {
+		while let Ok(Some(_)) = response_stream.message().await {}
+	})
+	.await;
+}
+
+/**
+ * Fails when no response has been received within the idle timeout, if any
+ */
+struct Watchdog {
+	idle_timeout: Option<Duration>,
+	deadline: Option<Instant>,
+}
+
+impl Watchdog {
+	fn new(idle_timeout: Option<Duration>) -> Self {
+		let mut watchdog = Self {
+			idle_timeout,
+			deadline: None,
+		};
+		watchdog.reset();
+		watchdog
+	}
+
+	fn reset(&mut self) {
+		self.deadline = self.idle_timeout.map(|idle_timeout| Instant::now() + idle_timeout);
+	}
+
+	async fn watch<F: Future>(&self, future: F) -> Result<F::Output, IndexingServiceError> {
+		match (self.deadline, self.idle_timeout) {
+			(Some(deadline), Some(idle_timeout)) => tokio::time::timeout_at(deadline, future)
 				.await
-				.map_err(|error| IndexingServiceError::Receive(error.to_string()))?
-			{
-				Some(response) => handle_response(response, &channel.tx, &*observer).await?,
-				None => continue,
-			}
+				.map_err(|_| IndexingServiceError::Idle(idle_timeout)),
+			_ => Ok(future.await),
 		}
 	}
 }
 
-struct Channel {
-	tx: Sender<ConnectIndexerRequest>,
-	rx: Receiver<ConnectIndexerRequest>,
+pub(super) struct Channel {
+	pub(super) tx: Sender<ConnectIndexerRequest>,
+	pub(super) rx: Receiver<ConnectIndexerRequest>,
 }
 
 impl Channel {
@@ -67,7 +238,7 @@ async fn send_connect_request(
 	send(sender, connect_request(indexer_id)).await
 }
 
-async fn send_ack_request(
+pub(super) async fn send_ack_request(
 	sender: &Sender<ConnectIndexerRequest>,
 	block_hash: &BlockHash,
 ) -> Result<(), IndexingServiceError> {
@@ -97,56 +268,71 @@ fn ack_block(block_hash: &BlockHash) -> ConnectIndexerRequest {
 	}
 }
 
-async fn handle_response(
+/**
+ * Decode the response into a message of the event stream, then notify the observer. Blocks are
+ * acked once all their events have been observed.
+ */
+pub(super) async fn handle_response(
 	response: ConnectIndexerResponse,
 	sender: &Sender<ConnectIndexerRequest>,
+	abis: &ContractAbis,
 	observer: &dyn BlockchainObserver,
 ) -> Result<(), IndexingServiceError> {
-	match response.message {
-		Some(ResponseMessage::Connected(IndexerConnected {
-			indexer: Some(indexer),
-			version: _,
-		})) => {
-			observer.on_connect(&indexer.id.into());
-			Ok(())
-		},
-
-		Some(ResponseMessage::NewBlock(NewBlock {
-			new_head: Some(new_head),
-		})) => {
-			let block_hash = BlockHash::from(new_head.hash);
-			observer.on_new_block(&block_hash);
-			Ok(())
-		},
-
-		Some(ResponseMessage::Reorg(_)) => {
-			observer.on_reorg();
-			Ok(())
-		},
-
-		Some(ResponseMessage::NewEvents(NewEvents { block, events })) => {
-			events.into_iter().for_each(|event| {
-				if let Some(event) = event.try_into().ok() {
-					observer.on_new_event(&event);
-				}
-			});
+	let acknowledger: Arc<dyn BlockAcknowledger> = Arc::new(Acknowledger(sender.clone()));
+	match message(response, abis, &acknowledger)? {
+		Some(IndexerMessage::Connected(indexer_id)) => observer.on_connect(&indexer_id).await?,
+
+		Some(IndexerMessage::NewBlock(block_hash)) => observer.on_new_block(&block_hash).await?,
+
+		Some(IndexerMessage::Reorg(new_head)) => observer.on_reorg(&new_head).await?,
+
+		Some(IndexerMessage::NewEvents {
+			block,
+			events,
+			undecodable_events,
+			ack,
+		}) => {
+			for (event, metadata) in events {
+				observer.on_new_event(&event, &metadata).await?;
+			}
+			for dead_letter in undecodable_events {
+				observer
+					.on_undecodable_event(
+						&dead_letter.event,
+						&dead_letter.metadata,
+						&dead_letter.error,
+					)
+					.await?;
+			}
 
-			match block {
-				Some(header) => send_ack_request(sender, &header.hash.into()).await,
-				_ => Ok(()),
+			if let Some(ack) = ack {
+				ack.ack().await?;
+				observer.on_block_processed(&block).await?;
 			}
 		},
 
-		_ => Ok(()),
+		None => (),
+	}
+	Ok(())
+}
+
+impl From<apibara::BlockHeader> for BlockHeader {
+	fn from(header: apibara::BlockHeader) -> Self {
+		let block = Self::new(header.hash.into(), header.number);
+		match header.timestamp.and_then(|timestamp| u64::try_from(timestamp.seconds).ok()) {
+			Some(timestamp) => block.with_timestamp(timestamp),
+			None => block,
+		}
 	}
 }
 
 #[cfg(test)]
 mod test {
 	use super::{
-		apibara::{BlockHeader, TopicValue},
+		apibara::{BlockHeader, IndexerConnected, NewBlock, TopicValue},
 		*,
 	};
+	use crate::infrastructure::apibara::fake_server::{self, FakeServer, Step};
 	use mockall::predicate::*;
 	use rstest::*;
 	use tokio::sync::mpsc::error::TryRecvError;
@@ -207,11 +393,64 @@ mod test {
 						],
 					},
 				],
+				log_index: 7,
 				..Default::default()
 			})),
 		}
 	}
 
+	#[rstest]
+	#[tokio::test]
+	async fn gives_up_reconnecting_according_to_the_policy(mut observer: MockBlockchainObserver) {
+		let channel = tonic::transport::Endpoint::from_static("http://127.0.0.1:1").connect_lazy();
+		let client = Client::new(IndexerManagerClient::new(channel)).with_reconnect_policy(
+			ReconnectPolicy::default().initial_delay(Duration::from_millis(1)).max_retries(2),
+		);
+
+		observer.expect_on_disconnect().never();
+		observer.expect_on_reconnect().never();
+
+		let indexer = Indexer::new("ID".into(), Network::Starknet(StarknetChain::Devnet), 0, vec![]);
+		let result = client
+			.fetch_new_events(&indexer, Arc::new(observer), CancellationToken::new())
+			.await;
+		assert!(
+			matches!(result, Err(IndexingServiceError::Connection { .. })),
+			"{result:?}"
+		);
+	}
+
+	#[tokio::test]
+	async fn watchdog_fails_when_idle_for_too_long() {
+		let watchdog = Watchdog::new(Some(Duration::from_millis(10)));
+		let result = watchdog.watch(std::future::pending::<()>()).await;
+		assert!(matches!(result, Err(IndexingServiceError::Idle(_))), "{result:?}");
+	}
+
+	#[tokio::test]
+	async fn watchdog_forwards_the_output_in_time() {
+		let watchdog = Watchdog::new(Some(Duration::from_secs(60)));
+		let result = watchdog.watch(async { 42 }).await;
+		assert_eq!(42, result.unwrap());
+	}
+
+	#[tokio::test]
+	async fn watchdog_can_be_reset() {
+		let mut watchdog = Watchdog::new(Some(Duration::from_millis(50)));
+		tokio::time::sleep(Duration::from_millis(30)).await;
+		watchdog.reset();
+
+		let result = watchdog.watch(tokio::time::sleep(Duration::from_millis(30))).await;
+		assert!(result.is_ok(), "{result:?}");
+	}
+
+	#[tokio::test]
+	async fn watchdog_can_be_disabled() {
+		let watchdog = Watchdog::new(None);
+		let result = watchdog.watch(async { 42 }).await;
+		assert_eq!(42, result.unwrap());
+	}
+
 	#[rstest]
 	#[tokio::test]
 	async fn can_send_a_connect_request(mut channel: Channel, indexer_id: IndexerId) {
@@ -242,9 +481,10 @@ mod test {
 			})),
 		};
 
-		observer.expect_on_connect().return_const(());
+		observer.expect_on_connect().returning(|_| Ok(()));
 
-		let result = handle_response(response, &channel.tx, &observer).await;
+		let result =
+			handle_response(response, &channel.tx, &ContractAbis::default(), &observer).await;
 		assert!(result.is_ok(), "{}", result.err().unwrap());
 		assert_eq!(TryRecvError::Empty, channel.rx.try_recv().unwrap_err());
 	}
@@ -265,9 +505,10 @@ mod test {
 			})),
 		};
 
-		observer.expect_on_new_block().with(eq(block_hash.clone())).return_const(());
+		observer.expect_on_new_block().with(eq(block_hash.clone())).returning(|_| Ok(()));
 
-		let result = handle_response(response, &channel.tx, &observer).await;
+		let result =
+			handle_response(response, &channel.tx, &ContractAbis::default(), &observer).await;
 		assert!(result.is_ok(), "{}", result.err().unwrap());
 		assert_eq!(TryRecvError::Empty, channel.rx.try_recv().unwrap_err());
 	}
@@ -284,15 +525,30 @@ mod test {
 			message: Some(ResponseMessage::NewEvents(apibara::NewEvents {
 				block: Some(BlockHeader {
 					hash: block_hash.bytes(),
+					number: 42,
 					..Default::default()
 				}),
 				events: vec![apibara_event.clone(), apibara_event, Default::default()],
 			})),
 		};
 
-		observer.expect_on_new_event().times(2).return_const(());
-
-		let result = handle_response(response, &channel.tx, &observer).await;
+		observer
+			.expect_on_new_event()
+			.withf(|_, metadata| metadata.block_number == 42 && metadata.log_index == 7)
+			.times(2)
+			.returning(|_, _| Ok(()));
+		observer
+			.expect_on_undecodable_event()
+			.withf(|_, metadata, _| metadata.block_number == 42)
+			.times(1)
+			.returning(|_, _, _| Ok(()));
+		observer
+			.expect_on_block_processed()
+			.with(eq(crate::domain::BlockHeader::new(block_hash.clone(), 42)))
+			.returning(|_| Ok(()));
+
+		let result =
+			handle_response(response, &channel.tx, &ContractAbis::default(), &observer).await;
 		assert!(result.is_ok(), "{}", result.err().unwrap());
 
 		let request = channel.rx.try_recv().unwrap();
@@ -304,30 +560,326 @@ mod test {
 		);
 	}
 
+	#[rstest]
+	#[tokio::test]
+	async fn does_not_ack_a_block_when_an_observer_fails(
+		mut channel: Channel,
+		mut observer: MockBlockchainObserver,
+		apibara_event: apibara::Event,
+		block_hash: BlockHash,
+	) {
+		let response = ConnectIndexerResponse {
+			message: Some(ResponseMessage::NewEvents(apibara::NewEvents {
+				block: Some(BlockHeader {
+					hash: block_hash.bytes(),
+					..Default::default()
+				}),
+				events: vec![apibara_event.clone(), apibara_event],
+			})),
+		};
+
+		observer.expect_on_new_event().times(1).returning(|_, _| {
+			Err(BlockchainObserverError::Infrastructure(
+				"database unavailable".into(),
+			))
+		});
+
+		let result =
+			handle_response(response, &channel.tx, &ContractAbis::default(), &observer).await;
+		assert!(matches!(result, Err(IndexingServiceError::Observer(_))));
+		assert_eq!(TryRecvError::Empty, channel.rx.try_recv().unwrap_err());
+	}
+
 	#[rstest]
 	#[tokio::test]
 	async fn can_handle_a_new_reorg_response(
 		mut channel: Channel,
 		mut observer: MockBlockchainObserver,
+		block_hash: BlockHash,
 	) {
 		let response = ConnectIndexerResponse {
-			message: Some(ResponseMessage::Reorg(apibara::Reorg::default())),
+			message: Some(ResponseMessage::Reorg(apibara::Reorg {
+				new_head: Some(BlockHeader {
+					hash: block_hash.bytes(),
+					number: 42,
+					..Default::default()
+				}),
+			})),
 		};
 
-		observer.expect_on_reorg().return_const(());
+		observer
+			.expect_on_reorg()
+			.with(eq(crate::domain::BlockHeader::new(block_hash, 42)))
+			.returning(|_| Ok(()));
 
-		let result = handle_response(response, &channel.tx, &observer).await;
+		let result =
+			handle_response(response, &channel.tx, &ContractAbis::default(), &observer).await;
 		assert!(result.is_ok(), "{}", result.err().unwrap());
 		assert_eq!(TryRecvError::Empty, channel.rx.try_recv().unwrap_err());
 	}
 
+	#[rstest]
+	#[tokio::test]
+	async fn fails_on_a_reorg_without_new_head(
+		mut channel: Channel,
+		mut observer: MockBlockchainObserver,
+	) {
+		let response = ConnectIndexerResponse {
+			message: Some(ResponseMessage::Reorg(apibara::Reorg { new_head: None })),
+		};
+
+		observer.expect_on_reorg().never();
+
+		let result =
+			handle_response(response, &channel.tx, &ContractAbis::default(), &observer).await;
+		assert!(matches!(
+			result,
+			Err(IndexingServiceError::ReorgWithoutHead)
+		));
+		assert_eq!(TryRecvError::Empty, channel.rx.try_recv().unwrap_err());
+	}
+
 	#[rstest]
 	#[tokio::test]
 	async fn can_handle_an_empty_response(mut channel: Channel, observer: MockBlockchainObserver) {
 		let response = ConnectIndexerResponse { message: None };
 
-		let result = handle_response(response, &channel.tx, &observer).await;
+		let result =
+			handle_response(response, &channel.tx, &ContractAbis::default(), &observer).await;
 		assert!(result.is_ok(), "{}", result.err().unwrap());
 		assert_eq!(TryRecvError::Empty, channel.rx.try_recv().unwrap_err());
 	}
+
+	#[rstest]
+	fn block_headers_keep_their_timestamp(block_hash: BlockHash) {
+		let header = BlockHeader {
+			hash: block_hash.bytes(),
+			number: 42,
+			timestamp: Some(prost_types::Timestamp {
+				seconds: 1660000000,
+				nanos: 0,
+			}),
+			..Default::default()
+		};
+
+		assert_eq!(
+			crate::domain::BlockHeader::new(block_hash, 42).with_timestamp(1660000000),
+			header.into()
+		);
+	}
+
+	fn indexer() -> Indexer {
+		Indexer::new(
+			"ID".into(),
+			Network::Starknet(StarknetChain::Devnet),
+			0,
+			Vec::new(),
+		)
+	}
+
+	#[rstest]
+	#[tokio::test]
+	async fn streams_events_and_acks_blocks_end_to_end(apibara_event: apibara::Event) {
+		let server = FakeServer::start(vec![vec![
+			fake_server::connected("ID"),
+			fake_server::new_block(vec![1], 1),
+			fake_server::new_events(vec![1], 1, vec![apibara_event.clone()]),
+			Step::WaitForAck(vec![1]),
+			fake_server::new_block(vec![2], 2),
+			fake_server::new_events(vec![2], 2, vec![apibara_event]),
+			Step::WaitForAck(vec![2]),
+			Step::Close,
+		]])
+		.await;
+		let client = server.client().with_reconnect_policy(ReconnectPolicy::never());
+		let observer = Arc::new(InMemoryBlockchainObserver::default());
+
+		let result = client
+			.fetch_new_events(&indexer(), observer.clone(), CancellationToken::new())
+			.await;
+
+		assert!(
+			matches!(result, Err(IndexingServiceError::StreamClosed)),
+			"{:?}",
+			result
+		);
+		assert_eq!(vec![IndexerId::from("ID")], server.connections());
+		assert_eq!(
+			vec![BlockHash::from(vec![1]), BlockHash::from(vec![2])],
+			server.acks()
+		);
+		assert_eq!(
+			vec![1, 2],
+			observer
+				.events()
+				.iter()
+				.map(|(_, metadata)| metadata.block_number)
+				.collect::<Vec<_>>()
+		);
+	}
+
+	#[rstest]
+	#[tokio::test]
+	async fn rolls_back_on_reorg_end_to_end(apibara_event: apibara::Event) {
+		let server = FakeServer::start(vec![vec![
+			fake_server::connected("ID"),
+			fake_server::new_events(vec![1], 1, vec![apibara_event.clone()]),
+			Step::WaitForAck(vec![1]),
+			fake_server::new_events(vec![2], 2, vec![apibara_event]),
+			Step::WaitForAck(vec![2]),
+			fake_server::reorg(vec![1], 1),
+			Step::Close,
+		]])
+		.await;
+		let client = server.client().with_reconnect_policy(ReconnectPolicy::never());
+		let observer = Arc::new(InMemoryBlockchainObserver::default());
+
+		let result = client
+			.fetch_new_events(&indexer(), observer.clone(), CancellationToken::new())
+			.await;
+
+		assert!(
+			matches!(result, Err(IndexingServiceError::StreamClosed)),
+			"{:?}",
+			result
+		);
+		assert_eq!(1, observer.events().len());
+		assert_eq!(1, observer.events()[0].1.block_number);
+	}
+
+	#[rstest]
+	#[tokio::test]
+	async fn reconnects_after_the_stream_closed_end_to_end(mut observer: MockBlockchainObserver) {
+		let server = FakeServer::start(vec![
+			vec![
+				fake_server::connected("ID"),
+				fake_server::new_events(vec![1], 1, vec![]),
+				Step::WaitForAck(vec![1]),
+				Step::Close,
+			],
+			vec![
+				fake_server::connected("ID"),
+				fake_server::new_events(vec![2], 2, vec![]),
+				Step::WaitForAck(vec![2]),
+				Step::Close,
+			],
+			// Retries are reset by the responses above, so closing again without any response
+			// is needed to exhaust them
+			vec![Step::Close],
+		])
+		.await;
+		let client = server.client().with_reconnect_policy(
+			ReconnectPolicy::default()
+				.initial_delay(Duration::from_millis(1))
+				.max_retries(1),
+		);
+
+		observer.expect_on_connect().times(2).returning(|_| Ok(()));
+		observer.expect_on_block_processed().times(2).returning(|_| Ok(()));
+		observer.expect_on_disconnect().times(3).returning(|_| Ok(()));
+		observer.expect_on_reconnect().times(2).returning(|_| Ok(()));
+
+		let result = client
+			.fetch_new_events(&indexer(), Arc::new(observer), CancellationToken::new())
+			.await;
+
+		assert!(
+			matches!(result, Err(IndexingServiceError::StreamClosed)),
+			"{:?}",
+			result
+		);
+		assert_eq!(3, server.connections().len());
+		assert_eq!(
+			vec![BlockHash::from(vec![1]), BlockHash::from(vec![2])],
+			server.acks()
+		);
+	}
+
+	#[rstest]
+	#[tokio::test]
+	async fn does_not_ack_a_block_when_an_observer_fails_end_to_end(
+		mut observer: MockBlockchainObserver,
+		apibara_event: apibara::Event,
+	) {
+		let server = FakeServer::start(vec![vec![
+			fake_server::connected("ID"),
+			fake_server::new_events(vec![1], 1, vec![apibara_event]),
+		]])
+		.await;
+		let client = server.client().with_reconnect_policy(ReconnectPolicy::never());
+
+		observer.expect_on_connect().returning(|_| Ok(()));
+		observer.expect_on_new_event().returning(|_, _| {
+			Err(BlockchainObserverError::Infrastructure(Box::from(
+				"failure",
+			)))
+		});
+
+		let result = client
+			.fetch_new_events(&indexer(), Arc::new(observer), CancellationToken::new())
+			.await;
+
+		assert!(
+			matches!(result, Err(IndexingServiceError::Observer(_))),
+			"{:?}",
+			result
+		);
+		assert!(server.acks().is_empty());
+	}
+
+	#[rstest]
+	#[tokio::test]
+	async fn finishes_and_acks_the_current_block_on_shutdown_end_to_end(
+		mut observer: MockBlockchainObserver,
+		apibara_event: apibara::Event,
+	) {
+		let server = FakeServer::start(vec![vec![
+			fake_server::connected("ID"),
+			fake_server::new_events(vec![1], 1, vec![apibara_event.clone(), apibara_event]),
+			fake_server::new_events(vec![2], 2, vec![]),
+		]])
+		.await;
+		let client = server.client().with_reconnect_policy(ReconnectPolicy::never());
+		let shutdown = CancellationToken::new();
+
+		observer.expect_on_connect().returning(|_| Ok(()));
+		{
+			// The signal arrives in the middle of the first block
+			let shutdown = shutdown.clone();
+			observer.expect_on_new_event().times(2).returning(move |_, _| {
+				shutdown.cancel();
+				Ok(())
+			});
+		}
+		observer
+			.expect_on_block_processed()
+			.withf(|block| block.number == 1)
+			.times(1)
+			.returning(|_| Ok(()));
+		observer.expect_on_disconnect().never();
+
+		let result = tokio::time::timeout(
+			Duration::from_secs(1),
+			client.fetch_new_events(&indexer(), Arc::new(observer), shutdown),
+		)
+		.await
+		.expect("the stream was not closed");
+
+		assert!(result.is_ok(), "{:?}", result);
+		assert_eq!(vec![BlockHash::from(vec![1])], server.acks());
+	}
+
+	#[rstest]
+	#[tokio::test]
+	async fn does_not_reconnect_after_shutdown(mut observer: MockBlockchainObserver) {
+		let channel = tonic::transport::Endpoint::from_static("http://127.0.0.1:1").connect_lazy();
+		let client = Client::new(IndexerManagerClient::new(channel));
+		let shutdown = CancellationToken::new();
+		shutdown.cancel();
+
+		observer.expect_on_disconnect().never();
+
+		let result = client.fetch_new_events(&indexer(), Arc::new(observer), shutdown).await;
+		assert!(result.is_ok(), "{:?}", result);
+	}
 }
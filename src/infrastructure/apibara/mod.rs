@@ -5,24 +5,112 @@ use proto as apibara;
 mod error;
 use error::Error;
 
+mod dead_letters;
+pub use dead_letters::DeadLetterDecoder;
+
+mod event_stream;
 mod events;
+pub(crate) use events::codegen;
+pub use events::{Abi, AbiError, ContractAbis};
 mod indexer_repository;
 mod indexing_service;
 
+mod reconnect;
+pub use reconnect::ReconnectPolicy;
+
+mod record;
+pub use record::Recorder;
+
+mod replay;
+pub use replay::Replay;
+
+#[cfg(test)]
+mod fake_server;
+
+use crate::{
+	domain::ContractAddress,
+	infrastructure::{Health, Metrics},
+};
 use apibara::indexer_manager_client::IndexerManagerClient;
+use std::{sync::Arc, time::Duration};
 use tokio::sync::RwLock;
 
-pub struct Client(RwLock<IndexerManagerClient<tonic::transport::Channel>>);
+pub struct Client {
+	inner: RwLock<IndexerManagerClient<tonic::transport::Channel>>,
+	reconnect_policy: ReconnectPolicy,
+	idle_timeout: Option<Duration>,
+	recorder: Option<Arc<Recorder>>,
+	abis: Arc<ContractAbis>,
+	metrics: Option<Arc<Metrics>>,
+	health: Option<Arc<Health>>,
+}
 
 impl Client {
 	pub fn new(inner: IndexerManagerClient<tonic::transport::Channel>) -> Self {
-		Self(RwLock::new(inner))
+		Self {
+			inner: RwLock::new(inner),
+			reconnect_policy: Default::default(),
+			idle_timeout: None,
+			recorder: None,
+			abis: Default::default(),
+			metrics: None,
+			health: None,
+		}
 	}
 
 	pub async fn default() -> Result<Self, Error> {
 		let inner = IndexerManagerClient::connect(apibara_url()).await.map_err(Error::from)?;
 		Ok(Self::new(inner))
 	}
+
+	/**
+	 * Modify the way the indexing stream reconnects after being disconnected
+	 */
+	pub fn with_reconnect_policy(mut self, reconnect_policy: ReconnectPolicy) -> Self {
+		self.reconnect_policy = reconnect_policy;
+		self
+	}
+
+	/**
+	 * Consider the connection dead if no response is received within `idle_timeout`
+	 */
+	pub fn with_idle_timeout(mut self, idle_timeout: Duration) -> Self {
+		self.idle_timeout = Some(idle_timeout);
+		self
+	}
+
+	/**
+	 * Record every response of the indexing streams, so that they can be replayed later on
+	 */
+	pub fn with_recorder(mut self, recorder: Recorder) -> Self {
+		self.recorder = Some(Arc::new(recorder));
+		self
+	}
+
+	/**
+	 * Decode the events of the contract with the given address as described by the ABI, even
+	 * those without a dedicated type
+	 */
+	pub fn with_abi(mut self, contract_address: ContractAddress, abi: Abi) -> Self {
+		Arc::make_mut(&mut self.abis).add(contract_address, abi);
+		self
+	}
+
+	/**
+	 * Measure the time it takes to handle and ack the events of each block
+	 */
+	pub fn with_metrics(mut self, metrics: Arc<Metrics>) -> Self {
+		self.metrics = Some(metrics);
+		self
+	}
+
+	/**
+	 * Report whether the indexing streams are open, for the readiness of the indexers
+	 */
+	pub fn with_health(mut self, health: Arc<Health>) -> Self {
+		self.health = Some(health);
+		self
+	}
 }
 
 fn apibara_url() -> String {
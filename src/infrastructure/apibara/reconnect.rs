@@ -0,0 +1,117 @@
+use rand::Rng;
+use std::time::Duration;
+
+/**
+ * Describes how the indexing stream reconnects to the Apibara server once disconnected.
+ * Delays grow exponentially from `initial_delay` up to `max_delay`, with some jitter so that
+ * several indexers do not reconnect all at once.
+ */
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReconnectPolicy {
+	initial_delay: Duration,
+	max_delay: Duration,
+	max_retries: Option<u32>,
+}
+
+impl Default for ReconnectPolicy {
+	fn default() -> Self {
+		Self {
+			initial_delay: Duration::from_millis(500),
+			max_delay: Duration::from_secs(60),
+			max_retries: None,
+		}
+	}
+}
+
+impl ReconnectPolicy {
+	/**
+	 * Never reconnect, give up at the first disconnection
+	 */
+	pub fn never() -> Self {
+		Self::default().max_retries(0)
+	}
+
+	/**
+	 * Modify the delay before the first reconnection attempt
+	 */
+	pub fn initial_delay(mut self, delay: Duration) -> Self {
+		self.initial_delay = delay;
+		self
+	}
+
+	/**
+	 * Modify the upper bound of the delay between two reconnection attempts
+	 */
+	pub fn max_delay(mut self, delay: Duration) -> Self {
+		self.max_delay = delay;
+		self
+	}
+
+	/**
+	 * Give up after `max_retries` consecutive failed reconnection attempts
+	 */
+	pub fn max_retries(mut self, max_retries: u32) -> Self {
+		self.max_retries = Some(max_retries);
+		self
+	}
+
+	/**
+	 * Whether a new attempt should be made after `retries` consecutive failed attempts
+	 */
+	pub fn should_retry(&self, retries: u32) -> bool {
+		!matches!(self.max_retries, Some(max_retries) if retries >= max_retries)
+	}
+
+	/**
+	 * Delay to wait before the reconnection attempt following `retries` failed attempts.
+	 * The delay is picked randomly between half and all of the exponential backoff.
+	 */
+	pub fn delay(&self, retries: u32) -> Duration {
+		let backoff = self
+			.initial_delay
+			.checked_mul(2_u32.saturating_pow(retries))
+			.unwrap_or(self.max_delay)
+			.min(self.max_delay);
+
+		rand::thread_rng().gen_range(backoff / 2..=backoff)
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use rstest::*;
+
+	#[rstest]
+	#[case(0, 250, 500)]
+	#[case(1, 500, 1000)]
+	#[case(3, 2000, 4000)]
+	#[case(10, 30000, 60000)]
+	#[case(100, 30000, 60000)]
+	fn delay_grows_exponentially_up_to_max_delay(
+		#[case] retries: u32,
+		#[case] min_millis: u64,
+		#[case] max_millis: u64,
+	) {
+		let delay = ReconnectPolicy::default().delay(retries);
+		assert!(delay >= Duration::from_millis(min_millis), "{delay:?}");
+		assert!(delay <= Duration::from_millis(max_millis), "{delay:?}");
+	}
+
+	#[test]
+	fn retries_forever_by_default() {
+		assert!(ReconnectPolicy::default().should_retry(u32::MAX - 1));
+	}
+
+	#[test]
+	fn gives_up_after_max_retries() {
+		let policy = ReconnectPolicy::default().max_retries(3);
+		assert!(policy.should_retry(2));
+		assert!(!policy.should_retry(3));
+	}
+
+	#[test]
+	fn never_retries() {
+		assert!(!ReconnectPolicy::never().should_retry(0));
+	}
+}
@@ -0,0 +1,388 @@
+use super::apibara::{
+	connect_indexer_response::Message as ResponseMessage, event::Event as ApibaraEventInner,
+	BlockHeader, ConnectIndexerResponse, EthereumEvent, Event as ApibaraEvent, Indexer,
+	IndexerConnected, NewBlock, NewEvents, Reorg, StarkNetEvent, TopicValue,
+};
+use crate::domain::IndexerId;
+use log::warn;
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+use std::{
+	fs::File,
+	io::{BufRead, BufReader},
+	path::{Path, PathBuf},
+};
+use tokio::{io::AsyncWriteExt, sync::Mutex};
+
+/**
+ * A raw response of the indexing stream, as written in a JSON line of a recording
+ */
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Record {
+	Connected {
+		indexer_id: String,
+	},
+	NewBlock {
+		new_head: Header,
+	},
+	Reorg {
+		new_head: Header,
+	},
+	NewEvents {
+		block: Option<Header>,
+		events: Vec<RecordedEvent>,
+	},
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Header {
+	hash: Hex,
+	parent_hash: Option<Hex>,
+	number: u64,
+	timestamp: Option<i64>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RecordedEvent {
+	Starknet {
+		address: Hex,
+		log_index: u64,
+		topics: Vec<Hex>,
+		data: Vec<Hex>,
+	},
+	Ethereum {
+		address: Hex,
+		log_index: u64,
+		topics: Vec<Hex>,
+		data: Hex,
+	},
+}
+
+/**
+ * Bytes, written as a 0x prefixed hexadecimal string
+ */
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Hex(Vec<u8>);
+
+impl Serialize for Hex {
+	fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+		serializer.serialize_str(&format!("0x{}", hex::encode(&self.0)))
+	}
+}
+
+impl<'de> Deserialize<'de> for Hex {
+	fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+		let string = String::deserialize(deserializer)?;
+		let digits = string
+			.strip_prefix("0x")
+			.ok_or_else(|| de::Error::custom("missing 0x prefix"))?;
+		hex::decode(digits).map(Self).map_err(de::Error::custom)
+	}
+}
+
+impl Record {
+	/**
+	 * Returns None for the responses which carry no message
+	 */
+	pub fn from_response(response: &ConnectIndexerResponse) -> Option<Self> {
+		let record = match response.message.clone()? {
+			ResponseMessage::Connected(connected) => Self::Connected {
+				indexer_id: connected.indexer.map(|indexer| indexer.id).unwrap_or_default(),
+			},
+			ResponseMessage::NewBlock(new_block) => Self::NewBlock {
+				new_head: new_block.new_head.unwrap_or_default().into(),
+			},
+			ResponseMessage::Reorg(reorg) => Self::Reorg {
+				new_head: reorg.new_head.unwrap_or_default().into(),
+			},
+			ResponseMessage::NewEvents(new_events) => Self::NewEvents {
+				block: new_events.block.map(Header::from),
+				events: new_events
+					.events
+					.into_iter()
+					.filter_map(RecordedEvent::from_event)
+					.collect(),
+			},
+		};
+		Some(record)
+	}
+}
+
+impl From<Record> for ConnectIndexerResponse {
+	fn from(record: Record) -> Self {
+		let message = match record {
+			Record::Connected { indexer_id } => ResponseMessage::Connected(IndexerConnected {
+				indexer: Some(Indexer {
+					id: indexer_id,
+					..Default::default()
+				}),
+				version: None,
+			}),
+			Record::NewBlock { new_head } => ResponseMessage::NewBlock(NewBlock {
+				new_head: Some(new_head.into()),
+			}),
+			Record::Reorg { new_head } => ResponseMessage::Reorg(Reorg {
+				new_head: Some(new_head.into()),
+			}),
+			Record::NewEvents { block, events } => ResponseMessage::NewEvents(NewEvents {
+				block: block.map(BlockHeader::from),
+				events: events.into_iter().map(ApibaraEvent::from).collect(),
+			}),
+		};
+
+		Self {
+			message: Some(message),
+		}
+	}
+}
+
+impl From<BlockHeader> for Header {
+	fn from(header: BlockHeader) -> Self {
+		Self {
+			hash: Hex(header.hash),
+			parent_hash: header.parent_hash.map(Hex),
+			number: header.number,
+			timestamp: header.timestamp.map(|timestamp| timestamp.seconds),
+		}
+	}
+}
+
+impl From<Header> for BlockHeader {
+	fn from(header: Header) -> Self {
+		Self {
+			hash: header.hash.0,
+			parent_hash: header.parent_hash.map(|hash| hash.0),
+			number: header.number,
+			timestamp: header.timestamp.map(|seconds| prost_types::Timestamp { seconds, nanos: 0 }),
+		}
+	}
+}
+
+fn hexes(values: Vec<TopicValue>) -> Vec<Hex> {
+	values.into_iter().map(|value| Hex(value.value)).collect()
+}
+
+fn topic_values(values: Vec<Hex>) -> Vec<TopicValue> {
+	values.into_iter().map(|value| TopicValue { value: value.0 }).collect()
+}
+
+impl RecordedEvent {
+	fn from_event(event: ApibaraEvent) -> Option<Self> {
+		let event = match event.event? {
+			ApibaraEventInner::Starknet(event) => Self::Starknet {
+				address: Hex(event.address),
+				log_index: event.log_index,
+				topics: hexes(event.topics),
+				data: hexes(event.data),
+			},
+			ApibaraEventInner::Ethereum(event) => Self::Ethereum {
+				address: Hex(event.address),
+				log_index: event.log_index,
+				topics: hexes(event.topics),
+				data: Hex(event.data),
+			},
+		};
+		Some(event)
+	}
+}
+
+impl From<RecordedEvent> for ApibaraEvent {
+	fn from(event: RecordedEvent) -> Self {
+		let event = match event {
+			RecordedEvent::Starknet {
+				address,
+				log_index,
+				topics,
+				data,
+			} => ApibaraEventInner::Starknet(StarkNetEvent {
+				address: address.0,
+				log_index,
+				topics: topic_values(topics),
+				data: topic_values(data),
+			}),
+			RecordedEvent::Ethereum {
+				address,
+				log_index,
+				topics,
+				data,
+			} => ApibaraEventInner::Ethereum(EthereumEvent {
+				address: address.0,
+				log_index,
+				topics: topic_values(topics),
+				data: data.0,
+			}),
+		};
+
+		Self { event: Some(event) }
+	}
+}
+
+/**
+ * Writes every response of the indexing streams in `<directory>/<indexer id>.jsonl`
+ */
+pub struct Recorder {
+	directory: PathBuf,
+	lock: Mutex<()>,
+}
+
+impl Recorder {
+	pub fn new<P: AsRef<Path>>(directory: P) -> Self {
+		Self {
+			directory: directory.as_ref().to_path_buf(),
+			lock: Mutex::new(()),
+		}
+	}
+
+	/**
+	 * Recording is best effort: failures are logged but do not stop the indexing
+	 */
+	pub(super) async fn record(&self, indexer_id: &IndexerId, response: &ConnectIndexerResponse) {
+		if let Err(error) = self.try_record(indexer_id, response).await {
+			warn!(
+				"Unable to record response of indexer `{}`: {}",
+				indexer_id, error
+			);
+		}
+	}
+
+	async fn try_record(
+		&self,
+		indexer_id: &IndexerId,
+		response: &ConnectIndexerResponse,
+	) -> Result<(), Box<dyn std::error::Error>> {
+		let record = match Record::from_response(response) {
+			Some(record) => record,
+			None => return Ok(()),
+		};
+
+		let mut line = serde_json::to_string(&record)?;
+		line.push('\n');
+
+		let _guard = self.lock.lock().await;
+		tokio::fs::create_dir_all(&self.directory).await?;
+		let mut file = tokio::fs::OpenOptions::new()
+			.create(true)
+			.append(true)
+			.open(self.directory.join(format!("{}.jsonl", indexer_id)))
+			.await?;
+		file.write_all(line.as_bytes()).await?;
+		// A tokio file completes writes in the background, only a flush waits for them
+		file.flush().await?;
+		Ok(())
+	}
+}
+
+/**
+ * Read the responses recorded in a JSON lines file
+ */
+pub fn read<P: AsRef<Path>>(path: P) -> std::io::Result<Vec<ConnectIndexerResponse>> {
+	let file = File::open(path)?;
+	BufReader::new(file)
+		.lines()
+		.filter(|line| !matches!(line, Ok(line) if line.trim().is_empty()))
+		.map(|line| {
+			let record: Record = serde_json::from_str(&line?)?;
+			Ok(record.into())
+		})
+		.collect()
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use rstest::*;
+
+	#[fixture]
+	fn new_events() -> ConnectIndexerResponse {
+		ConnectIndexerResponse {
+			message: Some(ResponseMessage::NewEvents(NewEvents {
+				block: Some(BlockHeader {
+					hash: vec![0, 18, 52],
+					parent_hash: Some(vec![1]),
+					number: 42,
+					timestamp: Some(prost_types::Timestamp {
+						seconds: 1660000000,
+						nanos: 0,
+					}),
+				}),
+				events: vec![ApibaraEvent {
+					event: Some(ApibaraEventInner::Starknet(StarkNetEvent {
+						address: vec![18, 52],
+						log_index: 7,
+						topics: vec![TopicValue { value: vec![1] }],
+						data: vec![TopicValue { value: vec![2] }, TopicValue { value: vec![3] }],
+					})),
+				}],
+			})),
+		}
+	}
+
+	#[rstest]
+	fn records_are_written_as_json_with_hexadecimal_bytes(new_events: ConnectIndexerResponse) {
+		let record = Record::from_response(&new_events).unwrap();
+
+		assert_eq!(
+			serde_json::json!({
+				"new_events": {
+					"block": {
+						"hash": "0x001234",
+						"parent_hash": "0x01",
+						"number": 42,
+						"timestamp": 1660000000
+					},
+					"events": [{
+						"starknet": {
+							"address": "0x1234",
+							"log_index": 7,
+							"topics": ["0x01"],
+							"data": ["0x02", "0x03"]
+						}
+					}]
+				}
+			}),
+			serde_json::to_value(&record).unwrap()
+		);
+	}
+
+	#[rstest]
+	#[case(new_events())]
+	#[case(ConnectIndexerResponse {
+		message: Some(ResponseMessage::Reorg(Reorg {
+			new_head: Some(BlockHeader { hash: vec![1], number: 1, ..Default::default() }),
+		})),
+	})]
+	#[case(ConnectIndexerResponse {
+		message: Some(ResponseMessage::Connected(IndexerConnected {
+			indexer: Some(Indexer { id: String::from("ID"), ..Default::default() }),
+			version: None,
+		})),
+	})]
+	fn responses_survive_a_round_trip(#[case] response: ConnectIndexerResponse) {
+		let json = serde_json::to_string(&Record::from_response(&response).unwrap()).unwrap();
+		let record: Record = serde_json::from_str(&json).unwrap();
+		assert_eq!(response, ConnectIndexerResponse::from(record));
+	}
+
+	#[test]
+	fn invalid_hexadecimal_is_rejected() {
+		let result = serde_json::from_str::<Hex>("\"1234\"");
+		assert!(result.is_err());
+	}
+
+	#[rstest]
+	#[tokio::test]
+	async fn recorder_appends_responses_per_indexer(new_events: ConnectIndexerResponse) {
+		let directory = std::env::temp_dir().join(format!("recordings-{}", rand::random::<u64>()));
+		let recorder = Recorder::new(&directory);
+
+		recorder.record(&"ID".into(), &new_events).await;
+		recorder.record(&"ID".into(), &ConnectIndexerResponse { message: None }).await;
+		recorder.record(&"ID".into(), &new_events).await;
+
+		let responses = read(directory.join("ID.jsonl")).unwrap();
+		assert_eq!(vec![new_events.clone(), new_events], responses);
+
+		std::fs::remove_dir_all(directory).unwrap();
+	}
+}
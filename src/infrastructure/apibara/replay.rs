@@ -0,0 +1,206 @@
+use super::{
+	apibara::ConnectIndexerResponse,
+	event_stream::message,
+	events::{Abi, ContractAbis},
+	indexing_service::{handle_response, Channel},
+	record,
+};
+use crate::domain::*;
+use async_trait::async_trait;
+use futures::StreamExt;
+use std::{
+	path::{Path, PathBuf},
+	sync::Arc,
+};
+use tokio_util::sync::CancellationToken;
+
+/**
+ * Replays the responses recorded by a `Recorder`, as if they were streamed by Apibara.
+ * The indexing stops once all the recorded responses have been handled, or on shutdown.
+ */
+pub struct Replay {
+	path: PathBuf,
+	abis: ContractAbis,
+}
+
+impl Replay {
+	pub fn new<P: AsRef<Path>>(path: P) -> Self {
+		Self {
+			path: path.as_ref().to_path_buf(),
+			abis: ContractAbis::default(),
+		}
+	}
+
+	/**
+	 * Decode the events of the contract with the given address as described by the ABI, see
+	 * `Client::with_abi`
+	 */
+	pub fn with_abi(mut self, contract_address: ContractAddress, abi: Abi) -> Self {
+		self.abis.add(contract_address, abi);
+		self
+	}
+}
+
+#[async_trait]
+impl IndexingService for Replay {
+	async fn fetch_new_events(
+		&self,
+		_indexer: &Indexer,
+		observer: Arc<dyn BlockchainObserver>,
+		shutdown: CancellationToken,
+	) -> Result<(), IndexingServiceError> {
+		let responses = self.read()?;
+
+		let mut channel = Channel::new();
+		for response in responses {
+			if shutdown.is_cancelled() {
+				break;
+			}
+			handle_response(response, &channel.tx, &self.abis, &*observer).await?;
+			// Nobody is listening to the acks, drop them so that the channel never gets full
+			while channel.rx.try_recv().is_ok() {}
+		}
+
+		Ok(())
+	}
+}
+
+#[async_trait]
+impl StreamingService for Replay {
+	async fn event_stream(&self, _indexer: &Indexer) -> Result<EventStream, IndexingServiceError> {
+		let acknowledger: Arc<dyn BlockAcknowledger> = Arc::new(NoAcknowledger);
+		let messages: Vec<_> = self
+			.read()?
+			.into_iter()
+			.filter_map(|response| message(response, &self.abis, &acknowledger).transpose())
+			.collect();
+
+		Ok(futures::stream::iter(messages).boxed())
+	}
+}
+
+impl Replay {
+	fn read(&self) -> Result<Vec<ConnectIndexerResponse>, IndexingServiceError> {
+		record::read(&self.path).map_err(|error| {
+			IndexingServiceError::Receive(format!(
+				"unable to read the recording `{}`: {}",
+				self.path.display(),
+				error
+			))
+		})
+	}
+}
+
+/**
+ * Nobody is listening to the acks of a replay
+ */
+struct NoAcknowledger;
+
+#[async_trait]
+impl BlockAcknowledger for NoAcknowledger {
+	async fn ack(&self, _block_hash: &BlockHash) -> Result<(), IndexingServiceError> {
+		Ok(())
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use mockall::predicate::eq;
+
+	#[tokio::test]
+	async fn replays_the_recorded_responses() {
+		let mut observer = MockBlockchainObserver::new();
+		observer
+			.expect_on_connect()
+			.with(eq(IndexerId::from("indexer-goerli")))
+			.times(1)
+			.returning(|_| Ok(()));
+		observer.expect_on_new_block().times(2).returning(|_| Ok(()));
+		observer.expect_on_new_event().times(1).returning(|_, _| Ok(()));
+		observer.expect_on_block_processed().times(2).returning(|_| Ok(()));
+		observer.expect_on_reorg().times(1).returning(|_| Ok(()));
+
+		let replay = Replay::new("tests/fixtures/indexer-goerli.jsonl");
+		let indexer = Indexer::new(
+			"indexer-goerli".into(),
+			Network::Starknet(StarknetChain::Goerli),
+			0,
+			Vec::new(),
+		);
+
+		let result = replay
+			.fetch_new_events(&indexer, Arc::new(observer), CancellationToken::new())
+			.await;
+		assert!(result.is_ok(), "{}", result.err().unwrap());
+	}
+
+	#[tokio::test]
+	async fn stops_replaying_on_shutdown() {
+		let replay = Replay::new("tests/fixtures/indexer-goerli.jsonl");
+		let indexer = Indexer::new(
+			"indexer-goerli".into(),
+			Network::Starknet(StarknetChain::Goerli),
+			0,
+			Vec::new(),
+		);
+		let shutdown = CancellationToken::new();
+		shutdown.cancel();
+
+		let result = replay
+			.fetch_new_events(&indexer, Arc::new(MockBlockchainObserver::new()), shutdown)
+			.await;
+		assert!(result.is_ok(), "{}", result.err().unwrap());
+	}
+
+	#[tokio::test]
+	async fn streams_the_recorded_messages() {
+		let replay = Replay::new("tests/fixtures/indexer-goerli.jsonl");
+		let indexer = Indexer::new(
+			"indexer-goerli".into(),
+			Network::Starknet(StarknetChain::Goerli),
+			0,
+			Vec::new(),
+		);
+
+		let messages: Vec<_> = replay
+			.event_stream(&indexer)
+			.await
+			.unwrap()
+			.map(|message| message.unwrap())
+			.collect()
+			.await;
+
+		assert_eq!(6, messages.len());
+		match &messages[2] {
+			IndexerMessage::NewEvents {
+				block, events, ack, ..
+			} => {
+				assert_eq!(291345, block.number);
+				assert_eq!(1, events.len());
+				assert!(ack.is_some());
+			},
+			message => panic!("unexpected message: {:?}", message),
+		}
+	}
+
+	#[tokio::test]
+	async fn fails_when_the_recording_cannot_be_read() {
+		let replay = Replay::new("tests/fixtures/unknown.jsonl");
+		let indexer = Indexer::new(
+			"ID".into(),
+			Network::Starknet(StarknetChain::Devnet),
+			0,
+			Vec::new(),
+		);
+
+		let result = replay
+			.fetch_new_events(
+				&indexer,
+				Arc::new(MockBlockchainObserver::new()),
+				CancellationToken::new(),
+			)
+			.await;
+		assert!(matches!(result, Err(IndexingServiceError::Receive(_))));
+	}
+}
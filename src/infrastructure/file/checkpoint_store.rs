@@ -0,0 +1,150 @@
+use crate::domain::{self, *};
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::{
+	collections::HashMap,
+	path::{Path, PathBuf},
+	str::FromStr,
+};
+use tokio::sync::Mutex;
+
+#[derive(Debug, Serialize, Deserialize)]
+struct Checkpoint {
+	block_hash: String,
+	block_number: u64,
+}
+
+/**
+ * Stores the checkpoints of all indexers in a single JSON file
+ */
+pub struct CheckpointStore {
+	path: PathBuf,
+	lock: Mutex<()>,
+}
+
+impl CheckpointStore {
+	pub fn new<P: AsRef<Path>>(path: P) -> Self {
+		Self {
+			path: path.as_ref().to_path_buf(),
+			lock: Mutex::new(()),
+		}
+	}
+
+	async fn read(&self) -> Result<HashMap<String, Checkpoint>, String> {
+		match tokio::fs::read(&self.path).await {
+			Ok(content) => serde_json::from_slice(&content).map_err(|e| e.to_string()),
+			Err(error) if error.kind() == std::io::ErrorKind::NotFound => Ok(HashMap::new()),
+			Err(error) => Err(error.to_string()),
+		}
+	}
+
+	async fn write(&self, checkpoints: &HashMap<String, Checkpoint>) -> Result<(), String> {
+		let content = serde_json::to_vec_pretty(checkpoints).map_err(|e| e.to_string())?;
+		// Write to a temporary file first so that a crash cannot leave a truncated file behind
+		let temporary_path = self.path.with_extension("tmp");
+		tokio::fs::write(&temporary_path, content).await.map_err(|e| e.to_string())?;
+		tokio::fs::rename(&temporary_path, &self.path).await.map_err(|e| e.to_string())
+	}
+}
+
+#[async_trait]
+impl domain::CheckpointStore for CheckpointStore {
+	async fn load(
+		&self,
+		indexer_id: &IndexerId,
+	) -> Result<Option<BlockHeader>, CheckpointStoreError> {
+		let to_error = |details| CheckpointStoreError::Load {
+			id: indexer_id.clone(),
+			details,
+		};
+
+		let _guard = self.lock.lock().await;
+		match self.read().await.map_err(to_error)?.remove(&indexer_id.to_string()) {
+			Some(checkpoint) => Ok(Some(BlockHeader::new(
+				BlockHash::from_str(&checkpoint.block_hash).map_err(|e| to_error(e.to_string()))?,
+				checkpoint.block_number,
+			))),
+			None => Ok(None),
+		}
+	}
+
+	async fn save(
+		&self,
+		indexer_id: &IndexerId,
+		checkpoint: &BlockHeader,
+	) -> Result<(), CheckpointStoreError> {
+		let to_error = |details| CheckpointStoreError::Save {
+			id: indexer_id.clone(),
+			details,
+		};
+
+		let _guard = self.lock.lock().await;
+		let mut checkpoints = self.read().await.map_err(to_error)?;
+		checkpoints.insert(
+			indexer_id.to_string(),
+			Checkpoint {
+				block_hash: checkpoint.hash.to_string(),
+				block_number: checkpoint.number,
+			},
+		);
+		self.write(&checkpoints).await.map_err(to_error)
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use rstest::*;
+
+	#[fixture]
+	fn path() -> PathBuf {
+		std::env::temp_dir().join(format!("checkpoints-{}.json", rand::random::<u64>()))
+	}
+
+	#[rstest]
+	#[tokio::test]
+	async fn returns_nothing_when_the_file_does_not_exist(path: PathBuf) {
+		let store = CheckpointStore::new(path);
+		assert_eq!(
+			None,
+			domain::CheckpointStore::load(&store, &"ID".into()).await.unwrap()
+		);
+	}
+
+	#[rstest]
+	#[tokio::test]
+	async fn saves_and_loads_checkpoints(path: PathBuf) {
+		let store = CheckpointStore::new(&path);
+		let checkpoint = BlockHeader::new(BlockHash::from_str("0x1234").unwrap(), 42);
+		let other_checkpoint = BlockHeader::new(BlockHash::from_str("0x5678").unwrap(), 43);
+
+		domain::CheckpointStore::save(&store, &"ID".into(), &checkpoint).await.unwrap();
+		domain::CheckpointStore::save(&store, &"OTHER".into(), &other_checkpoint)
+			.await
+			.unwrap();
+
+		let store = CheckpointStore::new(&path);
+		assert_eq!(
+			Some(checkpoint),
+			domain::CheckpointStore::load(&store, &"ID".into()).await.unwrap()
+		);
+		assert_eq!(
+			Some(other_checkpoint),
+			domain::CheckpointStore::load(&store, &"OTHER".into()).await.unwrap()
+		);
+
+		std::fs::remove_file(path).unwrap();
+	}
+
+	#[rstest]
+	#[tokio::test]
+	async fn forwards_invalid_file_errors(path: PathBuf) {
+		std::fs::write(&path, "not json").unwrap();
+
+		let store = CheckpointStore::new(&path);
+		let result = domain::CheckpointStore::load(&store, &"ID".into()).await;
+		assert!(matches!(result, Err(CheckpointStoreError::Load { .. })));
+
+		std::fs::remove_file(path).unwrap();
+	}
+}
@@ -0,0 +1,369 @@
+use crate::domain::{self, *};
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::{
+	path::{Path, PathBuf},
+	str::FromStr,
+};
+use tokio::{io::AsyncWriteExt, sync::Mutex};
+
+#[derive(Debug, Serialize, Deserialize)]
+struct StoredDeadLetter {
+	indexer_id: String,
+	contract_address: String,
+	block_number: u64,
+	block_hash: String,
+	block_timestamp: u64,
+	log_index: u64,
+	topics: Vec<String>,
+	data: Vec<String>,
+	/** None if the event is unsupported */
+	invalid: Option<String>,
+}
+
+/**
+ * Appends the dead letters of all indexers to a single JSON lines file
+ */
+pub struct DeadLetterStore {
+	path: PathBuf,
+	lock: Mutex<()>,
+}
+
+impl DeadLetterStore {
+	pub fn new<P: AsRef<Path>>(path: P) -> Self {
+		Self {
+			path: path.as_ref().to_path_buf(),
+			lock: Mutex::new(()),
+		}
+	}
+
+	/**
+	 * The content of the file, empty if nothing was saved yet
+	 */
+	async fn content(&self) -> std::io::Result<String> {
+		match tokio::fs::read_to_string(&self.path).await {
+			Err(error) if error.kind() == std::io::ErrorKind::NotFound => Ok(String::new()),
+			result => result,
+		}
+	}
+}
+
+fn parse_all(values: Vec<String>) -> Result<Vec<HexaString>, ParseHexaStringError> {
+	values.iter().map(|value| HexaString::from_str(value)).collect()
+}
+
+impl StoredDeadLetter {
+	fn new(indexer_id: &IndexerId, dead_letter: &DeadLetter) -> Self {
+		let to_strings = |values: &[HexaString]| values.iter().map(ToString::to_string).collect();
+
+		Self {
+			indexer_id: indexer_id.to_string(),
+			contract_address: dead_letter.metadata.contract_address.to_string(),
+			block_number: dead_letter.metadata.block_number,
+			block_hash: dead_letter.metadata.block_hash.to_string(),
+			block_timestamp: dead_letter.metadata.block_timestamp,
+			log_index: dead_letter.metadata.log_index,
+			topics: to_strings(&dead_letter.event.topics),
+			data: to_strings(&dead_letter.event.data),
+			invalid: match &dead_letter.error {
+				DecodingError::Unsupported => None,
+				DecodingError::Invalid(details) => Some(details.clone()),
+			},
+		}
+	}
+
+	fn is_event(&self, indexer_id: &IndexerId, metadata: &EventMetadata) -> bool {
+		self.indexer_id == indexer_id.to_string()
+			&& self.block_hash == metadata.block_hash.to_string()
+			&& self.log_index == metadata.log_index
+	}
+
+	fn into_dead_letter(self) -> Result<DeadLetter, ParseHexaStringError> {
+		Ok(DeadLetter {
+			event: RawEvent {
+				topics: parse_all(self.topics)?,
+				data: parse_all(self.data)?,
+			},
+			metadata: EventMetadata {
+				block_number: self.block_number,
+				block_hash: BlockHash::from_str(&self.block_hash)?,
+				block_timestamp: self.block_timestamp,
+				contract_address: ContractAddress::from_str(&self.contract_address)?,
+				log_index: self.log_index,
+			},
+			error: match self.invalid {
+				None => DecodingError::Unsupported,
+				Some(details) => DecodingError::Invalid(details),
+			},
+		})
+	}
+}
+
+#[async_trait]
+impl domain::DeadLetterStore for DeadLetterStore {
+	async fn save(
+		&self,
+		indexer_id: &IndexerId,
+		dead_letter: &DeadLetter,
+	) -> Result<(), DeadLetterStoreError> {
+		let to_error = |details: String| DeadLetterStoreError::Save {
+			id: indexer_id.clone(),
+			details,
+		};
+
+		let mut line = serde_json::to_string(&StoredDeadLetter::new(indexer_id, dead_letter))
+			.map_err(|e| to_error(e.to_string()))?;
+		line.push('\n');
+
+		let _guard = self.lock.lock().await;
+		let content = self.content().await.map_err(|e| to_error(e.to_string()))?;
+		let already_saved = content.lines().any(|line| {
+			matches!(
+				serde_json::from_str::<StoredDeadLetter>(line),
+				Ok(stored) if stored.is_event(indexer_id, &dead_letter.metadata)
+			)
+		});
+		if already_saved {
+			return Ok(());
+		}
+
+		let mut file = tokio::fs::OpenOptions::new()
+			.create(true)
+			.append(true)
+			.open(&self.path)
+			.await
+			.map_err(|e| to_error(e.to_string()))?;
+		file.write_all(line.as_bytes()).await.map_err(|e| to_error(e.to_string()))?;
+		// A tokio file completes writes in the background, only a flush waits for them
+		file.flush().await.map_err(|e| to_error(e.to_string()))
+	}
+
+	async fn remove(
+		&self,
+		indexer_id: &IndexerId,
+		metadata: &EventMetadata,
+	) -> Result<(), DeadLetterStoreError> {
+		let to_error = |e: std::io::Error| DeadLetterStoreError::Remove {
+			id: indexer_id.clone(),
+			details: e.to_string(),
+		};
+
+		let _guard = self.lock.lock().await;
+		let content = self.content().await.map_err(to_error)?;
+		// Corrupt lines are kept as they are, to be investigated
+		let kept: String = content
+			.lines()
+			.filter(|line| {
+				!matches!(
+					serde_json::from_str::<StoredDeadLetter>(line),
+					Ok(stored) if stored.is_event(indexer_id, metadata)
+				)
+			})
+			.flat_map(|line| [line, "\n"])
+			.collect();
+		if kept.len() == content.len() {
+			return Ok(());
+		}
+		tokio::fs::write(&self.path, kept).await.map_err(to_error)
+	}
+
+	async fn list(&self, indexer_id: &IndexerId) -> Result<Vec<DeadLetter>, DeadLetterStoreError> {
+		let to_error = |details: String| DeadLetterStoreError::List {
+			id: indexer_id.clone(),
+			details,
+		};
+
+		let _guard = self.lock.lock().await;
+		let content = self.content().await.map_err(|e| to_error(e.to_string()))?;
+
+		// A corrupt line, like one partially written before a crash, must not hide the others
+		let skip = |index: usize, details: String| {
+			log::warn!(
+				"Skipping line {} of `{}`: {}",
+				index + 1,
+				self.path.display(),
+				details
+			)
+		};
+
+		let mut dead_letters = Vec::new();
+		for (index, line) in content.lines().enumerate() {
+			if line.trim().is_empty() {
+				continue;
+			}
+			let stored = match serde_json::from_str::<StoredDeadLetter>(line) {
+				Ok(stored) => stored,
+				Err(error) => {
+					skip(index, error.to_string());
+					continue;
+				},
+			};
+			if stored.indexer_id != indexer_id.to_string() {
+				continue;
+			}
+			match stored.into_dead_letter() {
+				Ok(dead_letter) => dead_letters.push(dead_letter),
+				Err(error) => skip(index, error.to_string()),
+			}
+		}
+		Ok(dead_letters)
+	}
+
+	async fn flush(&self) -> Result<(), DeadLetterStoreError> {
+		let to_error = |e: std::io::Error| DeadLetterStoreError::Flush(e.to_string());
+
+		let _guard = self.lock.lock().await;
+		match tokio::fs::File::open(&self.path).await {
+			Ok(file) => file.sync_all().await.map_err(to_error),
+			Err(error) if error.kind() == std::io::ErrorKind::NotFound => Ok(()),
+			Err(error) => Err(to_error(error)),
+		}
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use rstest::*;
+
+	#[fixture]
+	fn path() -> PathBuf {
+		std::env::temp_dir().join(format!("dead-letters-{}.jsonl", rand::random::<u64>()))
+	}
+
+	fn dead_letter(block_number: u64, error: DecodingError) -> DeadLetter {
+		DeadLetter {
+			event: RawEvent {
+				topics: vec![HexaString::from_str("0x01").unwrap()],
+				data: vec![
+					HexaString::from_str("0x02").unwrap(),
+					HexaString::from_str("0x03").unwrap(),
+				],
+			},
+			metadata: EventMetadata {
+				block_number,
+				block_hash: BlockHash::from(block_number.to_be_bytes().to_vec()),
+				block_timestamp: 1660000000,
+				contract_address: ContractAddress::from_str("0x05").unwrap(),
+				log_index: 6,
+			},
+			error,
+		}
+	}
+
+	#[rstest]
+	#[tokio::test]
+	async fn returns_nothing_when_the_file_does_not_exist(path: PathBuf) {
+		let store = DeadLetterStore::new(path);
+		assert!(domain::DeadLetterStore::list(&store, &"ID".into()).await.unwrap().is_empty());
+	}
+
+	#[rstest]
+	#[tokio::test]
+	async fn saves_and_lists_dead_letters_per_indexer(path: PathBuf) {
+		let store = DeadLetterStore::new(&path);
+		let unsupported = dead_letter(1, DecodingError::Unsupported);
+		let invalid = dead_letter(2, DecodingError::Invalid(String::from("missing field")));
+
+		domain::DeadLetterStore::save(&store, &"ID".into(), &unsupported).await.unwrap();
+		domain::DeadLetterStore::save(&store, &"OTHER".into(), &unsupported)
+			.await
+			.unwrap();
+		domain::DeadLetterStore::save(&store, &"ID".into(), &invalid).await.unwrap();
+
+		let store = DeadLetterStore::new(&path);
+		assert_eq!(
+			vec![unsupported.clone(), invalid],
+			domain::DeadLetterStore::list(&store, &"ID".into()).await.unwrap()
+		);
+		assert_eq!(
+			vec![unsupported],
+			domain::DeadLetterStore::list(&store, &"OTHER".into()).await.unwrap()
+		);
+
+		std::fs::remove_file(path).unwrap();
+	}
+
+	#[rstest]
+	#[tokio::test]
+	async fn flushes_whether_or_not_the_file_exists(path: PathBuf) {
+		let store = DeadLetterStore::new(&path);
+		domain::DeadLetterStore::flush(&store).await.unwrap();
+
+		domain::DeadLetterStore::save(
+			&store,
+			&"ID".into(),
+			&dead_letter(1, DecodingError::Unsupported),
+		)
+		.await
+		.unwrap();
+		domain::DeadLetterStore::flush(&store).await.unwrap();
+
+		std::fs::remove_file(path).unwrap();
+	}
+
+	#[rstest]
+	#[tokio::test]
+	async fn skips_corrupt_lines(path: PathBuf) {
+		let store = DeadLetterStore::new(&path);
+		let dead_letter = dead_letter(1, DecodingError::Unsupported);
+		domain::DeadLetterStore::save(&store, &"ID".into(), &dead_letter).await.unwrap();
+
+		let mut content = std::fs::read_to_string(&path).unwrap();
+		content.push_str("not json\n");
+		content.push_str(&content.replace("0x05", "not hexadecimal"));
+		content.push_str(r#"{"indexer_id":"ID","contract"#);
+		std::fs::write(&path, content).unwrap();
+
+		let result = domain::DeadLetterStore::list(&store, &"ID".into()).await;
+		assert_eq!(vec![dead_letter], result.unwrap());
+
+		std::fs::remove_file(path).unwrap();
+	}
+
+	#[rstest]
+	#[tokio::test]
+	async fn keeps_an_event_delivered_again_once(path: PathBuf) {
+		let store = DeadLetterStore::new(&path);
+		let dead_letter = dead_letter(1, DecodingError::Unsupported);
+
+		domain::DeadLetterStore::save(&store, &"ID".into(), &dead_letter).await.unwrap();
+		domain::DeadLetterStore::save(&store, &"ID".into(), &dead_letter).await.unwrap();
+		domain::DeadLetterStore::save(&store, &"OTHER".into(), &dead_letter)
+			.await
+			.unwrap();
+
+		assert_eq!(
+			vec![dead_letter],
+			domain::DeadLetterStore::list(&store, &"ID".into()).await.unwrap()
+		);
+
+		std::fs::remove_file(path).unwrap();
+	}
+
+	#[rstest]
+	#[tokio::test]
+	async fn removes_the_dead_letter_of_an_event(path: PathBuf) {
+		let store = DeadLetterStore::new(&path);
+		let first = dead_letter(1, DecodingError::Unsupported);
+		let second = dead_letter(2, DecodingError::Unsupported);
+
+		domain::DeadLetterStore::save(&store, &"ID".into(), &first).await.unwrap();
+		domain::DeadLetterStore::save(&store, &"OTHER".into(), &first).await.unwrap();
+		domain::DeadLetterStore::save(&store, &"ID".into(), &second).await.unwrap();
+		domain::DeadLetterStore::remove(&store, &"ID".into(), &first.metadata)
+			.await
+			.unwrap();
+
+		assert_eq!(
+			vec![second],
+			domain::DeadLetterStore::list(&store, &"ID".into()).await.unwrap()
+		);
+		assert_eq!(
+			vec![first],
+			domain::DeadLetterStore::list(&store, &"OTHER".into()).await.unwrap()
+		);
+
+		std::fs::remove_file(path).unwrap();
+	}
+}
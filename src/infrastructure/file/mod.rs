@@ -0,0 +1,5 @@
+mod checkpoint_store;
+pub use checkpoint_store::CheckpointStore;
+
+mod dead_letter_store;
+pub use dead_letter_store::DeadLetterStore;
@@ -0,0 +1,166 @@
+mod observer;
+pub use observer::Observer;
+
+use crate::domain::*;
+use std::{
+	collections::BTreeMap,
+	sync::{Arc, RwLock},
+	time::{Duration, Instant},
+};
+
+#[derive(Debug, Clone, Copy)]
+struct IndexerHealth {
+	/** Set by the indexing loop while the stream of the indexer is open */
+	streaming: bool,
+	/** Set by the observer once the server confirmed the connection */
+	connected: bool,
+	/** Last time a block or an event was received, registration time until then */
+	last_block_at: Instant,
+}
+
+/**
+ * Liveness and readiness of the indexers.
+ * An indexer is ready once its stream is open and connected, and alive as long as it receives
+ * blocks within the liveness window.
+ */
+#[derive(Debug)]
+pub struct Health {
+	liveness_window: Duration,
+	indexers: RwLock<BTreeMap<String, IndexerHealth>>,
+}
+
+impl Health {
+	pub fn new(liveness_window: Duration) -> Self {
+		Self {
+			liveness_window,
+			indexers: Default::default(),
+		}
+	}
+
+	/**
+	 * Register the indexer and create the observer tracking its connection and blocks
+	 */
+	pub fn observer(self: &Arc<Self>, indexer_id: &IndexerId) -> Observer {
+		self.indexers
+			.write()
+			.expect("health lock is poisoned")
+			.entry(indexer_id.to_string())
+			.or_insert(IndexerHealth {
+				streaming: false,
+				connected: false,
+				last_block_at: Instant::now(),
+			});
+
+		Observer::new(self.clone(), indexer_id.clone())
+	}
+
+	/**
+	 * The indexing stream of the indexer has been opened
+	 */
+	pub fn on_stream_opened(&self, indexer_id: &IndexerId) {
+		self.update(indexer_id, |health| health.streaming = true);
+	}
+
+	/**
+	 * The indexing stream of the indexer has been closed, whatever the reason
+	 */
+	pub fn on_stream_closed(&self, indexer_id: &IndexerId) {
+		self.update(indexer_id, |health| {
+			health.streaming = false;
+			health.connected = false;
+		});
+	}
+
+	/**
+	 * Indexers whose stream is not open and connected
+	 */
+	pub fn unready_indexers(&self) -> Vec<String> {
+		self.indexers
+			.read()
+			.expect("health lock is poisoned")
+			.iter()
+			.filter(|(_, health)| !(health.streaming && health.connected))
+			.map(|(id, _)| id.clone())
+			.collect()
+	}
+
+	/**
+	 * Indexers which did not receive any block within the liveness window
+	 */
+	pub fn stale_indexers(&self) -> Vec<String> {
+		self.stale_indexers_at(Instant::now())
+	}
+
+	fn stale_indexers_at(&self, now: Instant) -> Vec<String> {
+		self.indexers
+			.read()
+			.expect("health lock is poisoned")
+			.iter()
+			.filter(|(_, health)| {
+				now.saturating_duration_since(health.last_block_at) > self.liveness_window
+			})
+			.map(|(id, _)| id.clone())
+			.collect()
+	}
+
+	/**
+	 * Health of unregistered indexers is ignored
+	 */
+	fn update<F: FnOnce(&mut IndexerHealth)>(&self, indexer_id: &IndexerId, update: F) {
+		if let Some(health) = self
+			.indexers
+			.write()
+			.expect("health lock is poisoned")
+			.get_mut(&indexer_id.to_string())
+		{
+			update(health)
+		}
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+
+	fn health() -> Arc<Health> {
+		let health = Arc::new(Health::new(Duration::from_secs(60)));
+		health.observer(&"ID".into());
+		health
+	}
+
+	#[test]
+	fn indexers_are_ready_once_streaming_and_connected() {
+		let health = health();
+		assert_eq!(vec![String::from("ID")], health.unready_indexers());
+
+		health.on_stream_opened(&"ID".into());
+		assert_eq!(vec![String::from("ID")], health.unready_indexers());
+
+		health.update(&"ID".into(), |health| health.connected = true);
+		assert!(health.unready_indexers().is_empty());
+
+		health.on_stream_closed(&"ID".into());
+		assert_eq!(vec![String::from("ID")], health.unready_indexers());
+	}
+
+	#[test]
+	fn indexers_are_stale_without_blocks_within_the_liveness_window() {
+		let health = health();
+		let now = Instant::now();
+		health.update(&"ID".into(), |health| health.last_block_at = now);
+
+		assert!(health.stale_indexers_at(now + Duration::from_secs(60)).is_empty());
+		assert_eq!(
+			vec![String::from("ID")],
+			health.stale_indexers_at(now + Duration::from_secs(61))
+		);
+	}
+
+	#[test]
+	fn unregistered_indexers_are_ignored() {
+		let health = health();
+		health.on_stream_opened(&"OTHER".into());
+
+		assert_eq!(vec![String::from("ID")], health.unready_indexers());
+	}
+}
@@ -0,0 +1,114 @@
+use super::{Health, IndexerHealth};
+use crate::domain::*;
+use async_trait::async_trait;
+use std::{sync::Arc, time::Instant};
+
+/**
+ * Tracks the connection and the blocks of an indexer from its indexing stream
+ */
+pub struct Observer {
+	health: Arc<Health>,
+	indexer_id: IndexerId,
+}
+
+impl Observer {
+	pub(super) fn new(health: Arc<Health>, indexer_id: IndexerId) -> Self {
+		Self { health, indexer_id }
+	}
+
+	fn update<F: FnOnce(&mut IndexerHealth)>(&self, update: F) {
+		self.health.update(&self.indexer_id, update)
+	}
+
+	fn keep_alive(&self) {
+		self.update(|health| health.last_block_at = Instant::now());
+	}
+}
+
+#[async_trait]
+impl BlockchainObserver for Observer {
+	async fn on_connect(&self, _indexer_id: &IndexerId) -> Result<(), BlockchainObserverError> {
+		self.update(|health| health.connected = true);
+		Ok(())
+	}
+
+	async fn on_new_event(
+		&self,
+		_event: &Event,
+		_metadata: &EventMetadata,
+	) -> Result<(), BlockchainObserverError> {
+		self.keep_alive();
+		Ok(())
+	}
+
+	async fn on_new_block(&self, _block_hash: &BlockHash) -> Result<(), BlockchainObserverError> {
+		self.keep_alive();
+		Ok(())
+	}
+
+	async fn on_block_processed(
+		&self,
+		_block: &BlockHeader,
+	) -> Result<(), BlockchainObserverError> {
+		self.keep_alive();
+		Ok(())
+	}
+
+	async fn on_disconnect(&self, _indexer_id: &IndexerId) -> Result<(), BlockchainObserverError> {
+		self.update(|health| health.connected = false);
+		Ok(())
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use std::{str::FromStr, time::Duration};
+
+	#[tokio::test]
+	async fn tracks_the_connection() {
+		let health = Arc::new(Health::new(Duration::from_secs(60)));
+		let observer = health.observer(&"ID".into());
+		health.on_stream_opened(&"ID".into());
+
+		observer.on_connect(&"ID".into()).await.unwrap();
+		assert!(health.unready_indexers().is_empty());
+
+		observer.on_disconnect(&"ID".into()).await.unwrap();
+		assert_eq!(vec![String::from("ID")], health.unready_indexers());
+	}
+
+	#[tokio::test]
+	async fn new_blocks_keep_the_indexer_alive() {
+		let health = Arc::new(Health::new(Duration::from_secs(60)));
+		let observer = health.observer(&"ID".into());
+		let long_ago = Instant::now() - Duration::from_secs(120);
+		observer.update(|health| health.last_block_at = long_ago);
+		assert_eq!(vec![String::from("ID")], health.stale_indexers());
+
+		observer.on_new_block(&BlockHash::from_str("0x1234").unwrap()).await.unwrap();
+
+		assert!(health.stale_indexers().is_empty());
+	}
+
+	#[tokio::test]
+	async fn handled_events_and_blocks_keep_the_indexer_alive() {
+		let health = Arc::new(Health::new(Duration::from_secs(60)));
+		let observer = health.observer(&"ID".into());
+		let long_ago = Instant::now() - Duration::from_secs(120);
+
+		observer.update(|health| health.last_block_at = long_ago);
+		observer
+			.on_new_event(
+				&Event::GithubIdentifierRegistered(Default::default()),
+				&Default::default(),
+			)
+			.await
+			.unwrap();
+		assert!(health.stale_indexers().is_empty());
+
+		observer.update(|health| health.last_block_at = long_ago);
+		observer.on_block_processed(&Default::default()).await.unwrap();
+		assert!(health.stale_indexers().is_empty());
+	}
+}
@@ -0,0 +1,301 @@
+use super::{Health, Metrics};
+use crate::application::Supervisor;
+use hyper::{
+	header::CONTENT_TYPE,
+	service::{make_service_fn, service_fn},
+	Body, Method, Request, Response, StatusCode,
+};
+use std::{
+	collections::BTreeMap,
+	convert::Infallible,
+	net::{SocketAddr, TcpListener},
+	sync::Arc,
+};
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum Error {
+	#[error("unable to listen on `{address}`: {details}")]
+	Bind { address: String, details: String },
+	#[error("the HTTP server stopped: {0}")]
+	Serve(String),
+}
+
+/**
+ * Serves the metrics, the health and the status of the indexers over HTTP
+ */
+pub struct Server {
+	listener: TcpListener,
+	metrics: Option<Arc<Metrics>>,
+	health: Option<Arc<Health>>,
+	supervisor: Option<Arc<Supervisor>>,
+}
+
+impl Server {
+	pub fn bind(address: &str) -> Result<Self, Error> {
+		let bind_error = |error: std::io::Error| Error::Bind {
+			address: address.to_owned(),
+			details: error.to_string(),
+		};
+		let listener = TcpListener::bind(address).map_err(bind_error)?;
+		listener.set_nonblocking(true).map_err(bind_error)?;
+		Ok(Self {
+			listener,
+			metrics: None,
+			health: None,
+			supervisor: None,
+		})
+	}
+
+	pub fn local_addr(&self) -> Option<SocketAddr> {
+		self.listener.local_addr().ok()
+	}
+
+	/**
+	 * Serve the metrics on `/metrics`, in the Prometheus text format
+	 */
+	pub fn with_metrics(mut self, metrics: Arc<Metrics>) -> Self {
+		self.metrics = Some(metrics);
+		self
+	}
+
+	/**
+	 * Serve the liveness of the indexers on `/healthz` and their readiness on `/readyz`
+	 */
+	pub fn with_health(mut self, health: Arc<Health>) -> Self {
+		self.health = Some(health);
+		self
+	}
+
+	/**
+	 * Serve the status of the supervised indexers on `/status`, as JSON
+	 */
+	pub fn with_supervisor(mut self, supervisor: Arc<Supervisor>) -> Self {
+		self.supervisor = Some(supervisor);
+		self
+	}
+
+	pub async fn run(self) -> Result<(), Error> {
+		let (metrics, health, supervisor) = (self.metrics, self.health, self.supervisor);
+		let make_service = make_service_fn(move |_| {
+			let (metrics, health, supervisor) =
+				(metrics.clone(), health.clone(), supervisor.clone());
+			async move {
+				Ok::<_, Infallible>(service_fn(move |request| {
+					let response = respond(
+						&request,
+						metrics.as_deref(),
+						health.as_deref(),
+						supervisor.as_deref(),
+					);
+					async move { Ok::<_, Infallible>(response) }
+				}))
+			}
+		});
+
+		hyper::Server::from_tcp(self.listener)
+			.map_err(|error| Error::Serve(error.to_string()))?
+			.serve(make_service)
+			.await
+			.map_err(|error| Error::Serve(error.to_string()))
+	}
+}
+
+fn respond(
+	request: &Request<Body>,
+	metrics: Option<&Metrics>,
+	health: Option<&Health>,
+	supervisor: Option<&Supervisor>,
+) -> Response<Body> {
+	match (
+		request.method(),
+		request.uri().path(),
+		metrics,
+		health,
+		supervisor,
+	) {
+		(&Method::GET, "/metrics", Some(metrics), ..) => Response::builder()
+			.header(CONTENT_TYPE, "text/plain; version=0.0.4")
+			.body(Body::from(metrics.render()))
+			.expect("metrics response is valid"),
+		(&Method::GET, "/healthz", _, Some(health), _) => check(
+			health.stale_indexers(),
+			"no block received within the liveness window",
+		),
+		(&Method::GET, "/readyz", _, Some(health), _) =>
+			check(health.unready_indexers(), "not connected"),
+		(&Method::GET, "/status", _, _, Some(supervisor)) => status(supervisor),
+		_ => Response::builder()
+			.status(StatusCode::NOT_FOUND)
+			.body(Body::empty())
+			.expect("not found response is valid"),
+	}
+}
+
+fn status(supervisor: &Supervisor) -> Response<Body> {
+	let statuses: BTreeMap<_, _> = supervisor
+		.statuses()
+		.into_iter()
+		.map(|(indexer_id, status)| (indexer_id.to_string(), status))
+		.collect();
+	Response::builder()
+		.header(CONTENT_TYPE, "application/json")
+		.body(Body::from(
+			serde_json::to_string(&statuses).expect("statuses are serializable"),
+		))
+		.expect("status response is valid")
+}
+
+/**
+ * Succeed unless some indexers failed the check
+ */
+fn check(failing_indexers: Vec<String>, reason: &str) -> Response<Body> {
+	let (status, body) = match failing_indexers.as_slice() {
+		[] => (StatusCode::OK, String::from("ok\n")),
+		_ => (
+			StatusCode::SERVICE_UNAVAILABLE,
+			format!("{}: {}\n", reason, failing_indexers.join(", ")),
+		),
+	};
+	Response::builder()
+		.status(status)
+		.header(CONTENT_TYPE, "text/plain")
+		.body(Body::from(body))
+		.expect("health response is valid")
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use crate::domain::{
+		BlockchainObserver, Indexer, MockBlockchainObserver, MockIndexingService, Network,
+		StarknetChain,
+	};
+	use std::time::Duration;
+	use tokio::{
+		io::{AsyncReadExt, AsyncWriteExt},
+		net::TcpStream,
+	};
+
+	async fn get(address: SocketAddr, path: &str) -> String {
+		let mut stream = TcpStream::connect(address).await.unwrap();
+		stream
+			.write_all(
+				format!(
+					"GET {} HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\n\r\n",
+					path
+				)
+				.as_bytes(),
+			)
+			.await
+			.unwrap();
+		let mut response = String::new();
+		stream.read_to_string(&mut response).await.unwrap();
+		response
+	}
+
+	fn server() -> (SocketAddr, Arc<Health>) {
+		let metrics = Arc::new(Metrics::default());
+		metrics.observer(&"ID".into(), &Network::Starknet(StarknetChain::Goerli));
+		let health = Arc::new(Health::new(Duration::from_secs(60)));
+		health.observer(&"ID".into());
+
+		let server = Server::bind("127.0.0.1:0")
+			.unwrap()
+			.with_metrics(metrics)
+			.with_health(health.clone());
+		let address = server.local_addr().unwrap();
+		tokio::spawn(server.run());
+		(address, health)
+	}
+
+	#[tokio::test]
+	async fn serves_metrics() {
+		let (address, _) = server();
+
+		let response = get(address, "/metrics").await;
+
+		assert!(response.starts_with("HTTP/1.1 200 OK"));
+		assert!(response.contains("content-type: text/plain; version=0.0.4"));
+		assert!(response.contains(
+			r#"marketplace_indexer_blocks_total{indexer="ID",network="starknet-goerli"} 0"#
+		));
+	}
+
+	#[tokio::test]
+	async fn serves_readiness() {
+		let (address, health) = server();
+
+		let response = get(address, "/readyz").await;
+		assert!(response.starts_with("HTTP/1.1 503 Service Unavailable"));
+		assert!(response.ends_with("not connected: ID\n"));
+
+		health.on_stream_opened(&"ID".into());
+		health.observer(&"ID".into()).on_connect(&"ID".into()).await.unwrap();
+
+		let response = get(address, "/readyz").await;
+		assert!(response.starts_with("HTTP/1.1 200 OK"));
+	}
+
+	#[tokio::test]
+	async fn serves_liveness() {
+		let (address, _) = server();
+
+		let response = get(address, "/healthz").await;
+
+		assert!(response.starts_with("HTTP/1.1 200 OK"));
+		assert!(response.ends_with("ok\n"));
+	}
+
+	#[tokio::test]
+	async fn serves_the_status_of_the_supervised_indexers() {
+		let mut supervisor = Supervisor::new(Arc::new(MockIndexingService::new()));
+		supervisor.supervise(
+			Indexer::new(
+				"ID".into(),
+				Network::Starknet(StarknetChain::Goerli),
+				0,
+				Vec::new(),
+			),
+			Arc::new(MockBlockchainObserver::new()),
+		);
+		let server = Server::bind("127.0.0.1:0").unwrap().with_supervisor(Arc::new(supervisor));
+		let address = server.local_addr().unwrap();
+		tokio::spawn(server.run());
+
+		let response = get(address, "/status").await;
+
+		assert!(response.starts_with("HTTP/1.1 200 OK"));
+		assert!(response.contains("content-type: application/json"));
+		assert!(response.ends_with(
+			r#"{"ID":{"state":"starting","connected":false,"last_block":null,"processed_blocks":0,"error_count":0,"last_error":null,"undecodable_events":0}}"#
+		));
+	}
+
+	#[tokio::test]
+	async fn health_is_not_served_unless_configured() {
+		let server = Server::bind("127.0.0.1:0").unwrap();
+		let address = server.local_addr().unwrap();
+		tokio::spawn(server.run());
+
+		let response = get(address, "/healthz").await;
+
+		assert!(response.starts_with("HTTP/1.1 404 Not Found"));
+	}
+
+	#[tokio::test]
+	async fn unknown_paths_are_not_found() {
+		let (address, _) = server();
+
+		let response = get(address, "/unknown").await;
+
+		assert!(response.starts_with("HTTP/1.1 404 Not Found"));
+	}
+
+	#[test]
+	fn bind_errors_are_reported() {
+		let result = Server::bind("not an address");
+
+		assert!(matches!(result, Err(Error::Bind { .. })));
+	}
+}
@@ -0,0 +1,318 @@
+mod observer;
+pub use observer::Observer;
+
+use crate::domain::*;
+use std::{
+	collections::BTreeMap,
+	fmt::Write,
+	sync::{Arc, RwLock},
+	time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+/**
+ * Upper bounds of the ack latency histogram buckets, in seconds
+ */
+const ACK_LATENCY_BUCKETS: &[f64] = &[
+	0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0,
+];
+
+#[derive(Debug, Default, Clone)]
+struct IndexerMetrics {
+	network: String,
+	events: BTreeMap<String, u64>,
+	undecodable_events: u64,
+	blocks: u64,
+	last_block_number: Option<u64>,
+	last_block_timestamp: Option<u64>,
+	reorgs: u64,
+	reconnects: u64,
+	ack_latency_buckets: Vec<u64>,
+	ack_latency_sum: f64,
+	ack_latency_count: u64,
+}
+
+/**
+ * Health metrics of the indexers, labeled by indexer and network, and rendered in the
+ * Prometheus text format
+ */
+#[derive(Debug, Default)]
+pub struct Metrics {
+	indexers: RwLock<BTreeMap<String, IndexerMetrics>>,
+}
+
+impl Metrics {
+	/**
+	 * Register the indexer and create the observer feeding its metrics
+	 */
+	pub fn observer(self: &Arc<Self>, indexer_id: &IndexerId, network: &Network) -> Observer {
+		self.indexers
+			.write()
+			.expect("metrics lock is poisoned")
+			.entry(indexer_id.to_string())
+			.or_insert_with(|| IndexerMetrics {
+				network: network.to_string(),
+				ack_latency_buckets: vec![0; ACK_LATENCY_BUCKETS.len()],
+				..Default::default()
+			});
+
+		Observer::new(self.clone(), indexer_id.clone())
+	}
+
+	/**
+	 * Record the time between the reception of the events of a block and its ack
+	 */
+	pub fn observe_ack_latency(&self, indexer_id: &IndexerId, latency: Duration) {
+		let latency = latency.as_secs_f64();
+		self.update(indexer_id, |metrics| {
+			for (bucket, upper_bound) in ACK_LATENCY_BUCKETS.iter().enumerate() {
+				if latency <= *upper_bound {
+					metrics.ack_latency_buckets[bucket] += 1;
+				}
+			}
+			metrics.ack_latency_sum += latency;
+			metrics.ack_latency_count += 1;
+		});
+	}
+
+	/**
+	 * Metrics of unregistered indexers are ignored
+	 */
+	fn update<F: FnOnce(&mut IndexerMetrics)>(&self, indexer_id: &IndexerId, update: F) {
+		if let Some(metrics) = self
+			.indexers
+			.write()
+			.expect("metrics lock is poisoned")
+			.get_mut(&indexer_id.to_string())
+		{
+			update(metrics)
+		}
+	}
+
+	/**
+	 * All the metrics, in the Prometheus text exposition format
+	 */
+	pub fn render(&self) -> String {
+		let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default();
+		self.render_at(now.as_secs())
+	}
+
+	fn render_at(&self, now: u64) -> String {
+		let indexers = self.indexers.read().expect("metrics lock is poisoned");
+		let mut output = String::new();
+
+		let mut family = |name: &str, kind: &str, help: &str, samples: Vec<(String, String)>| {
+			let _ = writeln!(output, "# HELP marketplace_indexer_{} {}", name, help);
+			let _ = writeln!(output, "# TYPE marketplace_indexer_{} {}", name, kind);
+			for (labels, value) in samples {
+				let _ = writeln!(output, "marketplace_indexer_{}{} {}", name, labels, value);
+			}
+		};
+
+		let each = |value: &dyn Fn(&IndexerMetrics) -> Option<String>| {
+			indexers
+				.iter()
+				.filter_map(|(id, metrics)| Some((labels(id, metrics, &[]), value(metrics)?)))
+				.collect::<Vec<_>>()
+		};
+
+		family(
+			"events_total",
+			"counter",
+			"Events decoded, by type.",
+			indexers
+				.iter()
+				.flat_map(|(id, metrics)| {
+					metrics.events.iter().map(move |(event_type, count)| {
+						(
+							labels(id, metrics, &[("type", event_type)]),
+							count.to_string(),
+						)
+					})
+				})
+				.collect(),
+		);
+		family(
+			"undecodable_events_total",
+			"counter",
+			"Events which could not be decoded.",
+			each(&|metrics| Some(metrics.undecodable_events.to_string())),
+		);
+		family(
+			"blocks_total",
+			"counter",
+			"New blocks seen on the indexing stream.",
+			each(&|metrics| Some(metrics.blocks.to_string())),
+		);
+		family(
+			"last_block_number",
+			"gauge",
+			"Number of the last block processed.",
+			each(&|metrics| metrics.last_block_number.map(|number| number.to_string())),
+		);
+		family(
+			"last_block_timestamp_seconds",
+			"gauge",
+			"Timestamp of the last block processed.",
+			each(&|metrics| metrics.last_block_timestamp.map(|timestamp| timestamp.to_string())),
+		);
+		family(
+			"head_lag_seconds",
+			"gauge",
+			"Time elapsed since the timestamp of the last block processed.",
+			each(&|metrics| {
+				metrics
+					.last_block_timestamp
+					.map(|timestamp| now.saturating_sub(timestamp).to_string())
+			}),
+		);
+		family(
+			"reorgs_total",
+			"counter",
+			"Chain reorganizations.",
+			each(&|metrics| Some(metrics.reorgs.to_string())),
+		);
+		family(
+			"reconnects_total",
+			"counter",
+			"Reconnections to the indexing stream.",
+			each(&|metrics| Some(metrics.reconnects.to_string())),
+		);
+		family(
+			"ack_latency_seconds",
+			"histogram",
+			"Time between the reception of the events of a block and its ack.",
+			indexers
+				.iter()
+				.flat_map(|(id, metrics)| {
+					let buckets = ACK_LATENCY_BUCKETS
+						.iter()
+						.map(|upper_bound| upper_bound.to_string())
+						.chain(std::iter::once(String::from("+Inf")))
+						.zip(
+							metrics
+								.ack_latency_buckets
+								.iter()
+								.chain(std::iter::once(&metrics.ack_latency_count)),
+						)
+						.map(|(upper_bound, count)| {
+							(
+								format!("_bucket{}", labels(id, metrics, &[("le", &upper_bound)])),
+								count.to_string(),
+							)
+						})
+						.collect::<Vec<_>>();
+
+					buckets.into_iter().chain([
+						(
+							format!("_sum{}", labels(id, metrics, &[])),
+							metrics.ack_latency_sum.to_string(),
+						),
+						(
+							format!("_count{}", labels(id, metrics, &[])),
+							metrics.ack_latency_count.to_string(),
+						),
+					])
+				})
+				.collect(),
+		);
+
+		output
+	}
+}
+
+fn labels(indexer_id: &str, metrics: &IndexerMetrics, extra: &[(&str, &str)]) -> String {
+	let escape =
+		|value: &str| value.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n");
+
+	let labels = [
+		("indexer", indexer_id),
+		("network", metrics.network.as_str()),
+	]
+	.iter()
+	.chain(extra)
+	.map(|(name, value)| format!("{}=\"{}\"", name, escape(value)))
+	.collect::<Vec<_>>()
+	.join(",");
+	format!("{{{}}}", labels)
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use std::str::FromStr;
+
+	fn metrics() -> Arc<Metrics> {
+		let metrics = Arc::new(Metrics::default());
+		metrics.observer(&"ID".into(), &Network::Starknet(StarknetChain::Goerli));
+		metrics
+	}
+
+	fn sample(output: &str, name: &str) -> Option<String> {
+		output
+			.lines()
+			.find(|line| line.starts_with(&format!("marketplace_indexer_{}", name)))
+			.and_then(|line| line.rsplit(' ').next())
+			.map(ToOwned::to_owned)
+	}
+
+	#[test]
+	fn registered_indexers_start_at_zero() {
+		let output = metrics().render_at(0);
+
+		assert_eq!(
+			Some(String::from("0")),
+			sample(
+				&output,
+				r#"blocks_total{indexer="ID",network="starknet-goerli"}"#
+			)
+		);
+		assert_eq!(None, sample(&output, "last_block_number{"));
+		assert!(output.contains("# TYPE marketplace_indexer_reorgs_total counter\n"));
+	}
+
+	#[test]
+	fn ack_latencies_are_bucketed() {
+		let metrics = metrics();
+		metrics.observe_ack_latency(&"ID".into(), Duration::from_millis(20));
+		metrics.observe_ack_latency(&"ID".into(), Duration::from_secs(20));
+
+		let output = metrics.render_at(0);
+
+		let bucket = |le: &str| {
+			sample(
+				&output,
+				&format!(
+					r#"ack_latency_seconds_bucket{{indexer="ID",network="starknet-goerli",le="{}"}}"#,
+					le
+				),
+			)
+		};
+		assert_eq!(Some(String::from("0")), bucket("0.01"));
+		assert_eq!(Some(String::from("1")), bucket("0.025"));
+		assert_eq!(Some(String::from("1")), bucket("10"));
+		assert_eq!(Some(String::from("2")), bucket("+Inf"));
+		assert_eq!(
+			Some(String::from("2")),
+			sample(&output, "ack_latency_seconds_count{")
+		);
+	}
+
+	#[test]
+	fn unregistered_indexers_are_ignored() {
+		let metrics = metrics();
+		metrics.observe_ack_latency(&"OTHER".into(), Duration::from_secs(1));
+
+		assert!(!metrics.render_at(0).contains("OTHER"));
+	}
+
+	#[test]
+	fn label_values_are_escaped() {
+		let metrics = Arc::new(Metrics::default());
+		metrics.observer(
+			&"ID".into(),
+			&Network::from_str("starknet-\"quoted\"").unwrap(),
+		);
+
+		assert!(metrics.render_at(0).contains(r#"network="starknet-\"quoted\"""#));
+	}
+}
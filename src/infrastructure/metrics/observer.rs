@@ -0,0 +1,200 @@
+use super::{IndexerMetrics, Metrics};
+use crate::domain::*;
+use async_trait::async_trait;
+use std::sync::Arc;
+
+/**
+ * Feeds the metrics of an indexer from its indexing stream
+ */
+pub struct Observer {
+	metrics: Arc<Metrics>,
+	indexer_id: IndexerId,
+}
+
+impl Observer {
+	pub(super) fn new(metrics: Arc<Metrics>, indexer_id: IndexerId) -> Self {
+		Self {
+			metrics,
+			indexer_id,
+		}
+	}
+
+	fn update<F: FnOnce(&mut IndexerMetrics)>(&self, update: F) {
+		self.metrics.update(&self.indexer_id, update)
+	}
+}
+
+#[async_trait]
+impl BlockchainObserver for Observer {
+	async fn on_new_event(
+		&self,
+		event: &Event,
+		_metadata: &EventMetadata,
+	) -> Result<(), BlockchainObserverError> {
+		self.update(|metrics| *metrics.events.entry(event.name().to_owned()).or_default() += 1);
+		Ok(())
+	}
+
+	async fn on_undecodable_event(
+		&self,
+		_event: &RawEvent,
+		_metadata: &EventMetadata,
+		_error: &DecodingError,
+	) -> Result<(), BlockchainObserverError> {
+		self.update(|metrics| metrics.undecodable_events += 1);
+		Ok(())
+	}
+
+	async fn on_new_block(&self, _block_hash: &BlockHash) -> Result<(), BlockchainObserverError> {
+		self.update(|metrics| metrics.blocks += 1);
+		Ok(())
+	}
+
+	async fn on_reorg(&self, new_head: &BlockHeader) -> Result<(), BlockchainObserverError> {
+		self.update(|metrics| {
+			metrics.reorgs += 1;
+			metrics.last_block_number = Some(new_head.number);
+		});
+		Ok(())
+	}
+
+	async fn on_block_processed(&self, block: &BlockHeader) -> Result<(), BlockchainObserverError> {
+		self.update(|metrics| {
+			metrics.last_block_number = Some(block.number);
+			if block.timestamp.is_some() {
+				metrics.last_block_timestamp = block.timestamp;
+			}
+		});
+		Ok(())
+	}
+
+	async fn on_reconnect(&self, _indexer_id: &IndexerId) -> Result<(), BlockchainObserverError> {
+		self.update(|metrics| metrics.reconnects += 1);
+		Ok(())
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use rstest::*;
+	use std::str::FromStr;
+
+	#[fixture]
+	fn metrics() -> Arc<Metrics> {
+		Arc::new(Metrics::default())
+	}
+
+	fn metadata() -> EventMetadata {
+		EventMetadata {
+			block_number: 42,
+			..Default::default()
+		}
+	}
+
+	fn sample(metrics: &Metrics, name: &str) -> Option<String> {
+		let prefix = format!("marketplace_indexer_{}", name);
+		metrics
+			.render_at(1660000100)
+			.lines()
+			.find(|line| line.starts_with(&prefix))
+			.and_then(|line| line.rsplit(' ').next())
+			.map(ToOwned::to_owned)
+	}
+
+	#[rstest]
+	#[tokio::test]
+	async fn counts_events_per_type(metrics: Arc<Metrics>) {
+		let observer = metrics.observer(&"ID".into(), &Network::Ethereum(EthereumChain::Mainnet));
+		let decoded = Event::Decoded(DecodedEvent {
+			name: String::from("Transfer"),
+			fields: Vec::new(),
+		});
+
+		observer.on_new_event(&decoded, &metadata()).await.unwrap();
+		observer.on_new_event(&decoded, &metadata()).await.unwrap();
+		observer
+			.on_new_event(
+				&Event::GithubIdentifierRegistered(Default::default()),
+				&metadata(),
+			)
+			.await
+			.unwrap();
+		observer
+			.on_undecodable_event(
+				&Default::default(),
+				&metadata(),
+				&DecodingError::Unsupported,
+			)
+			.await
+			.unwrap();
+
+		assert_eq!(
+			Some(String::from("2")),
+			sample(
+				&metrics,
+				r#"events_total{indexer="ID",network="ethereum-mainnet",type="Transfer"}"#
+			)
+		);
+		assert_eq!(
+			Some(String::from("1")),
+			sample(
+				&metrics,
+				r#"events_total{indexer="ID",network="ethereum-mainnet",type="GithubIdentifierRegistered"}"#
+			)
+		);
+		assert_eq!(
+			Some(String::from("1")),
+			sample(&metrics, "undecodable_events_total{")
+		);
+	}
+
+	#[rstest]
+	#[tokio::test]
+	async fn tracks_the_last_block_and_the_head_lag(metrics: Arc<Metrics>) {
+		let observer = metrics.observer(&"ID".into(), &Network::Starknet(StarknetChain::Goerli));
+		let block_hash = BlockHash::from_str("0x1234").unwrap();
+
+		observer.on_new_block(&block_hash).await.unwrap();
+		observer
+			.on_block_processed(
+				&BlockHeader::new(block_hash.clone(), 42).with_timestamp(1660000000),
+			)
+			.await
+			.unwrap();
+
+		assert_eq!(Some(String::from("1")), sample(&metrics, "blocks_total{"));
+		assert_eq!(
+			Some(String::from("42")),
+			sample(&metrics, "last_block_number{")
+		);
+		assert_eq!(
+			Some(String::from("1660000000")),
+			sample(&metrics, "last_block_timestamp_seconds{")
+		);
+		assert_eq!(
+			Some(String::from("100")),
+			sample(&metrics, "head_lag_seconds{")
+		);
+
+		observer.on_reorg(&BlockHeader::new(block_hash, 40)).await.unwrap();
+		assert_eq!(
+			Some(String::from("40")),
+			sample(&metrics, "last_block_number{")
+		);
+		assert_eq!(Some(String::from("1")), sample(&metrics, "reorgs_total{"));
+	}
+
+	#[rstest]
+	#[tokio::test]
+	async fn counts_reconnects(metrics: Arc<Metrics>) {
+		let observer = metrics.observer(&"ID".into(), &Network::Starknet(StarknetChain::Goerli));
+
+		observer.on_reconnect(&"ID".into()).await.unwrap();
+
+		assert_eq!(
+			Some(String::from("1")),
+			sample(&metrics, "reconnects_total{")
+		);
+	}
+}
@@ -1,2 +1,25 @@
 mod apibara;
-pub use apibara::Client as ApibaraClient;
+pub(crate) use apibara::codegen as starknet_events;
+pub use apibara::{
+	Abi as ContractAbi, AbiError as ContractAbiError, Client as ApibaraClient,
+	DeadLetterDecoder as ApibaraDeadLetterDecoder, ReconnectPolicy, Recorder as ApibaraRecorder,
+	Replay as ApibaraReplay,
+};
+
+mod postgres;
+pub use postgres::{Client as PostgresClient, Error as PostgresError, GithubIdentifierProjection};
+
+mod file;
+pub use file::{CheckpointStore as FileCheckpointStore, DeadLetterStore as FileDeadLetterStore};
+
+mod sqlite;
+pub use sqlite::{Client as SqliteClient, Error as SqliteError};
+
+mod metrics;
+pub use metrics::{Metrics, Observer as MetricsObserver};
+
+mod health;
+pub use health::{Health, Observer as HealthObserver};
+
+mod http;
+pub use http::{Error as HttpError, Server as HttpServer};
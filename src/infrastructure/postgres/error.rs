@@ -0,0 +1,18 @@
+use crate::domain::BlockchainObserverError;
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum Error {
+	#[error("DATABASE_URL must be set to connect to Postgres database")]
+	DatabaseUrl(#[from] std::env::VarError),
+	#[error("Unable to connect to Postgres database")]
+	Connection(#[from] sqlx::Error),
+	#[error("Unable to migrate Postgres database")]
+	Migration(#[from] sqlx::migrate::MigrateError),
+}
+
+impl From<sqlx::Error> for BlockchainObserverError {
+	fn from(error: sqlx::Error) -> Self {
+		Self::Infrastructure(Box::new(error))
+	}
+}
@@ -0,0 +1,133 @@
+use super::Client;
+use crate::domain::*;
+use async_trait::async_trait;
+use std::sync::Arc;
+
+/**
+ * Projects GitHub identifier (un)registrations into the `github_identifiers` table.
+ * Writes are idempotent upserts, done in the same transaction as the indexer checkpoint, which
+ * never moves backwards when older events are projected again.
+ * On chain reorg, the rows of the blocks above the new head are deleted.
+ */
+pub struct GithubIdentifierProjection {
+	client: Arc<Client>,
+	indexer_id: IndexerId,
+}
+
+impl GithubIdentifierProjection {
+	pub fn new(client: Arc<Client>, indexer_id: IndexerId) -> Self {
+		Self { client, indexer_id }
+	}
+
+	async fn upsert(
+		&self,
+		profile_contract: &ContractAddress,
+		contributor_id: &ContributorId,
+		identifier: u128,
+		registered: bool,
+		metadata: &EventMetadata,
+	) -> Result<(), sqlx::Error> {
+		let mut transaction = self.client.0.begin().await?;
+
+		sqlx::query(
+			"INSERT INTO github_identifiers
+			(profile_contract, contributor_id, block_number, block_hash, log_index, identifier, registered, indexer_id)
+			VALUES ($1, $2, $3, $4, $5, $6::NUMERIC, $7, $8)
+			ON CONFLICT (indexer_id, profile_contract, contributor_id, block_number, log_index)
+			DO UPDATE SET
+			block_hash = EXCLUDED.block_hash,
+			identifier = EXCLUDED.identifier,
+			registered = EXCLUDED.registered",
+		)
+		.bind(profile_contract.to_string())
+		.bind(format!("0x{contributor_id:x}"))
+		.bind(metadata.block_number as i64)
+		.bind(metadata.block_hash.to_string())
+		.bind(metadata.log_index as i64)
+		.bind(identifier.to_string())
+		.bind(registered)
+		.bind(self.indexer_id.to_string())
+		.execute(&mut transaction)
+		.await?;
+
+		sqlx::query(
+			"INSERT INTO indexer_checkpoints (indexer_id, block_number, block_hash)
+			VALUES ($1, $2, $3)
+			ON CONFLICT (indexer_id) DO UPDATE SET
+			block_number = EXCLUDED.block_number,
+			block_hash = EXCLUDED.block_hash,
+			updated_at = NOW()
+			WHERE indexer_checkpoints.block_number <= EXCLUDED.block_number",
+		)
+		.bind(self.indexer_id.to_string())
+		.bind(metadata.block_number as i64)
+		.bind(metadata.block_hash.to_string())
+		.execute(&mut transaction)
+		.await?;
+
+		transaction.commit().await
+	}
+
+	async fn rollback(&self, new_head: &BlockHeader) -> Result<(), sqlx::Error> {
+		let mut transaction = self.client.0.begin().await?;
+
+		sqlx::query("DELETE FROM github_identifiers WHERE indexer_id = $1 AND block_number > $2")
+			.bind(self.indexer_id.to_string())
+			.bind(new_head.number as i64)
+			.execute(&mut transaction)
+			.await?;
+
+		sqlx::query(
+			"UPDATE indexer_checkpoints SET
+			block_number = $2,
+			block_hash = $3,
+			updated_at = NOW()
+			WHERE indexer_id = $1 AND block_number > $2",
+		)
+		.bind(self.indexer_id.to_string())
+		.bind(new_head.number as i64)
+		.bind(new_head.hash.to_string())
+		.execute(&mut transaction)
+		.await?;
+
+		transaction.commit().await
+	}
+}
+
+#[async_trait]
+impl BlockchainObserver for GithubIdentifierProjection {
+	async fn on_new_event(
+		&self,
+		event: &Event,
+		metadata: &EventMetadata,
+	) -> Result<(), BlockchainObserverError> {
+		match event {
+			Event::GithubIdentifierRegistered(event) =>
+				self.upsert(
+					&event.profile_contract,
+					&event.contributor_id,
+					event.identifier,
+					true,
+					metadata,
+				)
+				.await?,
+			Event::GithubIdentifierUnregistered(event) =>
+				self.upsert(
+					&event.profile_contract,
+					&event.contributor_id,
+					event.identifier,
+					false,
+					metadata,
+				)
+				.await?,
+			Event::Decoded(_) => (),
+		}
+
+		Ok(())
+	}
+
+	async fn on_reorg(&self, new_head: &BlockHeader) -> Result<(), BlockchainObserverError> {
+		self.rollback(new_head).await?;
+		Ok(())
+	}
+}
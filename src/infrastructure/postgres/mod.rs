@@ -0,0 +1,31 @@
+mod error;
+pub use error::Error;
+
+mod github_identifier_projection;
+pub use github_identifier_projection::GithubIdentifierProjection;
+
+use sqlx::{postgres::PgPoolOptions, PgPool};
+
+pub struct Client(PgPool);
+
+impl Client {
+	pub fn new(pool: PgPool) -> Self {
+		Self(pool)
+	}
+
+	/**
+	 * Connect to the database and apply the pending migrations
+	 */
+	pub async fn connect(database_url: &str) -> Result<Self, Error> {
+		let pool = PgPoolOptions::new().connect(database_url).await?;
+		sqlx::migrate!().run(&pool).await?;
+		Ok(Self::new(pool))
+	}
+
+	/**
+	 * Connect to the database at `DATABASE_URL`
+	 */
+	pub async fn default() -> Result<Self, Error> {
+		Self::connect(&std::env::var("DATABASE_URL")?).await
+	}
+}
@@ -0,0 +1,114 @@
+use super::Client;
+use crate::domain::*;
+use async_trait::async_trait;
+use sqlx::{Row, SqlitePool};
+use std::str::FromStr;
+
+pub(super) async fn create_table(pool: &SqlitePool) -> Result<(), sqlx::Error> {
+	sqlx::query(
+		"CREATE TABLE IF NOT EXISTS checkpoints (
+			indexer_id TEXT PRIMARY KEY,
+			block_hash TEXT NOT NULL,
+			block_number INTEGER NOT NULL
+		)",
+	)
+	.execute(pool)
+	.await?;
+	Ok(())
+}
+
+#[async_trait]
+impl CheckpointStore for Client {
+	async fn load(
+		&self,
+		indexer_id: &IndexerId,
+	) -> Result<Option<BlockHeader>, CheckpointStoreError> {
+		let to_error = |details| CheckpointStoreError::Load {
+			id: indexer_id.clone(),
+			details,
+		};
+
+		let row = sqlx::query("SELECT block_hash, block_number FROM checkpoints WHERE indexer_id = ?")
+			.bind(indexer_id.to_string())
+			.fetch_optional(&self.0)
+			.await
+			.map_err(|e| to_error(e.to_string()))?;
+
+		match row {
+			Some(row) => {
+				let block_hash: String = row.get(0);
+				let block_number: i64 = row.get(1);
+				Ok(Some(BlockHeader::new(
+					BlockHash::from_str(&block_hash).map_err(|e| to_error(e.to_string()))?,
+					block_number as u64,
+				)))
+			},
+			None => Ok(None),
+		}
+	}
+
+	async fn save(
+		&self,
+		indexer_id: &IndexerId,
+		checkpoint: &BlockHeader,
+	) -> Result<(), CheckpointStoreError> {
+		sqlx::query(
+			"INSERT INTO checkpoints (indexer_id, block_hash, block_number) VALUES (?, ?, ?)
+			ON CONFLICT (indexer_id) DO UPDATE SET
+			block_hash = excluded.block_hash,
+			block_number = excluded.block_number",
+		)
+		.bind(indexer_id.to_string())
+		.bind(checkpoint.hash.to_string())
+		.bind(checkpoint.number as i64)
+		.execute(&self.0)
+		.await
+		.map_err(|e| CheckpointStoreError::Save {
+			id: indexer_id.clone(),
+			details: e.to_string(),
+		})?;
+
+		Ok(())
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+
+	async fn client() -> Client {
+		Client::connect("sqlite::memory:").await.unwrap()
+	}
+
+	#[tokio::test]
+	async fn returns_nothing_when_there_is_no_checkpoint() {
+		assert_eq!(None, client().await.load(&"ID".into()).await.unwrap());
+	}
+
+	#[tokio::test]
+	async fn saves_and_loads_checkpoints() {
+		let client = client().await;
+		let checkpoint = BlockHeader::new(BlockHash::from_str("0x1234").unwrap(), 42);
+
+		CheckpointStore::save(&client, &"ID".into(), &checkpoint).await.unwrap();
+		assert_eq!(Some(checkpoint), client.load(&"ID".into()).await.unwrap());
+		assert_eq!(None, client.load(&"OTHER".into()).await.unwrap());
+	}
+
+	#[tokio::test]
+	async fn overwrites_the_previous_checkpoint() {
+		let client = client().await;
+		let checkpoint = BlockHeader::new(BlockHash::from_str("0x5678").unwrap(), 43);
+
+		CheckpointStore::save(
+			&client,
+			&"ID".into(),
+			&BlockHeader::new(BlockHash::from_str("0x1234").unwrap(), 42),
+		)
+		.await
+		.unwrap();
+		CheckpointStore::save(&client, &"ID".into(), &checkpoint).await.unwrap();
+
+		assert_eq!(Some(checkpoint), client.load(&"ID".into()).await.unwrap());
+	}
+}
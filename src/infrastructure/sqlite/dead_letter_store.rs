@@ -0,0 +1,240 @@
+use super::Client;
+use crate::domain::*;
+use async_trait::async_trait;
+use sqlx::{sqlite::SqliteRow, Row, SqlitePool};
+use std::str::FromStr;
+
+pub(super) async fn create_table(pool: &SqlitePool) -> Result<(), sqlx::Error> {
+	sqlx::query(
+		"CREATE TABLE IF NOT EXISTS dead_letters (
+			id INTEGER PRIMARY KEY AUTOINCREMENT,
+			indexer_id TEXT NOT NULL,
+			contract_address TEXT NOT NULL,
+			block_number INTEGER NOT NULL,
+			block_hash TEXT NOT NULL,
+			block_timestamp INTEGER NOT NULL,
+			log_index INTEGER NOT NULL,
+			topics TEXT NOT NULL,
+			data TEXT NOT NULL,
+			invalid TEXT
+		)",
+	)
+	.execute(pool)
+	.await?;
+	// Tables created before events were identified may keep an event several times
+	sqlx::query(
+		"DELETE FROM dead_letters WHERE id NOT IN (
+			SELECT MIN(id) FROM dead_letters GROUP BY indexer_id, block_hash, log_index
+		)",
+	)
+	.execute(pool)
+	.await?;
+	sqlx::query(
+		"CREATE UNIQUE INDEX IF NOT EXISTS dead_letters_event
+		ON dead_letters (indexer_id, block_hash, log_index)",
+	)
+	.execute(pool)
+	.await?;
+	Ok(())
+}
+
+fn to_json(values: &[HexaString]) -> Result<String, serde_json::Error> {
+	serde_json::to_string(&values.iter().map(ToString::to_string).collect::<Vec<_>>())
+}
+
+fn from_json(values: &str) -> Result<Vec<HexaString>, String> {
+	serde_json::from_str::<Vec<String>>(values)
+		.map_err(|e| e.to_string())?
+		.iter()
+		.map(|value| HexaString::from_str(value).map_err(|e| e.to_string()))
+		.collect()
+}
+
+fn dead_letter(row: SqliteRow) -> Result<DeadLetter, String> {
+	let parse = |column: &str| {
+		HexaString::from_str(&row.get::<String, _>(column)).map_err(|e| e.to_string())
+	};
+
+	Ok(DeadLetter {
+		event: RawEvent {
+			topics: from_json(&row.get::<String, _>("topics"))?,
+			data: from_json(&row.get::<String, _>("data"))?,
+		},
+		metadata: EventMetadata {
+			block_number: row.get::<i64, _>("block_number") as u64,
+			block_hash: parse("block_hash")?,
+			block_timestamp: row.get::<i64, _>("block_timestamp") as u64,
+			contract_address: parse("contract_address")?,
+			log_index: row.get::<i64, _>("log_index") as u64,
+		},
+		error: match row.get::<Option<String>, _>("invalid") {
+			None => DecodingError::Unsupported,
+			Some(details) => DecodingError::Invalid(details),
+		},
+	})
+}
+
+#[async_trait]
+impl DeadLetterStore for Client {
+	async fn save(
+		&self,
+		indexer_id: &IndexerId,
+		dead_letter: &DeadLetter,
+	) -> Result<(), DeadLetterStoreError> {
+		let to_error = |details: String| DeadLetterStoreError::Save {
+			id: indexer_id.clone(),
+			details,
+		};
+
+		let metadata = &dead_letter.metadata;
+		sqlx::query(
+			"INSERT INTO dead_letters (indexer_id, contract_address, block_number, block_hash,
+			block_timestamp, log_index, topics, data, invalid) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)
+			ON CONFLICT (indexer_id, block_hash, log_index) DO UPDATE SET invalid = excluded.invalid",
+		)
+		.bind(indexer_id.to_string())
+		.bind(metadata.contract_address.to_string())
+		.bind(metadata.block_number as i64)
+		.bind(metadata.block_hash.to_string())
+		.bind(metadata.block_timestamp as i64)
+		.bind(metadata.log_index as i64)
+		.bind(to_json(&dead_letter.event.topics).map_err(|e| to_error(e.to_string()))?)
+		.bind(to_json(&dead_letter.event.data).map_err(|e| to_error(e.to_string()))?)
+		.bind(match &dead_letter.error {
+			DecodingError::Unsupported => None,
+			DecodingError::Invalid(details) => Some(details.clone()),
+		})
+		.execute(&self.0)
+		.await
+		.map_err(|e| to_error(e.to_string()))?;
+
+		Ok(())
+	}
+
+	async fn remove(
+		&self,
+		indexer_id: &IndexerId,
+		metadata: &EventMetadata,
+	) -> Result<(), DeadLetterStoreError> {
+		sqlx::query(
+			"DELETE FROM dead_letters WHERE indexer_id = ? AND block_hash = ? AND log_index = ?",
+		)
+		.bind(indexer_id.to_string())
+		.bind(metadata.block_hash.to_string())
+		.bind(metadata.log_index as i64)
+		.execute(&self.0)
+		.await
+		.map_err(|e| DeadLetterStoreError::Remove {
+			id: indexer_id.clone(),
+			details: e.to_string(),
+		})?;
+
+		Ok(())
+	}
+
+	async fn list(&self, indexer_id: &IndexerId) -> Result<Vec<DeadLetter>, DeadLetterStoreError> {
+		let to_error = |details| DeadLetterStoreError::List {
+			id: indexer_id.clone(),
+			details,
+		};
+
+		sqlx::query("SELECT * FROM dead_letters WHERE indexer_id = ? ORDER BY id")
+			.bind(indexer_id.to_string())
+			.fetch_all(&self.0)
+			.await
+			.map_err(|e| to_error(e.to_string()))?
+			.into_iter()
+			.map(|row| dead_letter(row).map_err(to_error))
+			.collect()
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+
+	async fn client() -> Client {
+		Client::connect("sqlite::memory:").await.unwrap()
+	}
+
+	fn dead_letter(block_number: u64, error: DecodingError) -> DeadLetter {
+		DeadLetter {
+			event: RawEvent {
+				topics: vec![HexaString::from_str("0x01").unwrap()],
+				data: vec![
+					HexaString::from_str("0x02").unwrap(),
+					HexaString::from_str("0x03").unwrap(),
+				],
+			},
+			metadata: EventMetadata {
+				block_number,
+				block_hash: BlockHash::from(block_number.to_be_bytes().to_vec()),
+				block_timestamp: 1660000000,
+				contract_address: ContractAddress::from_str("0x5678").unwrap(),
+				log_index: 6,
+			},
+			error,
+		}
+	}
+
+	#[tokio::test]
+	async fn returns_nothing_when_there_is_no_dead_letter() {
+		assert!(DeadLetterStore::list(&client().await, &"ID".into()).await.unwrap().is_empty());
+	}
+
+	#[tokio::test]
+	async fn saves_and_lists_dead_letters_per_indexer() {
+		let client = client().await;
+		let unsupported = dead_letter(1, DecodingError::Unsupported);
+		let invalid = dead_letter(2, DecodingError::Invalid(String::from("missing field")));
+
+		DeadLetterStore::save(&client, &"ID".into(), &unsupported).await.unwrap();
+		DeadLetterStore::save(&client, &"OTHER".into(), &unsupported).await.unwrap();
+		DeadLetterStore::save(&client, &"ID".into(), &invalid).await.unwrap();
+
+		assert_eq!(
+			vec![unsupported.clone(), invalid],
+			DeadLetterStore::list(&client, &"ID".into()).await.unwrap()
+		);
+		assert_eq!(
+			vec![unsupported],
+			DeadLetterStore::list(&client, &"OTHER".into()).await.unwrap()
+		);
+	}
+
+	#[tokio::test]
+	async fn keeps_an_event_delivered_again_once() {
+		let client = client().await;
+		let unsupported = dead_letter(1, DecodingError::Unsupported);
+		let invalid = dead_letter(1, DecodingError::Invalid(String::from("missing field")));
+
+		DeadLetterStore::save(&client, &"ID".into(), &unsupported).await.unwrap();
+		DeadLetterStore::save(&client, &"ID".into(), &invalid).await.unwrap();
+
+		assert_eq!(
+			vec![invalid],
+			DeadLetterStore::list(&client, &"ID".into()).await.unwrap()
+		);
+	}
+
+	#[tokio::test]
+	async fn removes_the_dead_letter_of_an_event() {
+		let client = client().await;
+		let first = dead_letter(1, DecodingError::Unsupported);
+		let second = dead_letter(2, DecodingError::Unsupported);
+
+		DeadLetterStore::save(&client, &"ID".into(), &first).await.unwrap();
+		DeadLetterStore::save(&client, &"OTHER".into(), &first).await.unwrap();
+		DeadLetterStore::save(&client, &"ID".into(), &second).await.unwrap();
+		DeadLetterStore::remove(&client, &"ID".into(), &first.metadata).await.unwrap();
+
+		assert_eq!(
+			vec![second],
+			DeadLetterStore::list(&client, &"ID".into()).await.unwrap()
+		);
+		assert_eq!(
+			vec![first],
+			DeadLetterStore::list(&client, &"OTHER".into()).await.unwrap()
+		);
+	}
+}
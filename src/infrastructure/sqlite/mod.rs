@@ -0,0 +1,26 @@
+mod checkpoint_store;
+mod dead_letter_store;
+
+use sqlx::{sqlite::SqlitePoolOptions, SqlitePool};
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum Error {
+	#[error("Unable to connect to SQLite database")]
+	Connection(#[from] sqlx::Error),
+}
+
+pub struct Client(SqlitePool);
+
+impl Client {
+	/**
+	 * Connect to the database, creating it and its tables if needed
+	 */
+	pub async fn connect(database_url: &str) -> Result<Self, Error> {
+		// A single connection, so that in-memory databases are shared
+		let pool = SqlitePoolOptions::new().max_connections(1).connect(database_url).await?;
+		checkpoint_store::create_table(&pool).await?;
+		dead_letter_store::create_table(&pool).await?;
+		Ok(Self(pool))
+	}
+}
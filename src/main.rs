@@ -1,7 +1,9 @@
+mod cli;
+
+use clap::Parser;
+use cli::Cli;
 use dotenv::dotenv;
-use marketplace_indexer::{application::IndexerBuilder, domain::*, infrastructure::ApibaraClient};
 use slog::{o, Drain, Logger};
-use std::sync::Arc;
 
 fn get_root_logger() -> Logger {
 	let drain = match std::env::var("LOGS") {
@@ -21,29 +23,15 @@ fn get_root_logger() -> Logger {
 #[tokio::main]
 async fn main() {
 	dotenv().ok();
-	let _global_logger_guard = slog_scope::set_global_logger(get_root_logger());
-	_global_logger_guard.cancel_reset();
-
-	let apibara_client =
-		Arc::new(ApibaraClient::default().await.expect("Unable to connect to Apibara server"));
+	let global_logger_guard = slog_scope::set_global_logger(get_root_logger());
 
-	let indexer = IndexerBuilder::new(apibara_client.clone())
-		.network(Network::Starknet(StarknetChain::Goerli))
-		.start_at_block(291345)
-		.on_conflict_recreate()
-		.filter(registry_contract_address(), "GithubIdentifierRegistered")
-		.filter(registry_contract_address(), "GithubIdentifierUnregistered")
-		.build("indexer-goerli".into())
-		.await
-		.expect("Unable to create the indexer");
+	let result = Cli::parse().execute().await;
 
-	apibara_client
-		.fetch_new_events(&indexer, Arc::new(BlockchainLogger::default()))
-		.await
-		.expect("Error while fetching events");
-}
+	// Resetting the global logger drops the asynchronous drain, which flushes the pending logs
+	drop(global_logger_guard);
 
-fn registry_contract_address() -> ContractAddress {
-	let address = std::env::var("REGISTRY_ADDRESS").expect("REGISTRY_ADDRESS must be set");
-	address.parse().expect("REGISTRY_ADDRESS is not a valid contract address")
+	if let Err(error) = result {
+		eprintln!("Error: {}", error);
+		std::process::exit(1);
+	}
 }
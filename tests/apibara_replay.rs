@@ -0,0 +1,38 @@
+use marketplace_indexer::{domain::*, infrastructure::ApibaraReplay};
+use std::sync::Arc;
+use tokio_util::sync::CancellationToken;
+
+// The fixture mimics a stream recorded with `marketplace-indexer run --record <DIR>`
+const RECORDING: &str = "tests/fixtures/indexer-goerli.jsonl";
+
+fn indexer() -> Indexer {
+	Indexer::new(
+		"indexer-goerli".into(),
+		Network::Starknet(StarknetChain::Goerli),
+		291345,
+		Vec::new(),
+	)
+}
+
+#[tokio::test]
+async fn recorded_events_are_decoded_and_observed() {
+	let observer = Arc::new(InMemoryBlockchainObserver::default());
+
+	ApibaraReplay::new(RECORDING)
+		.fetch_new_events(&indexer(), observer.clone(), CancellationToken::new())
+		.await
+		.unwrap();
+
+	let events = observer.events();
+	assert_eq!(1, events.len());
+
+	let (event, metadata) = &events[0];
+	assert!(
+		matches!(event, Event::GithubIdentifierRegistered(event) if event.identifier == 107289007),
+		"{:?}",
+		event
+	);
+	assert_eq!(291345, metadata.block_number);
+	assert_eq!(BlockHash::from(vec![1, 1]), metadata.block_hash);
+	assert_eq!(7, metadata.log_index);
+}